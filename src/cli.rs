@@ -0,0 +1,744 @@
+use chrono::{Local, TimeDelta};
+use todolist::internal::tasks::structs::{DateTimeOption, Priority, Task, TaskStatus};
+use todolist::{
+    edit_in_editor, load_config, load_events, load_tasks, log_events, open_storage,
+    parse_dependencies, parse_due, parse_since, record_undo, replay, ret_editor, ret_stored_name,
+    save_task, show_timesheet, task_events, to_csv, to_line, to_tsv, Grouping, Labels, Query,
+    Recurrence, SubtaskPolicy, Tasks, TodoError, TodoResult, UndoStack,
+};
+
+/// exit code returned when a command completes successfully. Failed commands
+/// exit with the code of their error (see `TodoError::exit_code`).
+pub const EXIT_SUCCESS: i32 = 0;
+
+/// the statuses listed by `list --status all`.
+const ALL_STATUSES: [TaskStatus; 3] = [
+    TaskStatus::Active,
+    TaskStatus::Completed,
+    TaskStatus::Deleted,
+];
+
+const USAGE: &str = "Usage: todolist [--data-dir <path>] [<command> [<args>]]
+
+Runs the interactive menu when no command is given.
+
+Commands:
+  add <name> [--due <when>] [--priority <priority>] [--parent <id>]
+      [--repeat <rule>]
+                                   add an active task and print its Task ID
+  list [<query>] [--status <status>] [--sort urgency] [--json]
+                                   list tasks that match the query with the
+                                   given status (active, completed, deleted
+                                   or all; default active, or all if the
+                                   query has a status: condition), most
+                                   urgent first with --sort urgency
+  today [<query>] [--json]         list active tasks due today
+  overdue [<query>] [--json]       list active tasks that are past their due date
+  upcoming [<query>] [--json]      list active tasks due in the next seven days
+  ready [<query>] [--json]         list active tasks whose prerequisites are
+                                   all completed
+  blocked [<query>] [--json]       list active tasks waiting on prerequisites
+  search <text> [--json]           list tasks of every status whose names match
+                                   the text, best match first, then the tasks
+                                   whose notes contain it
+  show <id> [--json]               print every detail of a task of any status,
+                                   including its notes
+  done <id> [--cascade|--detach]   mark an active task as complete
+  edit <id> <changes>              rename an active task and/or change its
+                                   tags and project
+  notes <id> [<text>|-|none]       edit the notes of an active task in $VISUAL
+                                   or $EDITOR, set them to the text, read them
+                                   from stdin with -, or remove them with 'none'
+  due <id> <when>                  set the due date of an active task, or
+                                   remove it with 'none'
+  priority <id> <priority>         set the priority of an active task (high,
+                                   medium, low or none)
+  parent <id> <parent>             make an active task a subtask of another
+                                   active task, or a top level task with 'none'
+  depends <id> <ids>               make an active task depend on the tasks
+                                   with the given ids, where -id removes one
+  repeat <id> <rule>               make an active task repeat, or stop it
+                                   repeating with 'none'
+  delete <id> [--cascade|--detach] [--unlink]
+                                   delete an active task
+  restore <id>                     move a deleted task back to the active tasks
+  reopen <id>                      move a completed task back to the active tasks
+  purge [--older-than <days>]      permanently remove the tasks deleted more
+                                   than the given days ago (default set by
+                                   purge_after_days in config.json, 30)
+  start <id>                       start timing the work on an active task,
+                                   stopping the timer that is running
+  stop                             stop the running timer
+  timesheet [--by <group>] [--since <when>] [--csv]
+                                   print the time spent on each task grouped
+                                   by day, week, project or tag (default
+                                   day), from the given day on, or as CSV
+  undo                             undo the most recent change to the tasks
+  redo                             redo the most recently undone change
+  history <id> [--json]            list every recorded change to a task, oldest
+                                   first, as tab separated time, who, command
+                                   and change
+  replay [--json]                  list the tasks of every status as rebuilt
+                                   from the recorded changes
+  tui                              open the full-screen interface, where j/k
+                                   move, space completes, d deletes, / searches
+                                   and ? lists every key
+  help                             print this message
+
+Names may contain +tag and project:name words e.g., 'write docs +work
+project:work.docs' which set the tags and project of the task. When editing,
+-tag removes a tag and an empty project: removes the project. A tag starts
+with a letter or symbol, so words such as '+1' or 'C++' stay in the name;
+start a word with \\ to keep it in the name as written e.g., '\\+work'.
+
+A query is made of conditions joined with and, or, not and parentheses, where
+conditions next to each other must all match e.g.,
+  'status:active and (tag:urgent or due.before:friday) and name~\"deploy\"'
+The conditions are status:s, name:text (equals), name~text (contains),
+tag:x or +x, -x (not tagged x), project:x (x or its sub projects),
+priority:p, priority.above:p, priority.below:p, id:n, parent:n and due:date,
+due.before:date, due.after:date with the same for created, modified and
+finished. tag, project, parent and the dates also take none; the dates also
+take any.
+Quote values with spaces e.g., due.before:\"next friday\".
+
+A task with open subtasks is only completed or deleted with --cascade, which
+completes or deletes its open subtasks too, or --detach, which keeps them as
+top level tasks. A task other tasks depend on, or whose subtasks deleted by
+--cascade other tasks depend on, is only deleted with --unlink, which removes
+the deleted tasks from their dependencies. Completing a task reports the tasks
+that became ready on stderr.
+
+Every change made by a command or in the interactive menu can be undone,
+also after the program restarted. The last 100 changes are kept, and
+making a new change forgets the undone changes. Every change is also
+appended to events.jsonl in the data dir together with when it was made,
+who made it and the command or menu option that made it.
+
+A repeating task gets its next instance, due on the next date of its rule,
+when it is completed. Rules are phrases such as 'daily', 'every 2 weeks',
+'weekly on mon fri', 'every weekday', 'monthly on 15', 'every 3 days after
+completion' (counted from the completion date) or RRULEs such as
+'FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR'. Changing or stopping the repetition of
+the active instance changes the rest of the series.
+
+Only one timer runs at a time and a running timer keeps running after the
+program exits. Completing or deleting a task stops its timer. The time spent
+on a task is shown by show and kept in the --json output.
+
+Notes are free-form text of any number of lines. They are printed by show
+and kept in the --json output.
+
+Due dates are absolute dates such as '2024/12/31 17:00' or phrases such as
+'tomorrow 5pm', 'next friday' or 'in 3 days'. The today, overdue and upcoming
+commands sort tasks by due date.
+
+Tasks are printed one per line as tab separated columns:
+  id, status, created, last modified, finished, due, priority, project,
+  tags, parent, depends on, repeats, name
+or as JSON Lines with --json.
+
+Exit codes: 0 success, 1 storage failure, 2 usage error, 3 task not found,
+            4 unparsable stored data, 5 corrupted stored data";
+
+/// the line printed after an invalid argument instead of the whole `USAGE`.
+const USAGE_HINT: &str = "See 'todolist help' for the usage of each command.";
+
+/// # parse_id
+/// parses a Task ID passed on the command line.
+/// # Returns
+/// `Ok(task_id)` else `TodoError::Usage` if the Task ID is missing or not
+/// a number.
+fn parse_id(arg: Option<&String>) -> TodoResult<i32> {
+    let arg = arg.ok_or_else(|| TodoError::usage("missing Task ID"))?;
+    arg.parse::<i32>()
+        .map_err(|_| TodoError::usage(format!("invalid Task ID '{arg}'")))
+}
+
+/// # print_task
+/// prints `task` to stdout as a tab separated line or as JSON.
+fn print_task(task: &Task, json: bool) -> TodoResult<()> {
+    if json {
+        println!("{}", to_line(task)?);
+    } else {
+        println!("{}", to_tsv(task));
+    }
+    Ok(())
+}
+
+/// # parse_when
+/// parses the due date passed on the command line, where 'none' removes
+/// the due date.
+fn parse_when(when: &str) -> TodoResult<DateTimeOption> {
+    if when.eq_ignore_ascii_case("none") {
+        return Ok(DateTimeOption::None);
+    }
+    Ok(DateTimeOption::DateTime(parse_due(when, Local::now())?))
+}
+
+/// # parse_priority
+/// parses a priority passed on the command line.
+fn parse_priority(priority: &str) -> TodoResult<Priority> {
+    Priority::parse(priority).ok_or_else(|| {
+        TodoError::invalid(format!(
+            "unknown priority '{priority}'. Expected high, medium, low or none"
+        ))
+    })
+}
+
+/// # list_view
+/// prints the tasks returned by one of the views i.e., `today`, `overdue`,
+/// `upcoming`, `ready` or `blocked` that match the query in `args`.
+fn list_view(view_tasks: &[Task], args: &[String]) -> TodoResult<()> {
+    let mut json = false;
+    let mut query_words = Vec::<&str>::new();
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            _ => query_words.push(arg),
+        }
+    }
+    let query = Query::parse(&query_words.join(" "), Local::now())?;
+    for task in view_tasks.iter().filter(|task| query.matches(task)) {
+        print_task(task, json)?;
+    }
+    Ok(())
+}
+
+/// # search
+/// prints the tasks whose names match the text in `args`, best match first,
+/// followed by the tasks whose notes match it, whose ids are also reported
+/// on stderr (see `Tasks::search`).
+fn search(tasks: &Tasks, args: &[String]) -> TodoResult<()> {
+    let mut json = false;
+    let mut pattern_words = Vec::<&str>::new();
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            _ => pattern_words.push(arg),
+        }
+    }
+    if pattern_words.is_empty() {
+        return Err(TodoError::usage("missing search text"));
+    }
+    let hits = tasks.search(&pattern_words.join(" "));
+    for hit in &hits {
+        print_task(&hit.task, json)?;
+    }
+    let notes_ids = hits
+        .iter()
+        .filter(|hit| hit.notes_line.is_some())
+        .map(|hit| hit.task.id.to_string())
+        .collect::<Vec<String>>();
+    if !notes_ids.is_empty() {
+        eprintln!(
+            "todolist: tasks {} matched in their notes",
+            notes_ids.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// # history
+/// prints every recorded change to the task whose id is in `args`, oldest
+/// first, as tab separated time, actor, action and change, or as JSON Lines
+/// with `--json` (see `log_events`).
+fn history(args: &[String]) -> TodoResult<()> {
+    let task_id = parse_id(args.first())?;
+    let json = match args.get(1..) {
+        Some([]) | None => false,
+        Some([flag]) if flag == "--json" => true,
+        _ => return Err(TodoError::usage("expected a Task ID and optionally --json")),
+    };
+    for event in task_events(task_id)? {
+        if json {
+            let line = serde_json::to_string(&event)
+                .map_err(|e| TodoError::Storage(format!("Could not serialize event: {e}")))?;
+            println!("{line}");
+        } else {
+            println!(
+                "{}\t{}\t{}\t{}",
+                event.time.to_rfc3339(),
+                event.actor,
+                event.action,
+                event.show()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// # timesheet
+/// prints the time spent on each task grouped by `--by` (day by default)
+/// from the day given by `--since` on, or as CSV with `--csv` (see
+/// `Tasks::timesheet`).
+fn timesheet(tasks: &Tasks, args: &[String]) -> TodoResult<()> {
+    let now = Local::now();
+    let mut grouping = Grouping::Day;
+    let mut since = None;
+    let mut csv = false;
+    let mut args_iter = args.iter();
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "--csv" => csv = true,
+            "--by" | "--since" => {
+                let value = args_iter
+                    .next()
+                    .ok_or_else(|| TodoError::usage(format!("missing value for {arg}")))?;
+                if arg == "--by" {
+                    grouping = Grouping::parse(value).ok_or_else(|| {
+                        TodoError::invalid(format!(
+                            "unknown grouping '{value}'. Expected day, week, project or tag"
+                        ))
+                    })?;
+                } else {
+                    since = Some(parse_since(value, now)?);
+                }
+            }
+            _ => return Err(TodoError::usage(format!("unexpected argument '{arg}'"))),
+        }
+    }
+    let entries = tasks.timesheet(grouping, since, now);
+    if csv {
+        print!("{}", to_csv(&entries));
+    } else {
+        for line in show_timesheet(&entries) {
+            println!("{line}");
+        }
+    }
+    Ok(())
+}
+
+/// # list
+/// prints every task with the status selected by `--status` that matches
+/// the query in `args`, in stored order or most urgent first with
+/// `--sort urgency`. Without `--status` the active tasks are listed, or the
+/// tasks of every status if the query has a condition on the status.
+fn list(tasks: &Tasks, args: &[String]) -> TodoResult<()> {
+    let mut statuses = None;
+    let mut json = false;
+    let mut by_urgency = false;
+    let mut query_words = Vec::<&str>::new();
+    let mut args_iter = args.iter();
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--sort" => {
+                let order = args_iter
+                    .next()
+                    .ok_or_else(|| TodoError::usage("missing value for --sort"))?;
+                if order != "urgency" {
+                    return Err(TodoError::invalid(format!("unknown sort order '{order}'")));
+                }
+                by_urgency = true;
+            }
+            "--status" => {
+                let status = args_iter
+                    .next()
+                    .ok_or_else(|| TodoError::usage("missing value for --status"))?;
+                statuses = Some(if status == "all" {
+                    ALL_STATUSES.to_vec()
+                } else {
+                    let status = TaskStatus::parse(status)
+                        .ok_or_else(|| TodoError::invalid(format!("unknown status '{status}'")))?;
+                    vec![status]
+                });
+            }
+            _ => query_words.push(arg),
+        }
+    }
+    let query = Query::parse(&query_words.join(" "), Local::now())?;
+    let statuses = match statuses {
+        Some(statuses) => statuses,
+        None if query.mentions_status() => ALL_STATUSES.to_vec(),
+        None => vec![TaskStatus::Active],
+    };
+    let coefficients = load_config().urgency;
+    for status in statuses {
+        let listed = if by_urgency {
+            tasks.by_urgency(status, &coefficients, Local::now())
+        } else {
+            tasks.list(status).clone()
+        };
+        for task in listed.iter().filter(|task| query.matches(task)) {
+            print_task(task, json)?;
+        }
+    }
+    Ok(())
+}
+
+/// # execute
+/// loads the stored tasks and runs `command` with the arguments `rest`,
+/// saving the tasks the command changed.
+/// # Returns
+/// `Ok(())` if the command completes successfully else a `TodoError`.
+fn execute(command: &str, rest: &[String]) -> TodoResult<()> {
+    // read the tasks from the configured storage backend
+    let config = load_config();
+    let mut storage = open_storage(config.storage)?;
+    let mut tasks = load_tasks(storage.as_mut())?;
+    let before = tasks.clone();
+    let mut undo_stack = None;
+
+    let task_ids = match command {
+        "add" => {
+            let mut name_words = Vec::<&str>::new();
+            let mut due = DateTimeOption::None;
+            let mut priority = Priority::None;
+            let mut parent = None;
+            let mut recurrence = None;
+            let mut args_iter = rest.iter();
+            while let Some(arg) = args_iter.next() {
+                match arg.as_str() {
+                    "--due" | "--priority" | "--parent" | "--repeat" => {
+                        let value = args_iter
+                            .next()
+                            .ok_or_else(|| TodoError::usage(format!("missing value for {arg}")))?;
+                        match arg.as_str() {
+                            "--due" => due = parse_when(value)?,
+                            "--priority" => priority = parse_priority(value)?,
+                            "--repeat" => recurrence = Some(Recurrence::parse(value)?),
+                            _ => parent = Some(parse_id(Some(value))?),
+                        }
+                    }
+                    _ => name_words.push(arg),
+                }
+            }
+            let labels = Labels::parse(&name_words.join(" "))?;
+            let name = labels
+                .name
+                .clone()
+                .ok_or_else(|| TodoError::usage("missing task name"))?;
+            let new_task_id = tasks.add(name);
+            tasks.set_due(new_task_id, due)?;
+            tasks.set_priority(new_task_id, priority)?;
+            tasks.relabel(new_task_id, &labels)?;
+            tasks.set_parent(new_task_id, parent)?;
+            tasks.set_recurrence(new_task_id, recurrence)?;
+            println!("{new_task_id}");
+            vec![new_task_id]
+        }
+        "list" => return list(&tasks, rest),
+        "today" => return list_view(&tasks.due_today(Local::now()), rest),
+        "overdue" => return list_view(&tasks.overdue(Local::now()), rest),
+        "upcoming" => return list_view(&tasks.upcoming(Local::now()), rest),
+        "ready" => return list_view(&tasks.ready(), rest),
+        "blocked" => return list_view(&tasks.blocked(), rest),
+        "search" => return search(&tasks, rest),
+        "history" => return history(rest),
+        "timesheet" => return timesheet(&tasks, rest),
+        "show" => {
+            let task_id = parse_id(rest.first())?;
+            let json = match rest.get(1..) {
+                Some([]) | None => false,
+                Some([flag]) if flag == "--json" => true,
+                _ => return Err(TodoError::usage("expected a Task ID and optionally --json")),
+            };
+            let task = tasks.find(task_id).ok_or(TodoError::NotFound(task_id))?;
+            if json {
+                println!("{}", to_line(task)?);
+            } else {
+                println!("{}", task.show_details());
+            }
+            return Ok(());
+        }
+        "replay" => {
+            let json = match rest {
+                [] => false,
+                [flag] if flag == "--json" => true,
+                _ => return Err(TodoError::usage("expected no arguments or --json")),
+            };
+            for task in replay(&load_events()?)?.iter() {
+                print_task(task, json)?;
+            }
+            return Ok(());
+        }
+        "done" | "delete" => {
+            let task_id = parse_id(rest.first())?;
+            let mut policy = SubtaskPolicy::Block;
+            let mut unlink = false;
+            for arg in rest.iter().skip(1) {
+                match arg.as_str() {
+                    "--cascade" => policy = SubtaskPolicy::Cascade,
+                    "--detach" => policy = SubtaskPolicy::Detach,
+                    "--unlink" if command == "delete" => unlink = true,
+                    _ => return Err(TodoError::usage(format!("unexpected argument '{arg}'"))),
+                }
+            }
+            let status = if command == "done" {
+                TaskStatus::Completed
+            } else {
+                TaskStatus::Deleted
+            };
+            let deleted_ids = tasks.deleted_with(task_id, policy);
+            let dependents = tasks.dependents_of(&deleted_ids);
+            if status == TaskStatus::Deleted && !dependents.is_empty() && !unlink {
+                let dependent_ids = dependents
+                    .iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<String>>();
+                return Err(TodoError::invalid(format!(
+                    "tasks {} depend on task {task_id} or the subtasks deleted with it. Use \
+                     --unlink to remove them from their dependencies",
+                    dependent_ids.join(", ")
+                )));
+            }
+            let mut changed_ids = tasks.resolve_subtasks(task_id, status, policy)?;
+            let changed = if command == "done" {
+                tasks.complete(task_id)?
+            } else {
+                tasks.delete(task_id)?
+            };
+            print_task(changed, false)?;
+            changed_ids.insert(0, task_id);
+            if unlink {
+                changed_ids.extend(tasks.unlink_dependents(&deleted_ids));
+            }
+            // report the tasks that were waiting on the completed tasks and
+            // add the next instances of the completed recurring tasks
+            if status == TaskStatus::Completed {
+                for changed_id in changed_ids.clone() {
+                    for unblocked_id in tasks.unblocked_by(changed_id) {
+                        eprintln!("todolist: task {unblocked_id} is now ready to work on");
+                    }
+                    // detached subtasks and unlinked dependents stay active
+                    let is_completed = tasks
+                        .find(changed_id)
+                        .is_some_and(|task| task.status == TaskStatus::Completed);
+                    if !is_completed {
+                        continue;
+                    }
+                    if let Some(next_task_id) = tasks.recur(changed_id)? {
+                        changed_ids.push(next_task_id);
+                    }
+                }
+            }
+            for changed_id in &changed_ids[1..] {
+                print_task(
+                    tasks
+                        .find(*changed_id)
+                        .ok_or(TodoError::NotFound(*changed_id))?,
+                    false,
+                )?;
+            }
+            changed_ids
+        }
+        "edit" => {
+            let task_id = parse_id(rest.first())?;
+            if rest.len() < 2 {
+                return Err(TodoError::usage("missing new task name"));
+            }
+            let labels = Labels::parse_changes(&rest[1..].join(" "))?;
+            print_task(tasks.relabel(task_id, &labels)?, false)?;
+            vec![task_id]
+        }
+        "notes" => {
+            let task_id = parse_id(rest.first())?;
+            let notes = match rest.get(1..) {
+                Some([]) | None => {
+                    let editor = ret_editor().ok_or_else(|| {
+                        TodoError::invalid("set $VISUAL or $EDITOR or pass the notes as text")
+                    })?;
+                    // check the task before the editor is opened
+                    let target_task = tasks
+                        .active
+                        .iter()
+                        .find(|task| task.id == task_id)
+                        .ok_or(TodoError::NotFound(task_id))?;
+                    let current_notes = target_task.notes.clone().unwrap_or_default();
+                    Some(edit_in_editor(&editor, &current_notes)?)
+                }
+                Some([text]) if text.eq_ignore_ascii_case("none") => None,
+                Some([text]) if text == "-" => {
+                    let stdin_path = std::path::Path::new("stdin");
+                    Some(
+                        std::io::read_to_string(std::io::stdin())
+                            .map_err(|e| TodoError::io(stdin_path, e))?,
+                    )
+                }
+                Some(text) => Some(text.join(" ")),
+            };
+            print_task(tasks.set_notes(task_id, notes.as_deref())?, false)?;
+            vec![task_id]
+        }
+        "due" => {
+            let task_id = parse_id(rest.first())?;
+            if rest.len() < 2 {
+                return Err(TodoError::usage("missing due date"));
+            }
+            let due = parse_when(&rest[1..].join(" "))?;
+            print_task(tasks.set_due(task_id, due)?, false)?;
+            vec![task_id]
+        }
+        "priority" => {
+            let task_id = parse_id(rest.first())?;
+            let priority = match rest.get(1..) {
+                Some([priority]) => parse_priority(priority)?,
+                _ => return Err(TodoError::usage("expected a Task ID and a priority")),
+            };
+            print_task(tasks.set_priority(task_id, priority)?, false)?;
+            vec![task_id]
+        }
+        "parent" => {
+            let task_id = parse_id(rest.first())?;
+            let parent = match rest.get(1..) {
+                Some([parent]) if parent.eq_ignore_ascii_case("none") => None,
+                Some([parent]) => Some(parse_id(Some(parent))?),
+                _ => return Err(TodoError::usage("expected a Task ID and a parent Task ID")),
+            };
+            print_task(tasks.set_parent(task_id, parent)?, false)?;
+            vec![task_id]
+        }
+        "depends" => {
+            let task_id = parse_id(rest.first())?;
+            let (added, removed) =
+                parse_dependencies(&rest.get(1..).unwrap_or_default().join(" "))?;
+            if added.is_empty() && removed.is_empty() {
+                return Err(TodoError::usage("missing Task IDs to depend on"));
+            }
+            print_task(tasks.change_dependencies(task_id, &added, &removed)?, false)?;
+            vec![task_id]
+        }
+        "repeat" => {
+            let task_id = parse_id(rest.first())?;
+            let recurrence = match rest.get(1..) {
+                Some([rule]) if rule.eq_ignore_ascii_case("none") => None,
+                Some([]) | None => return Err(TodoError::usage("missing repeat rule")),
+                Some(rule) => Some(Recurrence::parse(&rule.join(" "))?),
+            };
+            print_task(tasks.set_recurrence(task_id, recurrence)?, false)?;
+            vec![task_id]
+        }
+        "restore" | "reopen" => {
+            let task_id = parse_id(rest.first())?;
+            if let Some(arg) = rest.get(1) {
+                return Err(TodoError::usage(format!("unexpected argument '{arg}'")));
+            }
+            let changed = if command == "restore" {
+                tasks.restore(task_id)?
+            } else {
+                tasks.reopen(task_id)?
+            };
+            print_task(changed, false)?;
+            vec![task_id]
+        }
+        "purge" => {
+            let purge_after_days = match rest {
+                [] => config.purge_after_days,
+                [flag, days] if flag == "--older-than" => days
+                    .parse::<u32>()
+                    .map_err(|_| TodoError::invalid(format!("'{days}' is not a number of days")))?,
+                _ => {
+                    return Err(TodoError::usage(
+                        "expected no arguments or --older-than <days>",
+                    ))
+                }
+            };
+            let age = TimeDelta::days(i64::from(purge_after_days));
+            let deleted_count = tasks.deleted.len();
+            let changed_ids = tasks.purge(age, Local::now());
+            let purged_count = deleted_count - tasks.deleted.len();
+            eprintln!("todolist: {purged_count} deleted tasks have been purged");
+            changed_ids
+        }
+        "start" => {
+            let task_id = parse_id(rest.first())?;
+            if let Some(arg) = rest.get(1) {
+                return Err(TodoError::usage(format!("unexpected argument '{arg}'")));
+            }
+            let mut changed_ids = vec![task_id];
+            if let Some(stopped_id) = tasks.start_timer(task_id, Local::now())? {
+                eprintln!("todolist: stopped the timer of task {stopped_id}");
+                changed_ids.push(stopped_id);
+            }
+            print_task(
+                tasks.find(task_id).ok_or(TodoError::NotFound(task_id))?,
+                false,
+            )?;
+            changed_ids
+        }
+        "stop" => {
+            if let Some(arg) = rest.first() {
+                return Err(TodoError::usage(format!("unexpected argument '{arg}'")));
+            }
+            let stopped = tasks.stop_timer(Local::now())?;
+            print_task(stopped, false)?;
+            vec![stopped.id]
+        }
+        "undo" | "redo" => {
+            if let Some(arg) = rest.first() {
+                return Err(TodoError::usage(format!("unexpected argument '{arg}'")));
+            }
+            let mut loaded = UndoStack::load()?;
+            let (action, past_tense) = if command == "undo" {
+                (loaded.undo(&mut tasks)?, "undone")
+            } else {
+                (loaded.redo(&mut tasks)?, "redone")
+            };
+            eprintln!("todolist: {} has been {past_tense}", action.show());
+            undo_stack = Some(loaded);
+            action.changes.iter().map(|change| change.task_id).collect()
+        }
+        _ => return Err(TodoError::usage(format!("unknown command '{command}'"))),
+    };
+    // save the tasks the command changed. Undo and redo save every task so
+    // that the restored tasks keep their place in the stored order.
+    if undo_stack.is_some() {
+        storage.save_all(&tasks)?;
+    } else {
+        for task_id in &task_ids {
+            save_task(storage.as_mut(), &tasks, *task_id)?;
+        }
+    }
+    // log the change in the audit trail, including undos and redos
+    let description = std::iter::once(command)
+        .chain(rest.iter().map(String::as_str))
+        .collect::<Vec<&str>>()
+        .join(" ");
+    let actor = ret_stored_name()
+        .or_else(|| std::env::var("USER").ok())
+        .or_else(|| std::env::var("USERNAME").ok())
+        .unwrap_or_else(|| "unknown".to_string());
+    log_events(&actor, &description, &before, &tasks, &task_ids)?;
+    // record the change so that it can be undone, or the undo or redo itself
+    match undo_stack {
+        Some(undo_stack) => undo_stack.save(),
+        None => record_undo(&description, &before, &tasks, &task_ids),
+    }
+}
+
+/// # run
+/// runs a single non-interactive command given on the command line, e.g.
+/// `todolist add "text"` or `todolist done 12`. The command reuses the same
+/// operations as the interactive menu and saves any change straight away.
+/// Errors are printed to stderr, followed by the usage of the program if
+/// the command line was not understood or by a hint pointing to it if an
+/// argument was not valid.
+/// # Arguments
+/// - **args**: the arguments passed to the program without the program name
+///   and without the `--data-dir` flag. Must not be empty.
+/// # Returns
+/// the exit code of the program i.e., `EXIT_SUCCESS` or the exit code of
+/// the error the command failed with (see `TodoError::exit_code`).
+pub fn run(args: &[String]) -> i32 {
+    let command = args[0].as_str();
+    if matches!(command, "help" | "--help" | "-h") {
+        println!("{USAGE}");
+        return EXIT_SUCCESS;
+    }
+    match execute(command, &args[1..]) {
+        Ok(_) => EXIT_SUCCESS,
+        Err(e @ TodoError::Usage(_)) => {
+            eprintln!("todolist: {e}\n\n{USAGE}");
+            e.exit_code()
+        }
+        Err(e @ TodoError::InvalidInput(_)) => {
+            eprintln!("todolist: {e}\n{USAGE_HINT}");
+            e.exit_code()
+        }
+        Err(e) => {
+            eprintln!("todolist: {e}");
+            e.exit_code()
+        }
+    }
+}
@@ -1,4 +1,9 @@
+pub mod config;
+pub mod error;
+pub mod inputs;
 pub mod name;
 pub mod paths;
-pub mod inputs;
-pub mod tasks;
\ No newline at end of file
+pub mod storage;
+pub mod tasks;
+#[cfg(feature = "tui")]
+pub mod tui;
@@ -0,0 +1,216 @@
+use crate::internal::paths::get_file_path;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// the file holding the configuration of the program.
+pub const CONFIG_FILE: &str = "config.json";
+
+/// enum `StorageKind`
+/// The storage backends that can be selected in 'config.json'
+///
+/// # Variants
+/// - **File**: tasks are stored in 'tasks.jsonl' (the default)
+/// - **Memory**: tasks are kept in memory and lost on exit
+/// - **Sqlite**: tasks are stored in the SQLite database 'tasks.db'
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageKind {
+    #[default]
+    File,
+    Memory,
+    Sqlite,
+}
+
+/// # Urgency
+/// the coefficients of the urgency score of a task (see `urgency::urgency`).
+/// Each term of the score is multiplied by its coefficient, so a coefficient
+/// of 0 leaves the term out.
+///
+/// # Members
+/// - **high**: the score of a task with `Priority::High`
+/// - **medium**: the score of a task with `Priority::Medium`
+/// - **low**: the score of a task with `Priority::Low`
+/// - **age**: the score of a task that is `age_max_days` or more days old.
+///   Younger tasks score proportionally less.
+/// - **age_max_days**: the age in days after which the age term stops growing
+/// - **due**: the score of a task that is a week or more overdue. Tasks due
+///   later score less, down to a fifth of it for tasks due in two weeks or
+///   more. Tasks without a due date score 0.
+/// - **tags**: the score of a task with three or more tags. A task with one
+///   tag scores 0.8 and with two tags 0.9 times it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Urgency {
+    pub high: f64,
+    pub medium: f64,
+    pub low: f64,
+    pub age: f64,
+    pub age_max_days: f64,
+    pub due: f64,
+    pub tags: f64,
+}
+
+impl Default for Urgency {
+    fn default() -> Self {
+        Urgency {
+            high: 6.0,
+            medium: 3.9,
+            low: 1.8,
+            age: 2.0,
+            age_max_days: 365.0,
+            due: 12.0,
+            tags: 1.0,
+        }
+    }
+}
+
+/// enum `Column`
+/// The columns that can be selected for the task tables of the interactive
+/// menu (see `table::show_table`).
+///
+/// # Variants
+/// - **Id**: the Task ID
+/// - **Name**: the name of the task, wrapped or cut to fit the terminal
+/// - **Status**: the status of the task
+/// - **Created**: when the task was created
+/// - **Modified**: when the task was last modified
+/// - **Finished**: when the task was completed or deleted
+/// - **Due**: the due date of the task
+/// - **Priority**: the priority of the task
+/// - **Tags**: the tags of the task as '+tag' words
+/// - **Project**: the project of the task
+/// - **Parent**: the id of the parent of the task
+/// - **Depends**: the ids of the tasks the task depends on
+/// - **Repeats**: the recurrence rule of the task
+/// - **Time**: the time spent on the task
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Column {
+    Id,
+    Name,
+    Status,
+    Created,
+    Modified,
+    Finished,
+    Due,
+    Priority,
+    Tags,
+    Project,
+    Parent,
+    Depends,
+    Repeats,
+    Time,
+}
+
+/// enum `ColorMode`
+/// when the task tables of the interactive menu are coloured.
+///
+/// # Variants
+/// - **Auto**: only when stdout is a terminal and `NO_COLOR` is not set (the default)
+/// - **Always**: also when stdout is piped or `NO_COLOR` is set
+/// - **Never**: the tables are never coloured
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// # Table
+/// the settings of the task tables of the interactive menu.
+///
+/// # Members
+/// - **columns**: the columns of the tables, in order. A column that is empty
+///   for every task of a table is left out of it.
+/// - **color**: when the tables are coloured
+/// - **wrap**: whether names too long for the terminal are wrapped onto more
+///   lines, else they are cut
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Table {
+    pub columns: Vec<Column>,
+    pub color: ColorMode,
+    pub wrap: bool,
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Table {
+            columns: vec![
+                Column::Id,
+                Column::Name,
+                Column::Status,
+                Column::Created,
+                Column::Modified,
+                Column::Finished,
+                Column::Due,
+                Column::Priority,
+                Column::Tags,
+                Column::Project,
+                Column::Time,
+            ],
+            color: ColorMode::default(),
+            wrap: true,
+        }
+    }
+}
+
+/// # Config
+/// a struct that models the contents of 'config.json' in the data dir.
+/// Every member is optional in the file and falls back to its default.
+///
+/// # Members
+/// - **storage**: the storage backend used to persist tasks
+/// - **urgency**: the coefficients used to order tasks by urgency
+/// - **purge_after_days**: the days a task stays deleted before it is purged
+///   (see `Tasks::purge`)
+/// - **table**: the settings of the task tables of the interactive menu
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub storage: StorageKind,
+    pub urgency: Urgency,
+    pub purge_after_days: u32,
+    pub table: Table,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            storage: StorageKind::default(),
+            urgency: Urgency::default(),
+            purge_after_days: 30,
+            table: Table::default(),
+        }
+    }
+}
+
+/// # load_config
+/// reads the configuration of the program from 'config.json' in the data dir.
+/// # Arguments
+/// none
+/// # Returns
+/// the parsed `Config`. The default `Config` is returned if the file does not
+/// exist, and also if it cannot be read or parsed in which case a warning is
+/// printed.
+pub fn load_config() -> Config {
+    let config_path = get_file_path(CONFIG_FILE);
+    if !config_path.exists() {
+        return Config::default();
+    }
+    let parsed = fs::read_to_string(&config_path)
+        .ok()
+        .and_then(|config_str| serde_json::from_str::<Config>(&config_str).ok());
+    match parsed {
+        Some(config) => config,
+        None => {
+            eprintln!(
+                "WARNING: Could not read {}. Using the default configuration",
+                config_path.display()
+            );
+            Config::default()
+        }
+    }
+}
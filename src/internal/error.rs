@@ -0,0 +1,162 @@
+use crate::internal::tasks::structs::TaskId;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// the result type returned by every fallible operation in the program.
+pub type TodoResult<T> = Result<T, TodoError>;
+
+/// enum `TodoError`
+/// The errors that operations in the program can fail with.
+///
+/// # Variants
+/// - **Io**: reading or writing `path` failed
+/// - **Parse**: a stored record could not be parsed. `source` is the file
+///   the record was read from, `line` its line number (counting from 1) and
+///   `field` the field that could not be parsed if it is known.
+/// - **NotFound**: no task has the given Task ID
+/// - **InvalidInput**: the user entered something that is not valid
+/// - **Usage**: the command line is not understood e.g., an unknown command
+///   or a missing or unexpected argument
+/// - **Query**: a query could not be parsed. `position` is the offset in
+///   characters of the offending token in `query`.
+/// - **InputClosed**: there is no more input to read e.g., stdin was closed
+/// - **Corrupt**: the stored data is damaged or was written by a newer
+///   version of the program
+/// - **Storage**: the storage backend failed e.g., a database error
+#[derive(Debug)]
+pub enum TodoError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        source: String,
+        line: usize,
+        field: Option<String>,
+        message: String,
+    },
+    NotFound(TaskId),
+    InvalidInput(String),
+    Usage(String),
+    Query {
+        query: String,
+        position: usize,
+        message: String,
+    },
+    InputClosed,
+    Corrupt(String),
+    Storage(String),
+}
+
+impl TodoError {
+    /// # io
+    /// creates a `TodoError::Io` for a failed operation on `path`.
+    pub fn io(path: &Path, source: io::Error) -> TodoError {
+        TodoError::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    /// # invalid
+    /// creates a `TodoError::InvalidInput` with `message`.
+    pub fn invalid(message: impl Into<String>) -> TodoError {
+        TodoError::InvalidInput(message.into())
+    }
+
+    /// # usage
+    /// creates a `TodoError::Usage` with `message`.
+    pub fn usage(message: impl Into<String>) -> TodoError {
+        TodoError::Usage(message.into())
+    }
+
+    /// # at
+    /// sets the `source` and `line` of a `TodoError::Parse` i.e., records
+    /// where the record that could not be parsed was read from. Other
+    /// errors are returned unchanged.
+    pub fn at(self, source: &str, line: usize) -> TodoError {
+        match self {
+            TodoError::Parse { field, message, .. } => TodoError::Parse {
+                source: source.to_string(),
+                line,
+                field,
+                message,
+            },
+            other => other,
+        }
+    }
+
+    /// # exit_code
+    /// returns the exit code the program exits with when it stops
+    /// because of this error.
+    ///
+    /// - 1 => `Io` and `Storage` i.e., storage failures
+    /// - 2 => `InvalidInput`, `Usage`, `Query` and `InputClosed` i.e., usage
+    ///   errors
+    /// - 3 => `NotFound`
+    /// - 4 => `Parse`
+    /// - 5 => `Corrupt`
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            TodoError::Io { .. } | TodoError::Storage(_) => 1,
+            TodoError::InvalidInput(_)
+            | TodoError::Usage(_)
+            | TodoError::Query { .. }
+            | TodoError::InputClosed => 2,
+            TodoError::NotFound(_) => 3,
+            TodoError::Parse { .. } => 4,
+            TodoError::Corrupt(_) => 5,
+        }
+    }
+}
+
+impl fmt::Display for TodoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TodoError::Io { path, source } => {
+                write!(f, "Could not access {}. Error: {source}", path.display())
+            }
+            TodoError::Parse {
+                source,
+                line,
+                field,
+                message,
+            } => match field {
+                Some(field) => write!(
+                    f,
+                    "Could not parse field '{field}' on line {line} of {source}: {message}"
+                ),
+                None => write!(f, "Could not parse line {line} of {source}: {message}"),
+            },
+            TodoError::NotFound(task_id) => write!(f, "No task found with Task ID = {task_id}"),
+            TodoError::InvalidInput(message) => write!(f, "Invalid input entered. {message}"),
+            TodoError::Usage(message) => write!(f, "Invalid command line. {message}"),
+            TodoError::Query {
+                query,
+                position,
+                message,
+            } => {
+                // point at the offending token under the query
+                write!(
+                    f,
+                    "Invalid query: {message}\n  {query}\n  {}^",
+                    " ".repeat(*position)
+                )
+            }
+            TodoError::InputClosed => write!(f, "There is no more input to read"),
+            TodoError::Corrupt(message) => write!(f, "The stored data is corrupted. {message}"),
+            TodoError::Storage(message) => write!(f, "The storage backend failed. {message}"),
+        }
+    }
+}
+
+impl Error for TodoError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TodoError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
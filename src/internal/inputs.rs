@@ -1,2 +1,3 @@
+pub mod console;
 pub mod input_fns;
-pub mod prelude;
\ No newline at end of file
+pub mod prelude;
@@ -0,0 +1,212 @@
+use crate::internal::config::Table;
+use crate::internal::error::{TodoError, TodoResult};
+use std::collections::VecDeque;
+use std::io::{stdin, stdout, Write};
+use std::path::Path;
+
+/// # Console
+/// a trait for the source of user input and the destination of user facing
+/// output of the interactive operations. This keeps operations such as `add`,
+/// `check`, `edit` and `delete` independent of stdin and stdout so that they
+/// can be driven by a terminal, a GUI or a script in tests.
+///
+/// # Methods
+/// - **input_str**: prompts the user and returns the trimmed input
+/// - **println**: prints a line of output to the user
+/// - **is_closed**: checks if the input source has run out of input
+/// - **table**: the settings of the task tables printed to the console
+/// - **input_num**: prompts the user for a number
+/// - **input_option**: prompts the user to pick one of a set of options
+pub trait Console {
+    /// # input_str
+    /// prompts the user with `prompt` and returns the user's trimmed input.
+    /// # Returns
+    /// User input as `Ok(String)` else `Err(TodoError::InputClosed)` if there
+    /// is no more input or another `TodoError` if no input could be read.
+    fn input_str(&mut self, prompt: &str) -> TodoResult<String>;
+
+    /// # println
+    /// prints `text` followed by a newline to the user.
+    fn println(&mut self, text: &str);
+
+    /// # is_closed
+    /// returns `true` once the input source has run out of input e.g., when
+    /// stdin reached end of file. Interactive loops stop when this happens.
+    fn is_closed(&self) -> bool {
+        false
+    }
+
+    /// # table
+    /// returns the settings of the task tables printed to the console (see
+    /// `table::print_table`), the default settings unless the console was
+    /// given others.
+    fn table(&self) -> Table {
+        Table::default()
+    }
+
+    /// # input_num
+    /// prompts the user with `prompt` and parses the user's input to an integer.
+    /// # Returns
+    /// The user's input as a number of value `Ok(i32)` else if reading the
+    /// user's input fails, its error is returned, and if parsing the input to
+    /// a number fails, `TodoError::InvalidInput` is returned.
+    fn input_num(&mut self, prompt: &str) -> TodoResult<i32> {
+        // read user input as string or `String`
+        let user_input = self.input_str(prompt)?;
+
+        // parse user input to i32
+        user_input
+            .parse::<i32>()
+            .map_err(|_| TodoError::invalid(format!("Could not parse '{user_input}' to a number")))
+    }
+
+    /// # input_option
+    /// prints `options` under the heading `title` with option numbers starting
+    /// from 1 and prompts the user with `prompt` to enter the number of an option.
+    /// # Returns
+    /// the selected option as `Ok(&str)` if the user entered a valid option
+    /// number else a `TodoError`.
+    fn input_option<'a>(
+        &mut self,
+        prompt: &str,
+        options: &[&'a str],
+        title: &str,
+    ) -> TodoResult<&'a str> {
+        // print `options title`
+        self.println(&format!("\n{title}"));
+        self.println(&"_".repeat(20));
+
+        // print options
+        for (opt_idx, option) in options.iter().enumerate() {
+            let opt_num = opt_idx + 1;
+            self.println(&format!("{opt_num}. {option}"));
+        }
+        // get input option num
+        let num_input = self.input_num(prompt)?;
+
+        // validate entered option number
+        if num_input < 1 || num_input > options.len() as i32 {
+            return Err(TodoError::invalid(format!(
+                "You have entered an invalid option number. Expected an input within range 1 - {}",
+                options.len()
+            )));
+        }
+
+        // extract and return selected option
+        let selected_option = options[(num_input - 1) as usize];
+        self.println(&format!("\nYou have selected {selected_option}"));
+        Ok(selected_option)
+    }
+}
+
+/// # StdConsole
+/// a `Console` that reads from stdin and writes to stdout.
+///
+/// # Members
+/// - **closed**: whether stdin has reached end of file
+/// - **table**: the settings of the task tables printed to stdout
+#[derive(Default)]
+pub struct StdConsole {
+    closed: bool,
+    table: Table,
+}
+
+impl StdConsole {
+    /// # new
+    /// creates a new `StdConsole` that prints tables with the default settings.
+    pub fn new() -> StdConsole {
+        StdConsole::default()
+    }
+
+    /// # with_table
+    /// creates a new `StdConsole` that prints tables with the settings
+    /// `table` e.g., those read from 'config.json'.
+    pub fn with_table(table: Table) -> StdConsole {
+        StdConsole {
+            closed: false,
+            table,
+        }
+    }
+}
+
+impl Console for StdConsole {
+    fn input_str(&mut self, prompt: &str) -> TodoResult<String> {
+        print!("\n{prompt}");
+        // create `String` buffer to store data that will be read.
+        let receiver = &mut String::new();
+        // flush stdout to remove the buffering side effect
+        stdout()
+            .flush()
+            .map_err(|e| TodoError::io(Path::new("stdout"), e))?;
+        // read data from stdin and store in the `String` buffer
+        let read_count = stdin()
+            .read_line(receiver)
+            .map_err(|e| TodoError::io(Path::new("stdin"), e))?;
+        if read_count == 0 {
+            self.closed = true;
+            return Err(TodoError::InputClosed);
+        }
+        // return trimmed `String` of the entered input.
+        Ok(receiver.trim().to_string())
+    }
+
+    fn println(&mut self, text: &str) {
+        println!("{text}");
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    fn table(&self) -> Table {
+        self.table.clone()
+    }
+}
+
+/// # ScriptedConsole
+/// a `Console` that answers prompts from a fixed list of inputs and records
+/// everything written to it. Used to drive the interactive operations from
+/// tests or scripts.
+///
+/// # Members
+/// - **inputs**: the inputs that are returned, in order, for each prompt
+/// - **output**: every prompt and line written to the console
+#[derive(Default)]
+pub struct ScriptedConsole {
+    pub inputs: VecDeque<String>,
+    pub output: Vec<String>,
+    closed: bool,
+}
+
+impl ScriptedConsole {
+    /// # new
+    /// creates a new `ScriptedConsole` that answers prompts with `inputs`.
+    pub fn new(inputs: &[&str]) -> ScriptedConsole {
+        ScriptedConsole {
+            inputs: inputs.iter().map(|input| input.to_string()).collect(),
+            output: Vec::new(),
+            closed: false,
+        }
+    }
+}
+
+impl Console for ScriptedConsole {
+    fn input_str(&mut self, prompt: &str) -> TodoResult<String> {
+        self.output.push(prompt.to_string());
+        match self.inputs.pop_front() {
+            Some(input) => Ok(input.trim().to_string()),
+            None => {
+                self.closed = true;
+                Err(TodoError::InputClosed)
+            }
+        }
+    }
+
+    fn println(&mut self, text: &str) {
+        self.output.push(text.to_string());
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed
+    }
+}
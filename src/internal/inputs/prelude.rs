@@ -1 +1,2 @@
+pub use super::console::{Console, ScriptedConsole, StdConsole};
 pub use super::input_fns::{input_num, input_option, input_str};
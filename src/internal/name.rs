@@ -1,8 +1,7 @@
-use super::inputs::prelude::input_str;
+use super::error::{TodoError, TodoResult};
+use super::inputs::prelude::Console;
 use super::paths::get_name_path;
 use std::fs;
-use std::fs::File;
-use std::io::{Read, Write};
 use std::path::PathBuf;
 
 /// # new_name
@@ -12,32 +11,25 @@ use std::path::PathBuf;
 /// is detected and prompts the user to enter his name
 /// before storing that name back to `name_path`
 /// # Arguments
+/// - **console**: the `Console` used to prompt the user
 /// - **name_path**: path to a 'names.txt' file where the
-/// program saves the user's name.
+///   program saves the user's name.
 /// # Returns
-/// `Some(name)` where name is a String containing the user's
-/// name if the function completes successfully else `None`.
-fn new_name(name_path: &PathBuf) -> Option<String> {
+/// `Ok(name)` where name is a String containing the user's
+/// name if the function completes successfully else a `TodoError`.
+fn new_name(console: &mut dyn Console, name_path: &PathBuf) -> TodoResult<String> {
     // prompt and get user's name input
     let new_name_prompt: &str =
         "This is your first time using the Todo List \nPlease enter your name: ";
-    let name_input = input_str(new_name_prompt)?;
+    let name_input = console.input_str(new_name_prompt)?;
 
-    // extract the parent dir of the name_path
-    let name_path_parent = name_path.parent()?;
     // create the parent dir if it does not exist
-    fs::create_dir_all(name_path_parent).ok()?;
-    // create the 'name.txt' file and open it in read-write mode
-    let mut name_file = File::create(name_path).ok()?;
-    // write the user's input name to the newly created file
-    match name_file.write(name_input.as_bytes()) {
-        Ok(_) => Some(name_input),
-        Err(_) => {
-            println!("WARNING: Could not save your name {name_input} to file");
-            println!("Please 🙏🙏 try again");
-            None
-        }
+    if let Some(name_path_parent) = name_path.parent() {
+        fs::create_dir_all(name_path_parent).map_err(|e| TodoError::io(name_path_parent, e))?;
     }
+    // write the user's input name to the 'names.txt' file
+    fs::write(name_path, name_input.as_bytes()).map_err(|e| TodoError::io(name_path, e))?;
+    Ok(name_input)
 }
 
 /// # get_name
@@ -46,36 +38,18 @@ fn new_name(name_path: &PathBuf) -> Option<String> {
 /// `name_path` must exist before passing into this function
 /// # Arguments
 /// - **name_path**: the path to a 'names.txt' file where the
-/// name of the user is stored.
+///   name of the user is stored.
 /// # Returns
-/// `Some(name)` where name is a String read from `name_path`
+/// `Ok(name)` where name is a String read from `name_path`
 /// after reading its file contents and trimming it. May return
-/// `None` if function fails.
-fn get_name(name_path: &PathBuf) -> Option<String> {
-    // open file stored in `name_path` in read mode
-    let mut name_file = match File::open(name_path) {
-        Ok(f) => f,
-        Err(_) => {
-            println!("WARNING: Could not open file {}", name_path.display());
-            println!("Please 🙏🙏 try again");
-            return None;
-        }
-    };
-
+/// `TodoError::Io` if function fails.
+fn get_name(name_path: &PathBuf) -> TodoResult<String> {
     // read contents of the file as a String
-    let mut buf = String::new();
-    match name_file.read_to_string(&mut buf) {
-        Ok(_) => (),
-        Err(_) => {
-            println!("WARNING: Could not read file {}", name_path.display());
-            println!("Please 🙏🙏 try again");
-            return None;
-        }
-    }
+    let buf = fs::read_to_string(name_path).map_err(|e| TodoError::io(name_path, e))?;
 
     // return trimmed version of file content
     // i.e., the user's name as String
-    Some(buf.trim().to_string())
+    Ok(buf.trim().to_string())
 }
 
 /// # ret_name
@@ -83,37 +57,56 @@ fn get_name(name_path: &PathBuf) -> Option<String> {
 /// to enter a name for a new user or reading the user's
 /// stored name for an existing user.
 /// # Arguments
-/// none
+/// - **console**: the `Console` used to prompt the user
 /// # Returns
-/// `Some(name)` where name is the name of the user if the
-/// operation completes successfully else `None`.
-fn ret_name() -> Option<String> {
+/// `Ok(name)` where name is the name of the user if the
+/// operation completes successfully else a `TodoError`.
+fn ret_name(console: &mut dyn Console) -> TodoResult<String> {
     let name_path = get_name_path();
     if name_path.exists() {
         get_name(&name_path)
     } else {
-        new_name(&name_path)
+        new_name(console, &name_path)
     }
 }
 
+/// # ret_stored_name
+/// returns the name of the user stored in 'names.txt' without prompting
+/// for one e.g., to record who ran a command on the command line.
+/// # Returns
+/// `Some(name)` if a non-empty name is stored else `None`.
+pub fn ret_stored_name() -> Option<String> {
+    let name_path = get_name_path();
+    get_name(&name_path).ok().filter(|name| !name.is_empty())
+}
+
 /// # ret_name_loop
 /// repeats the operation to get the user's name in the
 /// case of program failure during name retrieval. Breaks
-/// and returns upon success.
+/// and returns upon success. The error of each failed
+/// attempt is printed before the next attempt.
 /// # Arguments
+/// - **console**: the `Console` used to prompt the user
 /// - **trails**: the number of times the program attempts
-/// to retrieve the user's name
+///   to retrieve the user's name
 /// # Returns
-/// `Some(name)` where name is the name of the user if the
-/// operation completes successfully else `None`.
-pub fn ret_name_loop(trials: i32) -> Option<String> {
+/// `Ok(name)` where name is the name of the user if the
+/// operation completes successfully else the error of the
+/// last attempt.
+pub fn ret_name_loop(console: &mut dyn Console, trials: i32) -> TodoResult<String> {
+    let mut last_error = TodoError::invalid("No attempt was made to get your name");
     for _ in 1..=trials {
-        match ret_name() {
-            Some(name) => {
-                return Some(name);
+        match ret_name(console) {
+            Ok(name) => {
+                return Ok(name);
+            }
+            Err(TodoError::InputClosed) => return Err(TodoError::InputClosed),
+            Err(e) => {
+                console.println(&format!("WARNING: {e}"));
+                console.println("Please 🙏🙏 try again");
+                last_error = e;
             }
-            None => continue,
         }
     }
-    None
+    Err(last_error)
 }
@@ -1,63 +1,134 @@
 use std::env;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 const NAME_PATH: &str = "names.txt";
 const DATA_PATH: &str = "data";
+const APP_DIR: &str = "todolist";
+
+/// the environment variable that overrides the data dir of the program.
+pub const HOME_ENV: &str = "TODOLIST_HOME";
+
+/// the data dir passed to the program with the `--data-dir` flag.
+static DATA_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// # set_data_dir
+/// overrides the data dir returned by `get_path` for the rest of the
+/// program. This is how the `--data-dir` flag is applied, so it takes
+/// precedence over the `TODOLIST_HOME` environment variable. Relative
+/// paths are resolved against the current work dir at the time of the
+/// call. Only the first call has any effect.
+/// # Arguments
+/// - **data_dir**: the dir in which the data in the program is stored
+/// # Returns
+/// `true` if the override was applied else `false`.
+pub fn set_data_dir(data_dir: &Path) -> bool {
+    let data_dir = std::path::absolute(data_dir).unwrap_or(data_dir.to_path_buf());
+    DATA_DIR_OVERRIDE.set(data_dir).is_ok()
+}
 
 /// # get_path
-/// returns a specialized path to hold data in the program.
-/// This path is relative to the main function block, and
-/// the returned path is of the form "../data" i.e., go back
-/// to the parent dir of main i.e., 'src' and then look for
-/// 'data'.
+/// returns the dir that holds the data in the program. The dir is
+/// resolved in the following order:
+///
+/// - the dir passed with the `--data-dir` flag (see `set_data_dir`)
+/// - the `TODOLIST_HOME` environment variable
+/// - the per-user data dir of the platform joined with 'todolist' i.e.,
+///     - Linux: `$XDG_DATA_HOME/todolist` or `~/.local/share/todolist`
+///     - macOS: `~/Library/Application Support/todolist`
+///     - Windows: `%APPDATA%\todolist`
+/// - 'data' in the current work dir if none of the above is available
+///
+/// The result does not depend on the current work dir unless all the
+/// other options are unavailable.
 /// # Arguments
 /// none
 /// # Returns
-/// path that holds the parent dir (`data`) in which the data
-/// in the program is stored.
+/// path that holds the dir in which the data in the program is stored.
 pub fn get_path() -> PathBuf {
-    // get current work dir
-    let work_dir_option = match env::current_dir() {
-        Ok(cwd) => Some(cwd),
-        Err(_) => None,
-    };
-
-    // set default dir if getting current work dir
-    // does not succeed.
-    let default = String::from(r"C:\todolist");
-
-    // get parent dir of the current work dir
-    // set to default dir if getting current work dir fails
-    let parent_dir = match work_dir_option {
-        Some(cwd) => cwd,
-        None => PathBuf::from(&default),
-    };
+    if let Some(data_dir) = DATA_DIR_OVERRIDE.get() {
+        return data_dir.clone();
+    }
+    if let Some(data_dir) = env::var_os(HOME_ENV).filter(|value| !value.is_empty()) {
+        let data_dir = PathBuf::from(data_dir);
+        return std::path::absolute(&data_dir).unwrap_or(data_dir);
+    }
+    if let Some(platform_dir) = dirs::data_dir() {
+        return platform_dir.join(APP_DIR);
+    }
+    let work_dir = env::current_dir().unwrap_or_default();
+    work_dir.join(DATA_PATH)
+}
 
-    // convert parent dir to String
-    let mut data_path = match parent_dir.to_str() {
-        Some(path) => String::from(path),
-        None => default,
-    };
+/// # get_legacy_path
+/// returns the dir that older versions of the program stored their data
+/// in. These versions appended a literal `'\'` and 'data' to the current
+/// work dir, which on Windows is the 'data' dir inside the current work
+/// dir and on other platforms a sibling dir whose name ends with '\data'.
+/// # Arguments
+/// none
+/// # Returns
+/// `Some(path)` where path is the legacy data dir for the current work dir
+/// else `None` if the current work dir cannot be read.
+pub fn get_legacy_path() -> Option<PathBuf> {
+    let work_dir = env::current_dir().ok()?;
+    let mut legacy_path = work_dir.to_str()?.to_string();
+    legacy_path.push('\\');
+    legacy_path.push_str(DATA_PATH);
+    Some(PathBuf::from(legacy_path))
+}
 
-    // adds the data dir to the previous path String
-    data_path.push('\\');
-    data_path.push_str(DATA_PATH);
+/// # copy_dir
+/// recursively copies every file in `source` to `target`.
+fn copy_dir(source: &Path, target: &Path) -> Option<()> {
+    fs::create_dir_all(target).ok()?;
+    for entry in fs::read_dir(source).ok()? {
+        let entry = entry.ok()?;
+        let target_path = target.join(entry.file_name());
+        if entry.file_type().ok()?.is_dir() {
+            copy_dir(&entry.path(), &target_path)?;
+        } else {
+            fs::copy(entry.path(), target_path).ok()?;
+        }
+    }
+    Some(())
+}
 
-    // returns the new path (String) as PathBuf
-    PathBuf::from(data_path)
+/// # adopt_legacy_path
+/// copies the data of an older version of the program from the legacy data
+/// dir (see `get_legacy_path`) to the data dir returned by `get_path`, if
+/// the data dir holds no data yet. The legacy dir is left untouched.
+/// # Arguments
+/// none
+/// # Returns
+/// `Some(())` if data was copied else `None`.
+pub fn adopt_legacy_path() -> Option<()> {
+    let data_path = get_path();
+    let legacy_path = get_legacy_path()?;
+    if data_path.join(NAME_PATH).exists() || !legacy_path.join(NAME_PATH).exists() {
+        return None;
+    }
+    if legacy_path == data_path {
+        return None;
+    }
+    copy_dir(&legacy_path, &data_path)?;
+    eprintln!(
+        "Copied the data of an older version of the program from {} to {}",
+        legacy_path.display(),
+        data_path.display()
+    );
+    Some(())
 }
 
 /// # get_name_path
 /// returns the path to the 'names.txt' file which is
 /// used in storing the name of the user and is in
-/// turn stored in the DATA_PATH of the program.
+/// turn stored in the data dir of the program.
 ///
-/// - DATA_PATH => "../data" relative to src/main
+/// - data dir => see `get_path`
 ///
-/// - returned path => "../data/names.txt' relative
-/// to src/main
-///
-/// This DATA_PATH is obtained by calling `get_path`
+/// - returned path => "<data dir>/names.txt"
 ///
 /// # Arguments
 /// none
@@ -71,20 +142,46 @@ pub fn get_name_path() -> PathBuf {
 /// # get_file_path
 /// returns the path to any file passed into the
 /// function through the `filename` argument beginning
-/// with the DATA_PATH of the program as its main dir.
+/// with the data dir of the program as its main dir.
 ///
-///     DATA_PATH => "../data" relative to src/main
-///     returned path => "../data/<filename>" relative
-///     to src/main
+/// - data dir => see `get_path`
 ///
-/// This DATA_PATH is obtained by calling `get_path`
+/// - returned path => "<data dir>/<filename>"
 ///
 /// # Arguments
 /// - **filename**: the name and extension of the file
-/// whose path is being returned with DATA_PATH as its
-/// main dir.
+///   whose path is being returned with the data dir as its
+///   main dir.
 /// # Returns
-/// the path to `filename` with DATA_PATH as its main dir.
+/// the path to `filename` with the data dir as its main dir.
 pub fn get_file_path(filename: &str) -> PathBuf {
     get_path().join(filename)
 }
+
+/// # with_test_dir
+/// runs `test` with the `TODOLIST_HOME` environment variable pointing at a
+/// new empty dir, which is removed afterwards. The environment is shared by
+/// every test in the process, so the tests that use the data dir are run one
+/// at a time.
+/// # Arguments
+/// - **test**: the test to be run, which is passed the data dir
+/// # Returns
+/// the result of `test`.
+#[cfg(test)]
+pub fn with_test_dir<T>(test: impl FnOnce(&Path) -> T) -> T {
+    use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+    use std::sync::Mutex;
+
+    static TEST_DIR_LOCK: Mutex<()> = Mutex::new(());
+    let _guard = TEST_DIR_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let test_dir = env::temp_dir().join(format!("todolist-test-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&test_dir);
+    fs::create_dir_all(&test_dir).unwrap();
+    env::set_var(HOME_ENV, &test_dir);
+    let result = catch_unwind(AssertUnwindSafe(|| test(&test_dir)));
+    env::remove_var(HOME_ENV);
+    let _ = fs::remove_dir_all(&test_dir);
+    result.unwrap_or_else(|panic| resume_unwind(panic))
+}
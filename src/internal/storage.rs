@@ -0,0 +1,7 @@
+pub mod atomic;
+pub mod backend;
+pub mod file;
+pub mod memory;
+pub mod prelude;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
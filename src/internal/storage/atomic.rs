@@ -0,0 +1,101 @@
+use crate::internal::error::{TodoError, TodoResult};
+use std::fs;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// # temp_path
+/// returns the path of the temporary file used while writing `path`
+/// i.e., `path` with '.tmp' appended to its file name. The temporary
+/// file is kept in the same dir so that it can be renamed over `path`.
+fn temp_path(path: &Path) -> TodoResult<PathBuf> {
+    let mut file_name = path
+        .file_name()
+        .ok_or_else(|| TodoError::Storage(format!("{} is not a file", path.display())))?
+        .to_os_string();
+    file_name.push(".tmp");
+    Ok(path.with_file_name(file_name))
+}
+
+/// # write_atomic
+/// writes `contents` to `path` without ever leaving a partially written file
+/// behind. The data is written to a temporary file next to `path`, flushed to
+/// disk with fsync and then renamed over `path`, so after a crash `path` holds
+/// either its previous or its new contents.
+/// # Arguments
+/// - **path**: the file to be written
+/// - **contents**: the bytes to write to `path`
+/// # Returns
+/// `Ok(())` if the function completes successfully else `TodoError::Io`.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> TodoResult<()> {
+    let parent_path = path.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(parent_path).map_err(|e| TodoError::io(parent_path, e))?;
+    let temp_path = temp_path(path)?;
+
+    // write and fsync the temporary file
+    let written = File::create(&temp_path).and_then(|mut temp_file| {
+        temp_file.write_all(contents)?;
+        temp_file.sync_all()
+    });
+    if let Err(e) = written {
+        fs::remove_file(&temp_path).unwrap_or_default();
+        return Err(TodoError::io(&temp_path, e));
+    }
+
+    // replace `path` with the temporary file
+    if let Err(e) = fs::rename(&temp_path, path) {
+        fs::remove_file(&temp_path).unwrap_or_default();
+        return Err(TodoError::io(path, e));
+    }
+
+    // fsync the parent dir so that the rename itself is durable
+    #[cfg(unix)]
+    if let Ok(parent_dir) = File::open(parent_path) {
+        parent_dir.sync_all().unwrap_or_default();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::paths::with_test_dir;
+
+    /// the names of the files in `dir`, sorted.
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut file_names = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect::<Vec<String>>();
+        file_names.sort();
+        file_names
+    }
+
+    #[test]
+    fn the_target_is_replaced_without_leaving_the_temp_file() {
+        with_test_dir(|data_dir| {
+            let path = data_dir.join("tasks.jsonl");
+            fs::write(&path, "old\n").unwrap();
+            write_atomic(&path, b"new\n").unwrap();
+
+            assert_eq!(fs::read_to_string(&path).unwrap(), "new\n");
+            assert_eq!(file_names(data_dir), ["tasks.jsonl"]);
+        });
+    }
+
+    #[test]
+    fn a_failed_write_removes_the_temp_file() {
+        with_test_dir(|data_dir| {
+            // a file cannot be renamed over a dir that is not empty
+            let path = data_dir.join("tasks.jsonl");
+            fs::create_dir_all(path.join("kept")).unwrap();
+
+            assert!(matches!(
+                write_atomic(&path, b"new\n"),
+                Err(TodoError::Io { .. })
+            ));
+            assert_eq!(file_names(data_dir), ["tasks.jsonl"]);
+            assert!(path.join("kept").is_dir());
+        });
+    }
+}
@@ -0,0 +1,101 @@
+use crate::internal::config::StorageKind;
+use crate::internal::error::TodoResult;
+use crate::internal::storage::file::FileStorage;
+use crate::internal::storage::memory::MemoryStorage;
+#[cfg(feature = "sqlite")]
+use crate::internal::storage::sqlite::SqliteStorage;
+use crate::internal::tasks::structs::{Task, TaskId, Tasks};
+
+/// # Storage
+/// a trait implemented by every persistence backend of the program.
+///
+/// A backend stores every task together with its status, and keeps the
+/// order of the tasks within each status so that `load_all` returns the
+/// tasks in the order they were saved or inserted.
+///
+/// # Methods
+/// - **load_all**: reads every stored task
+/// - **save_all**: replaces all stored tasks with `tasks`
+/// - **insert**: stores a new task
+/// - **update**: replaces the stored task that has the same `id` as `task`
+/// - **delete**: permanently removes the stored task with id `task_id`
+/// - **last_task_id**: reads the highest Task ID ever assigned
+/// - **record_task_id**: records the highest Task ID ever assigned
+/// - **is_persistent**: checks if the backend keeps its tasks in the data dir
+pub trait Storage {
+    /// # load_all
+    /// reads every stored task.
+    /// # Returns
+    /// `Ok(Tasks)` if the operation completes successfully else a `TodoError`.
+    fn load_all(&mut self) -> TodoResult<Tasks>;
+
+    /// # save_all
+    /// replaces all stored tasks with the tasks in `tasks`.
+    /// # Returns
+    /// `Ok(())` if the operation completes successfully else a `TodoError`.
+    fn save_all(&mut self, tasks: &Tasks) -> TodoResult<()>;
+
+    /// # insert
+    /// stores a new task after all stored tasks of the same status.
+    /// # Returns
+    /// `Ok(())` if the operation completes successfully else a `TodoError`.
+    fn insert(&mut self, task: &Task) -> TodoResult<()>;
+
+    /// # update
+    /// replaces the stored task that has the same `id` as `task`. If
+    /// the status of the task changed, it is moved after all stored
+    /// tasks of its new status.
+    /// # Returns
+    /// `Ok(())` if the operation completes successfully else a `TodoError`
+    /// which is `TodoError::NotFound` if no task with the same `id` is stored.
+    fn update(&mut self, task: &Task) -> TodoResult<()>;
+
+    /// # delete
+    /// permanently removes the stored task with id `task_id`.
+    /// # Returns
+    /// `Ok(())` if the operation completes successfully else a `TodoError`
+    /// which is `TodoError::NotFound` if no task with id `task_id` is stored.
+    fn delete(&mut self, task_id: i32) -> TodoResult<()>;
+
+    /// # last_task_id
+    /// reads the highest Task ID ever assigned (see `Tasks::last_id`), which
+    /// is kept even after the task with that id is removed for good so that
+    /// its id is never reused.
+    /// # Returns
+    /// `Ok(task_id)` where task_id is 0 if none was recorded yet else a
+    /// `TodoError`.
+    fn last_task_id(&mut self) -> TodoResult<TaskId>;
+
+    /// # record_task_id
+    /// records `task_id` as the highest Task ID ever assigned unless a higher
+    /// one is already recorded.
+    /// # Returns
+    /// `Ok(())` if the operation completes successfully else a `TodoError`.
+    fn record_task_id(&mut self, task_id: TaskId) -> TodoResult<()>;
+
+    /// # is_persistent
+    /// checks if the backend keeps its tasks in the data dir. Data written to
+    /// the data dir by older versions of the program is only migrated to a
+    /// persistent backend (see `migrate`).
+    fn is_persistent(&self) -> bool;
+}
+
+/// # open_storage
+/// creates the storage backend selected by `kind`.
+/// # Arguments
+/// - **kind**: the storage backend to open, usually read from 'config.json'
+/// # Returns
+/// `Ok(Box<dyn Storage>)` if the backend could be opened else
+/// `TodoError::Storage`.
+pub fn open_storage(kind: StorageKind) -> TodoResult<Box<dyn Storage>> {
+    match kind {
+        StorageKind::File => Ok(Box::new(FileStorage::new())),
+        StorageKind::Memory => Ok(Box::new(MemoryStorage::new())),
+        #[cfg(feature = "sqlite")]
+        StorageKind::Sqlite => Ok(Box::new(SqliteStorage::new()?)),
+        #[cfg(not(feature = "sqlite"))]
+        StorageKind::Sqlite => Err(crate::internal::error::TodoError::Storage(
+            "This build of the program does not include the sqlite backend".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,125 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::paths::get_file_path;
+use crate::internal::storage::atomic::write_atomic;
+use crate::internal::storage::backend::Storage;
+use crate::internal::tasks::format::{from_line, to_lines};
+use crate::internal::tasks::migrate::{quarantine, read_last_task_id, write_last_task_id};
+use crate::internal::tasks::structs::{Task, TaskId, Tasks};
+use std::fs;
+use std::path::PathBuf;
+
+/// the JSON Lines file holding every task in the program.
+pub const TASKS_FILE: &str = "tasks.jsonl";
+
+/// # FileStorage
+/// the default storage backend which keeps every task as one line of JSON
+/// in 'tasks.jsonl' in the data dir (see `format::to_line`).
+///
+/// # Members
+/// - **path**: the path to the JSON Lines file
+pub struct FileStorage {
+    pub path: PathBuf,
+}
+
+impl FileStorage {
+    /// # new
+    /// creates a new `FileStorage` that stores tasks in 'tasks.jsonl'
+    /// in the data dir.
+    pub fn new() -> FileStorage {
+        FileStorage {
+            path: get_file_path(TASKS_FILE),
+        }
+    }
+
+    /// # read
+    /// reads the stored tasks from `path`. If the file has not been created yet, an
+    /// empty String is returned as there are no stored tasks.
+    /// # Returns
+    /// `Ok(String)` containing data read from storage if the function completes
+    /// successfully else `TodoError::Io`.
+    fn read(&self) -> TodoResult<String> {
+        // nothing has been saved yet
+        if !self.path.exists() {
+            return Ok(String::new());
+        }
+        fs::read_to_string(&self.path).map_err(|e| TodoError::io(&self.path, e))
+    }
+
+    /// # write
+    /// writes every task in `tasks` to `path` in the order active, completed
+    /// and deleted tasks. The file is replaced atomically (see `write_atomic`)
+    /// so a crash while writing never leaves a truncated file behind.
+    /// # Returns
+    /// `Ok(())` if the operation completes successfully else a `TodoError`.
+    fn write(&self, tasks: &Tasks) -> TodoResult<()> {
+        let mut task_print = String::new();
+        task_print.push_str(to_lines(&tasks.active)?.as_str());
+        task_print.push_str(to_lines(&tasks.completed)?.as_str());
+        task_print.push_str(to_lines(&tasks.deleted)?.as_str());
+        write_atomic(&self.path, task_print.as_bytes())
+    }
+}
+
+impl Default for FileStorage {
+    fn default() -> Self {
+        FileStorage::new()
+    }
+}
+
+impl Storage for FileStorage {
+    /// reads each line of the file and parses it to the `Task` struct used to store
+    /// data for tasks. Lines that cannot be parsed are quarantined and skipped instead
+    /// of failing the whole load.
+    fn load_all(&mut self) -> TodoResult<Tasks> {
+        let tasks_str = self.read()?;
+        let mut tasks = Tasks::new();
+        for (line_idx, task_str) in tasks_str.lines().enumerate() {
+            // skip empty lines
+            if task_str.trim().is_empty() {
+                continue;
+            }
+            // get `Task` object from the line and store it in `tasks`
+            match from_line(task_str) {
+                Ok(loaded_task) => tasks.push(loaded_task),
+                Err(e) => quarantine(&e.at(TASKS_FILE, line_idx + 1), task_str)?,
+            }
+        }
+        Ok(tasks)
+    }
+
+    fn save_all(&mut self, tasks: &Tasks) -> TodoResult<()> {
+        self.write(tasks)
+    }
+
+    fn insert(&mut self, task: &Task) -> TodoResult<()> {
+        let mut tasks = self.load_all()?;
+        tasks.push(task.clone());
+        self.write(&tasks)
+    }
+
+    fn update(&mut self, task: &Task) -> TodoResult<()> {
+        let mut tasks = self.load_all()?;
+        tasks.replace(task.clone())?;
+        self.write(&tasks)
+    }
+
+    fn delete(&mut self, task_id: i32) -> TodoResult<()> {
+        let mut tasks = self.load_all()?;
+        tasks.take(task_id).ok_or(TodoError::NotFound(task_id))?;
+        self.write(&tasks)
+    }
+
+    /// reads the highest Task ID ever assigned from 'meta.json'.
+    fn last_task_id(&mut self) -> TodoResult<TaskId> {
+        read_last_task_id()
+    }
+
+    /// records the highest Task ID ever assigned in 'meta.json'.
+    fn record_task_id(&mut self, task_id: TaskId) -> TodoResult<()> {
+        write_last_task_id(task_id)
+    }
+
+    fn is_persistent(&self) -> bool {
+        true
+    }
+}
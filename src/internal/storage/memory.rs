@@ -0,0 +1,65 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::storage::backend::Storage;
+use crate::internal::tasks::structs::{Task, TaskId, Tasks};
+
+/// # MemoryStorage
+/// a storage backend that keeps all tasks in memory and is discarded when
+/// the program exits. Useful for tests and for trying the program out, as
+/// it never reads or writes the data dir.
+///
+/// # Members
+/// - **tasks**: the stored tasks
+/// - **last_task_id**: the highest Task ID ever assigned
+#[derive(Default)]
+pub struct MemoryStorage {
+    pub tasks: Tasks,
+    pub last_task_id: TaskId,
+}
+
+impl MemoryStorage {
+    /// # new
+    /// creates a new `MemoryStorage` with no stored tasks.
+    pub fn new() -> MemoryStorage {
+        MemoryStorage::default()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn load_all(&mut self) -> TodoResult<Tasks> {
+        Ok(self.tasks.clone())
+    }
+
+    fn save_all(&mut self, tasks: &Tasks) -> TodoResult<()> {
+        self.tasks = tasks.clone();
+        Ok(())
+    }
+
+    fn insert(&mut self, task: &Task) -> TodoResult<()> {
+        self.tasks.push(task.clone());
+        Ok(())
+    }
+
+    fn update(&mut self, task: &Task) -> TodoResult<()> {
+        self.tasks.replace(task.clone())
+    }
+
+    fn delete(&mut self, task_id: i32) -> TodoResult<()> {
+        self.tasks
+            .take(task_id)
+            .map(|_| ())
+            .ok_or(TodoError::NotFound(task_id))
+    }
+
+    fn last_task_id(&mut self) -> TodoResult<TaskId> {
+        Ok(self.last_task_id)
+    }
+
+    fn record_task_id(&mut self, task_id: TaskId) -> TodoResult<()> {
+        self.last_task_id = self.last_task_id.max(task_id);
+        Ok(())
+    }
+
+    fn is_persistent(&self) -> bool {
+        false
+    }
+}
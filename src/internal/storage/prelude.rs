@@ -0,0 +1,6 @@
+pub use super::atomic::write_atomic;
+pub use super::backend::{open_storage, Storage};
+pub use super::file::FileStorage;
+pub use super::memory::MemoryStorage;
+#[cfg(feature = "sqlite")]
+pub use super::sqlite::SqliteStorage;
@@ -0,0 +1,255 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::paths::get_file_path;
+use crate::internal::storage::backend::Storage;
+use crate::internal::tasks::format::{from_line, to_line};
+use crate::internal::tasks::migrate::{quarantine, read_last_task_id};
+use crate::internal::tasks::structs::{Task, TaskId, Tasks};
+use rusqlite::{params, Connection, ErrorCode, OptionalExtension};
+use std::fs;
+
+/// the SQLite database file holding every task in the program.
+pub const DATABASE_FILE: &str = "tasks.db";
+
+/// the schema of the `tasks` table. Each task is stored as its JSON record
+/// (see `format::to_line`) next to the columns used to look it up, so new
+/// task fields do not require changes to the table. The `meta` table keeps
+/// the values that are not part of any task e.g., the highest Task ID ever
+/// assigned.
+const CREATE_TABLE: &str = "
+    CREATE TABLE IF NOT EXISTS tasks (
+        row INTEGER PRIMARY KEY AUTOINCREMENT,
+        id INTEGER NOT NULL,
+        status TEXT NOT NULL,
+        position INTEGER NOT NULL,
+        record TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS tasks_id ON tasks (id);
+    CREATE INDEX IF NOT EXISTS tasks_position ON tasks (position);
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY,
+        value INTEGER NOT NULL
+    );
+";
+
+/// the index that keeps two stored tasks from sharing a Task ID.
+const CREATE_UNIQUE_ID: &str = "CREATE UNIQUE INDEX IF NOT EXISTS tasks_unique_id ON tasks (id)";
+
+/// the key of the highest Task ID ever assigned in the `meta` table.
+const LAST_TASK_ID_KEY: &str = "last_task_id";
+
+/// # SqliteStorage
+/// a storage backend that keeps every task in an embedded SQLite database
+/// ('tasks.db' in the data dir). Unlike `FileStorage`, single task inserts,
+/// updates and deletes do not rewrite every stored task which suits
+/// programs with tens of thousands of tasks.
+///
+/// # Members
+/// - **connection**: the open connection to the database
+pub struct SqliteStorage {
+    connection: Connection,
+}
+
+impl SqliteStorage {
+    /// # new
+    /// opens (creating it if needed) 'tasks.db' in the data dir.
+    /// # Returns
+    /// `Ok(SqliteStorage)` if the database could be opened else a `TodoError`.
+    pub fn new() -> TodoResult<SqliteStorage> {
+        let db_path = get_file_path(DATABASE_FILE);
+        if let Some(parent_path) = db_path.parent() {
+            fs::create_dir_all(parent_path).map_err(|e| TodoError::io(parent_path, e))?;
+        }
+        let connection = Connection::open(&db_path).map_err(|e| {
+            TodoError::Storage(format!(
+                "Could not open database {}. Error: {e}",
+                db_path.display()
+            ))
+        })?;
+        SqliteStorage::from_connection(connection)
+    }
+
+    /// # from_connection
+    /// creates a `SqliteStorage` from an open connection, creating the
+    /// `tasks` and `meta` tables if they do not exist, and the unique index
+    /// on the Task IDs unless stored tasks share an id. Such tasks, which
+    /// older versions could store, are given new ids when they are loaded
+    /// (see `load_tasks`) and `save_all` then creates the index.
+    pub fn from_connection(connection: Connection) -> TodoResult<SqliteStorage> {
+        connection.execute_batch(CREATE_TABLE).map_err(db_error)?;
+        match connection.execute_batch(CREATE_UNIQUE_ID) {
+            Err(rusqlite::Error::SqliteFailure(e, _))
+                if e.code == ErrorCode::ConstraintViolation => {}
+            created => created.map_err(db_error)?,
+        }
+        Ok(SqliteStorage { connection })
+    }
+
+    /// # next_position
+    /// returns a position that orders a task after every stored task.
+    fn next_position(&self) -> TodoResult<i64> {
+        self.connection
+            .query_row(
+                "SELECT COALESCE(MAX(position), 0) + 1 FROM tasks",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(db_error)
+    }
+}
+
+/// # db_error
+/// converts an error returned by the database to `TodoError::Storage`.
+fn db_error(e: rusqlite::Error) -> TodoError {
+    TodoError::Storage(e.to_string())
+}
+
+impl Storage for SqliteStorage {
+    /// reads the record of every row in the order of their positions. Records
+    /// that cannot be parsed are quarantined and skipped instead of failing the
+    /// whole load.
+    fn load_all(&mut self) -> TodoResult<Tasks> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT row, record FROM tasks ORDER BY position")
+            .map_err(db_error)?;
+        let rows = statement
+            .query_map([], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(db_error)?;
+        let mut tasks = Tasks::new();
+        for row in rows {
+            let (row_num, record) = row.map_err(db_error)?;
+            match from_line(&record) {
+                Ok(task) => tasks.push(task),
+                Err(e) => quarantine(&e.at(DATABASE_FILE, row_num as usize), &record)?,
+            }
+        }
+        Ok(tasks)
+    }
+
+    fn save_all(&mut self, tasks: &Tasks) -> TodoResult<()> {
+        let transaction = self.connection.transaction().map_err(db_error)?;
+        transaction
+            .execute("DELETE FROM tasks", [])
+            .map_err(db_error)?;
+        for (position, task) in tasks.iter().enumerate() {
+            transaction
+                .execute(
+                    "INSERT INTO tasks (id, status, position, record) VALUES (?1, ?2, ?3, ?4)",
+                    params![task.id, task.status.show(), position as i64, to_line(task)?],
+                )
+                .map_err(db_error)?;
+        }
+        transaction
+            .execute_batch(CREATE_UNIQUE_ID)
+            .map_err(db_error)?;
+        transaction.commit().map_err(db_error)
+    }
+
+    fn insert(&mut self, task: &Task) -> TodoResult<()> {
+        let position = self.next_position()?;
+        self.connection
+            .execute(
+                "INSERT INTO tasks (id, status, position, record) VALUES (?1, ?2, ?3, ?4)",
+                params![task.id, task.status.show(), position, to_line(task)?],
+            )
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    fn update(&mut self, task: &Task) -> TodoResult<()> {
+        // a task whose status changed is moved after every stored task
+        let position = self.next_position()?;
+        let changed = self
+            .connection
+            .execute(
+                "UPDATE tasks SET
+                    position = CASE WHEN status = ?2 THEN position ELSE ?3 END,
+                    status = ?2,
+                    record = ?4
+                 WHERE id = ?1",
+                params![task.id, task.status.show(), position, to_line(task)?],
+            )
+            .map_err(db_error)?;
+        if changed == 0 {
+            return Err(TodoError::NotFound(task.id));
+        }
+        Ok(())
+    }
+
+    fn delete(&mut self, task_id: i32) -> TodoResult<()> {
+        let changed = self
+            .connection
+            .execute("DELETE FROM tasks WHERE id = ?1", params![task_id])
+            .map_err(db_error)?;
+        if changed == 0 {
+            return Err(TodoError::NotFound(task_id));
+        }
+        Ok(())
+    }
+
+    /// reads the highest Task ID ever assigned from the `meta` table. A
+    /// database written before the table existed falls back to 'meta.json'
+    /// where older versions recorded it.
+    fn last_task_id(&mut self) -> TodoResult<TaskId> {
+        let recorded = self
+            .connection
+            .query_row(
+                "SELECT value FROM meta WHERE key = ?1",
+                params![LAST_TASK_ID_KEY],
+                |row| row.get::<_, TaskId>(0),
+            )
+            .optional()
+            .map_err(db_error)?;
+        match recorded {
+            Some(task_id) => Ok(task_id),
+            None => read_last_task_id(),
+        }
+    }
+
+    /// records the highest Task ID ever assigned in the `meta` table. The id
+    /// read from 'meta.json' by `last_task_id` is recorded by `load_tasks`
+    /// before any task is saved.
+    fn record_task_id(&mut self, task_id: TaskId) -> TodoResult<()> {
+        self.connection
+            .execute(
+                "INSERT INTO meta (key, value) VALUES (?1, ?2)
+                 ON CONFLICT (key) DO UPDATE SET value = MAX(value, excluded.value)",
+                params![LAST_TASK_ID_KEY, task_id],
+            )
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    fn is_persistent(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open() -> SqliteStorage {
+        SqliteStorage::from_connection(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn records_the_highest_task_id_in_the_database() {
+        let mut storage = open();
+        storage.record_task_id(5).unwrap();
+        storage.record_task_id(3).unwrap();
+        assert_eq!(storage.last_task_id().unwrap(), 5);
+    }
+
+    #[test]
+    fn refuses_two_tasks_with_the_same_id() {
+        let mut storage = open();
+        let mut tasks = Tasks::new();
+        let task_id = tasks.add(String::from("first"));
+        storage.save_all(&tasks).unwrap();
+        let task = tasks.find(task_id).unwrap().clone();
+        assert!(matches!(storage.insert(&task), Err(TodoError::Storage(_))));
+    }
+}
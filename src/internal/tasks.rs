@@ -1,10 +1,39 @@
 pub mod add;
+pub mod api;
 pub mod check;
+pub mod dates;
 pub mod delete;
+pub mod dependencies;
+pub mod depends;
+pub mod due;
 pub mod edit;
+pub mod events;
+pub mod filter;
+pub mod format;
+pub mod fuzzy;
+pub mod ids;
+pub mod labels;
 pub mod load;
+pub mod migrate;
+pub mod notes;
+pub mod parent;
 pub mod prelude;
+pub mod priority;
+pub mod purge;
+pub mod query;
+pub mod recurrence;
+pub mod repeat;
+pub mod restore;
 pub mod save;
+pub mod search;
 pub mod structs;
+pub mod subtasks;
+pub mod table;
+pub mod timeline;
+pub mod timer;
+pub mod timesheet;
+pub mod undo;
+pub mod undo_stack;
+pub mod urgency;
 pub mod utils;
-pub mod view;
\ No newline at end of file
+pub mod view;
@@ -1,26 +1,42 @@
-use crate::internal::inputs::prelude::input_str;
-use crate::internal::tasks::structs::Task;
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::inputs::prelude::Console;
+use crate::internal::tasks::due::input_due;
+use crate::internal::tasks::labels::Labels;
+use crate::internal::tasks::priority::input_priority;
+use crate::internal::tasks::structs::{TaskId, Tasks};
 
 /// # add
-/// prompts the user to enter the name of a new task and then adds that task to a vector of
-/// active tasks available in the program
+/// prompts the user to enter the name, optional due date and priority of a new task and then
+/// adds that task to the active tasks available in the program (see `Tasks::add`).
+/// The name may contain `+tag` and `project:x` words which set the tags and project
+/// of the task (see `Labels::parse`).
 /// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the result
 /// - **name_in**: The name of the user
-/// - **active_tasks**: A mutable vector of tasks to which the new task is appended to
-/// - **new_task_id**: The id that should be assigned to the new task that is created.
+/// - **tasks**: A mutable ref to Tasks to whose active tasks the new task is appended
 /// # Returns
-/// `Some(())` or a Some of the unit type to signify success, while `None` is propagated if
-/// the function errors.
-pub fn add(name_in: &str, active_tasks: &mut Vec<Task>, new_task_id: i32) -> Option<()> {
+/// `Ok(task_id)` where task_id is the id of the new task to signify success, while a
+/// `TodoError` is propagated if the function errors.
+pub fn add(console: &mut dyn Console, name_in: &str, tasks: &mut Tasks) -> TodoResult<TaskId> {
     let prompt = format!("{name_in} please enter a new task: ");
     // get task name from user
-    let new_task_name = input_str(prompt.as_str())?;
+    let new_task_input = console.input_str(prompt.as_str())?;
+    let labels = Labels::parse(&new_task_input)?;
+    let new_task_name = labels
+        .name
+        .clone()
+        .ok_or_else(|| TodoError::invalid("The task has no name"))?;
+    // get the due date and priority before the task is created so bad input adds nothing
+    let due = input_due(console)?;
+    let priority = input_priority(console)?;
     // create new Task
-    let new_task = Task::from_name_id(new_task_name, new_task_id);
-    println!(
+    let new_task_id = tasks.add(new_task_name);
+    tasks.set_due(new_task_id, due)?;
+    tasks.set_priority(new_task_id, priority)?;
+    let new_task = tasks.relabel(new_task_id, &labels)?;
+    console.println(&format!(
         "\nAdded Task with details: {} successfully",
         &new_task.show()
-    );
-    active_tasks.push(new_task);
-    Some(())
+    ));
+    Ok(new_task_id)
 }
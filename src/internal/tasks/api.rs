@@ -0,0 +1,471 @@
+use crate::internal::config::Urgency;
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::tasks::fuzzy::{fuzzy_match, match_notes, SearchHit};
+use crate::internal::tasks::labels::{Filter, Labels};
+use crate::internal::tasks::query::Query;
+use crate::internal::tasks::structs::{DateTimeOption, Priority, Task, TaskId, TaskStatus, Tasks};
+use crate::internal::tasks::urgency::urgency;
+use crate::internal::tasks::utils::ret_last_task_id;
+use chrono::{DateTime, Days, Local, TimeDelta};
+
+/// the changes of status a task may go through, as (from, to) pairs. Every
+/// change of status is checked against these in `Tasks::transition`.
+const TRANSITIONS: [(TaskStatus, TaskStatus); 4] = [
+    (TaskStatus::Active, TaskStatus::Completed),
+    (TaskStatus::Active, TaskStatus::Deleted),
+    (TaskStatus::Completed, TaskStatus::Active),
+    (TaskStatus::Deleted, TaskStatus::Active),
+];
+
+/// The core operations on `Tasks`. None of these read input or print output,
+/// so they can be used by the interactive menu, the command line, a GUI or a
+/// test alike. The interactive operations in `add`, `check`, `edit` and
+/// `delete` prompt through a `Console` and then call these methods.
+impl Tasks {
+    /// # next_id
+    /// returns the task id that the next new task will be assigned i.e., one
+    /// more than the highest Task ID ever assigned, whether or not that task
+    /// still exists.
+    pub fn next_id(&self) -> TaskId {
+        self.last_id.max(ret_last_task_id(self)) + 1
+    }
+
+    /// # allocate_id
+    /// returns `next_id` and records it as assigned so that no other task
+    /// is given it.
+    pub fn allocate_id(&mut self) -> TaskId {
+        self.last_id = self.next_id();
+        self.last_id
+    }
+
+    /// # add
+    /// creates a new active task named `name` and appends it to the
+    /// active tasks.
+    /// # Arguments
+    /// - **name**: the name of the new task
+    /// # Returns
+    /// the id of the new task.
+    pub fn add(&mut self, name: String) -> TaskId {
+        let new_task_id = self.allocate_id();
+        self.active.push(Task::from_name_id(name, new_task_id));
+        new_task_id
+    }
+
+    /// # rename
+    /// changes the name of the active task with id `task_id` to `name`
+    /// and updates its `last_time_modified`.
+    /// # Arguments
+    /// - **task_id**: the id of the task to be renamed
+    /// - **name**: the new name of the task
+    /// # Returns
+    /// `Ok(old_name)` where old_name is the name of the task before it was
+    /// renamed if an active task with id `task_id` exists else
+    /// `TodoError::NotFound`.
+    pub fn rename(&mut self, task_id: TaskId, name: String) -> TodoResult<String> {
+        let target_task = self
+            .active
+            .iter_mut()
+            .find(|task| task.id == task_id)
+            .ok_or(TodoError::NotFound(task_id))?;
+        let old_name = target_task.name.clone();
+        target_task.set(name);
+        Ok(old_name)
+    }
+
+    /// # active_index
+    /// returns the index of the active task with id `task_id`.
+    /// # Returns
+    /// `Ok(index)` if an active task with id `task_id` exists else
+    /// `TodoError::NotFound`.
+    fn active_index(&self, task_id: TaskId) -> TodoResult<usize> {
+        self.active
+            .iter()
+            .position(|task| task.id == task_id)
+            .ok_or(TodoError::NotFound(task_id))
+    }
+
+    /// # transition
+    /// changes the status of the task with id `task_id` to `status` if the
+    /// change is one of `TRANSITIONS`. The task is moved to the end of the
+    /// member matching its new status and its `last_time_modified` is updated.
+    /// Its `time_finished` is recorded when it is completed or deleted and
+    /// cleared when it is reopened or restored, and its timer is stopped when
+    /// it is no longer active.
+    /// # Arguments
+    /// - **task_id**: the id of the task
+    /// - **status**: the new status of the task
+    /// # Returns
+    /// `Ok(task)` where task is a reference to the changed task else
+    /// `TodoError::NotFound` if no task with id `task_id` exists or
+    /// `TodoError::InvalidInput` if its status cannot change to `status`.
+    fn transition(&mut self, task_id: TaskId, status: TaskStatus) -> TodoResult<&Task> {
+        let current = self
+            .find(task_id)
+            .ok_or(TodoError::NotFound(task_id))?
+            .status;
+        if !TRANSITIONS.contains(&(current, status)) {
+            let verb = match status {
+                TaskStatus::Active => "made active",
+                TaskStatus::Completed => "completed",
+                TaskStatus::Deleted => "deleted",
+            };
+            return Err(TodoError::invalid(format!(
+                "Task {task_id} is {} and cannot be {verb}",
+                current.show().to_lowercase()
+            )));
+        }
+        let mut target_task = self.take(task_id).ok_or(TodoError::NotFound(task_id))?;
+        let now = Local::now();
+        target_task.time_finished = match status {
+            TaskStatus::Completed | TaskStatus::Deleted => DateTimeOption::DateTime(now),
+            TaskStatus::Active => DateTimeOption::None,
+        };
+        if status != TaskStatus::Active {
+            target_task.stop_work(now);
+        }
+        target_task.status = status;
+        target_task.last_time_modified = now;
+        let tasks_list = self.list_mut(status);
+        tasks_list.push(target_task);
+        Ok(&tasks_list[tasks_list.len() - 1])
+    }
+
+    /// # complete
+    /// marks the active task with id `task_id` as complete. Its status is
+    /// changed to `TaskStatus::Completed`, its `time_finished` is recorded,
+    /// and it is moved from the active tasks to the completed tasks.
+    /// # Arguments
+    /// - **task_id**: the id of the task to be marked as complete
+    /// # Returns
+    /// `Ok(task)` where task is a reference to the completed task else
+    /// `TodoError::NotFound` if no task with id `task_id` exists or
+    /// `TodoError::InvalidInput` if the task is not active.
+    pub fn complete(&mut self, task_id: TaskId) -> TodoResult<&Task> {
+        self.transition(task_id, TaskStatus::Completed)
+    }
+
+    /// # delete
+    /// deletes the active task with id `task_id`. Its status is changed to
+    /// `TaskStatus::Deleted` and it is moved from the active tasks to the
+    /// deleted tasks. Its `time_finished` records when it was deleted (see
+    /// `Tasks::purge`).
+    /// # Arguments
+    /// - **task_id**: the id of the task to be deleted
+    /// # Returns
+    /// `Ok(task)` where task is a reference to the deleted task else
+    /// `TodoError::NotFound` if no task with id `task_id` exists or
+    /// `TodoError::InvalidInput` if the task is not active.
+    pub fn delete(&mut self, task_id: TaskId) -> TodoResult<&Task> {
+        self.transition(task_id, TaskStatus::Deleted)
+    }
+
+    /// # restore
+    /// moves the deleted task with id `task_id` back to the active tasks.
+    /// # Arguments
+    /// - **task_id**: the id of the task to be restored
+    /// # Returns
+    /// `Ok(task)` where task is a reference to the restored task else
+    /// `TodoError::NotFound` if no task with id `task_id` exists or
+    /// `TodoError::InvalidInput` if the task is not deleted.
+    pub fn restore(&mut self, task_id: TaskId) -> TodoResult<&Task> {
+        if self
+            .find(task_id)
+            .is_some_and(|task| task.status != TaskStatus::Deleted)
+        {
+            return Err(TodoError::invalid(format!("Task {task_id} is not deleted")));
+        }
+        self.transition(task_id, TaskStatus::Active)
+    }
+
+    /// # reopen
+    /// moves the completed task with id `task_id` back to the active tasks
+    /// and clears its `time_finished`.
+    /// # Arguments
+    /// - **task_id**: the id of the task to be reopened
+    /// # Returns
+    /// `Ok(task)` where task is a reference to the reopened task else
+    /// `TodoError::NotFound` if no task with id `task_id` exists or
+    /// `TodoError::InvalidInput` if the task is not completed.
+    pub fn reopen(&mut self, task_id: TaskId) -> TodoResult<&Task> {
+        if self
+            .find(task_id)
+            .is_some_and(|task| task.status != TaskStatus::Completed)
+        {
+            return Err(TodoError::invalid(format!(
+                "Task {task_id} is not completed"
+            )));
+        }
+        self.transition(task_id, TaskStatus::Active)
+    }
+
+    /// # purge
+    /// permanently removes the deleted tasks that were deleted before
+    /// `now - age` i.e., whose `time_finished` is older than `age`, and
+    /// removes them from the parent and dependencies of the remaining tasks.
+    /// Tasks deleted by older versions, which did not record `time_finished`
+    /// for deleted tasks, are aged by their `last_time_modified` instead.
+    /// # Arguments
+    /// - **age**: how long a task stays deleted before it is purged
+    /// - **now**: the current time
+    /// # Returns
+    /// the ids of the purged tasks followed by the ids of the tasks that
+    /// referred to them.
+    pub fn purge(&mut self, age: TimeDelta, now: DateTime<Local>) -> Vec<TaskId> {
+        let cutoff = now - age;
+        let mut purged_ids = Vec::<TaskId>::new();
+        self.deleted.retain(|task| {
+            let time_deleted = task.time_finished.get().unwrap_or(task.last_time_modified);
+            let expired = time_deleted < cutoff;
+            if expired {
+                purged_ids.push(task.id);
+            }
+            !expired
+        });
+        let mut changed_ids = purged_ids.clone();
+        for status in [
+            TaskStatus::Active,
+            TaskStatus::Completed,
+            TaskStatus::Deleted,
+        ] {
+            for task in self.list_mut(status) {
+                let parent_purged = task
+                    .parent
+                    .is_some_and(|parent| purged_ids.contains(&parent));
+                let depends_purged = task.depends_on.iter().any(|id| purged_ids.contains(id));
+                if !parent_purged && !depends_purged {
+                    continue;
+                }
+                if parent_purged {
+                    task.parent = None;
+                }
+                task.depends_on.retain(|id| !purged_ids.contains(id));
+                task.last_time_modified = now;
+                changed_ids.push(task.id);
+            }
+        }
+        changed_ids
+    }
+
+    /// # set_due
+    /// sets or clears the due date of the active task with id `task_id`
+    /// and updates its `last_time_modified`.
+    /// # Arguments
+    /// - **task_id**: the id of the task
+    /// - **due**: the new due date of the task, or `DateTimeOption::None` to
+    ///   remove its due date
+    /// # Returns
+    /// `Ok(task)` where task is a reference to the changed task if an
+    /// active task with id `task_id` exists else `TodoError::NotFound`.
+    pub fn set_due(&mut self, task_id: TaskId, due: DateTimeOption) -> TodoResult<&Task> {
+        let task_idx = self.active_index(task_id)?;
+        let target_task = &mut self.active[task_idx];
+        target_task.due = due;
+        target_task.last_time_modified = Local::now();
+        Ok(target_task)
+    }
+
+    /// # due_where
+    /// returns a copy of every active task with a due date for which
+    /// `filter` returns `true`, sorted by due date.
+    fn due_where(&self, filter: impl Fn(DateTime<Local>) -> bool) -> Vec<Task> {
+        let mut due_tasks = self
+            .active
+            .iter()
+            .filter(|task| task.due.get().is_some_and(&filter))
+            .cloned()
+            .collect::<Vec<Task>>();
+        due_tasks.sort_by_key(|task| task.due.get());
+        due_tasks
+    }
+
+    /// # due_today
+    /// returns the active tasks due on the same day as `now`, sorted by
+    /// due date.
+    pub fn due_today(&self, now: DateTime<Local>) -> Vec<Task> {
+        self.due_where(|due| due.date_naive() == now.date_naive())
+    }
+
+    /// # overdue
+    /// returns the active tasks whose due date is before `now`, sorted by
+    /// due date.
+    pub fn overdue(&self, now: DateTime<Local>) -> Vec<Task> {
+        self.due_where(|due| due < now)
+    }
+
+    /// # upcoming
+    /// returns the active tasks due in the seven days after the day of
+    /// `now`, sorted by due date. Tasks due today are left to `due_today`.
+    pub fn upcoming(&self, now: DateTime<Local>) -> Vec<Task> {
+        let today = now.date_naive();
+        let week_end = today.checked_add_days(Days::new(7)).unwrap_or(today);
+        self.due_where(|due| due.date_naive() > today && due.date_naive() <= week_end)
+    }
+
+    /// # set_priority
+    /// sets the priority of the active task with id `task_id` and updates
+    /// its `last_time_modified`.
+    /// # Arguments
+    /// - **task_id**: the id of the task
+    /// - **priority**: the new priority of the task
+    /// # Returns
+    /// `Ok(task)` where task is a reference to the changed task if an
+    /// active task with id `task_id` exists else `TodoError::NotFound`.
+    pub fn set_priority(&mut self, task_id: TaskId, priority: Priority) -> TodoResult<&Task> {
+        let task_idx = self.active_index(task_id)?;
+        let target_task = &mut self.active[task_idx];
+        target_task.priority = priority;
+        target_task.last_time_modified = Local::now();
+        Ok(target_task)
+    }
+
+    /// # by_urgency
+    /// returns a copy of the tasks with status `status` ordered from the
+    /// most to the least urgent (see `urgency::urgency`). Tasks with the
+    /// same score keep their stored order.
+    /// # Arguments
+    /// - **status**: the status of the tasks to be ordered
+    /// - **coefficients**: the coefficients of the urgency score
+    /// - **now**: the current local datetime
+    pub fn by_urgency(
+        &self,
+        status: TaskStatus,
+        coefficients: &Urgency,
+        now: DateTime<Local>,
+    ) -> Vec<Task> {
+        let mut scored = self
+            .list(status)
+            .iter()
+            .map(|task| (urgency(task, coefficients, now), task.clone()))
+            .collect::<Vec<(f64, Task)>>();
+        scored.sort_by(|(first, _), (second, _)| second.total_cmp(first));
+        scored.into_iter().map(|(_, task)| task).collect()
+    }
+
+    /// # relabel
+    /// applies `labels` to the active task with id `task_id` i.e., renames
+    /// the task if `labels` has a name, changes its tags and project (see
+    /// `Labels::apply`) and updates its `last_time_modified`.
+    /// # Arguments
+    /// - **task_id**: the id of the task
+    /// - **labels**: the changes to the task, usually parsed from user input
+    /// # Returns
+    /// `Ok(task)` where task is a reference to the changed task if an
+    /// active task with id `task_id` exists else `TodoError::NotFound`.
+    pub fn relabel(&mut self, task_id: TaskId, labels: &Labels) -> TodoResult<&Task> {
+        let task_idx = self.active_index(task_id)?;
+        let target_task = &mut self.active[task_idx];
+        if let Some(name) = &labels.name {
+            target_task.set(name.clone());
+        }
+        labels.apply(target_task);
+        target_task.last_time_modified = Local::now();
+        Ok(target_task)
+    }
+
+    /// # filtered
+    /// returns a copy of the tasks with status `status` that match `filter`,
+    /// in stored order.
+    pub fn filtered(&self, status: TaskStatus, filter: &Filter) -> Vec<Task> {
+        self.list(status)
+            .iter()
+            .filter(|task| filter.matches(task))
+            .cloned()
+            .collect()
+    }
+
+    /// # query
+    /// returns a copy of the tasks that match `query`. Tasks of every status
+    /// are searched if the query has a condition on the status of a task
+    /// e.g., `status:completed`, else only the active tasks are searched.
+    pub fn query(&self, query: &Query) -> Vec<Task> {
+        let statuses = if query.mentions_status() {
+            vec![
+                TaskStatus::Active,
+                TaskStatus::Completed,
+                TaskStatus::Deleted,
+            ]
+        } else {
+            vec![TaskStatus::Active]
+        };
+        statuses
+            .into_iter()
+            .flat_map(|status| self.list(status).iter())
+            .filter(|task| query.matches(task))
+            .cloned()
+            .collect()
+    }
+
+    /// # search
+    /// searches the names of the tasks of every status for `pattern` (see
+    /// `fuzzy::fuzzy_match`), and the notes of the tasks whose names do not
+    /// match (see `fuzzy::match_notes`).
+    /// # Returns
+    /// the matching tasks, the tasks whose names match first with the best
+    /// match first, then the tasks whose notes match. Tasks that match
+    /// equally well are kept in the order active, completed, deleted.
+    pub fn search(&self, pattern: &str) -> Vec<SearchHit> {
+        let mut hits = self
+            .iter()
+            .filter_map(|task| {
+                if let Some(found) = fuzzy_match(pattern, &task.name) {
+                    return Some(SearchHit {
+                        task: task.clone(),
+                        score: found.score,
+                        positions: found.positions,
+                        notes_line: None,
+                    });
+                }
+                let notes_line = match_notes(pattern, task.notes.as_deref()?)?;
+                Some(SearchHit {
+                    task: task.clone(),
+                    score: 0,
+                    positions: Vec::new(),
+                    notes_line: Some(notes_line),
+                })
+            })
+            .collect::<Vec<SearchHit>>();
+        hits.sort_by_key(|hit| (hit.notes_line.is_some(), std::cmp::Reverse(hit.score)));
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_ranks_name_matches_above_notes_matches() {
+        let mut tasks = Tasks::new();
+        let notes_id = tasks.add(String::from("call the bank"));
+        tasks
+            .set_notes(notes_id, Some("ask about the invoice"))
+            .unwrap();
+        let name_id = tasks.add(String::from("send invoice"));
+        tasks.add(String::from("water the plants"));
+
+        let hits = tasks.search("invoice");
+        let ids = hits.iter().map(|hit| hit.task.id).collect::<Vec<TaskId>>();
+        assert_eq!(ids, vec![name_id, notes_id]);
+        assert_eq!(hits[0].notes_line, None);
+        assert_eq!(hits[1].notes_line.as_deref(), Some("ask about the invoice"));
+    }
+
+    #[test]
+    fn purge_ages_tasks_by_when_they_were_deleted() {
+        let mut tasks = Tasks::new();
+        let old_id = tasks.add(String::from("old"));
+        let recent_id = tasks.add(String::from("recent"));
+        tasks.delete(old_id).unwrap();
+        tasks.delete(recent_id).unwrap();
+        let now = Local::now();
+        for task in tasks.list_mut(TaskStatus::Deleted) {
+            // touching a deleted task does not make it younger
+            task.last_time_modified = now;
+        }
+        let old_task = tasks.find_mut(old_id).unwrap();
+        old_task.time_finished = DateTimeOption::DateTime(now - TimeDelta::days(10));
+
+        assert_eq!(tasks.purge(TimeDelta::days(7), now), vec![old_id]);
+        assert!(tasks.find(recent_id).is_some());
+    }
+}
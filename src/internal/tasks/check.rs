@@ -1,36 +1,100 @@
+use crate::internal::error::TodoResult;
+use crate::internal::inputs::prelude::Console;
+use crate::internal::tasks::parent::input_subtask_policy;
 use crate::internal::tasks::prelude::Tasks;
-use crate::internal::tasks::structs::{DateTimeOption, TaskStatus};
+use crate::internal::tasks::structs::{TaskId, TaskStatus};
 use crate::internal::tasks::utils::extract_task;
-use chrono::Local;
 
 /// # check
 /// marks an active task as complete. The program displays all active tasks and prompts the user
-/// to enter the task ID of the task to be marked complete. The task is then extracted, its status
-/// is changed to `TaskStatus::Completed`, it's `time_finished` field is recorded, and it is removed
-/// from the active tasks and appended to the completed tasks
+/// to enter the task ID of the task to be marked complete. The task is then marked as complete
+/// with `Tasks::complete` i.e., its status is changed to `TaskStatus::Completed`, it's
+/// `time_finished` field is recorded, and it is removed from the active tasks and appended to
+/// the completed tasks. If the task has open subtasks the user is asked whether to complete
+/// them too, keep the task open or detach them (see `Tasks::resolve_subtasks`). The tasks
+/// that were waiting on the completed task and are now ready to work on are printed, and
+/// the next instance of a recurring task is added (see `Tasks::recur`).
 /// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the result
 /// - **name_in**: The name of the user
 /// - **tasks**: A `Tasks` struct ref that holds the vector of tasks for active and completed
-/// tasks respectively.
+///   tasks respectively.
 /// # Returns
-/// `Some(())` or Some unit type if the function completes successfully else `None`.
-pub fn check(name_in: &str, tasks: &mut Tasks) -> Option<()> {
-    let active_tasks = &mut tasks.active;
-    let completed_tasks = &mut tasks.completed;
+/// `Ok(task_ids)` where task_ids are the ids of the completed task, of the subtasks it
+/// changed and of any new instances of recurring tasks if the function completes
+/// successfully else a `TodoError`.
+pub fn check(
+    console: &mut dyn Console,
+    name_in: &str,
+    tasks: &mut Tasks,
+) -> TodoResult<Vec<TaskId>> {
     let prompt = "Please enter the Task ID of the task you wish to mark as complete: ";
 
-    // extract the task index of the target task as per the user's selection
-    let (task_idx, _) = extract_task(name_in, prompt, active_tasks)?;
+    // extract the target task as per the user's selection
+    let task_id = extract_task(console, name_in, prompt, &tasks.active)?;
+    check_task(console, tasks, task_id)
+}
 
-    // remove and return target task from 'active tasks'
-    let mut target_task = active_tasks.remove(task_idx);
-    target_task.status = TaskStatus::Completed; // set status to completed
-    target_task.time_finished = DateTimeOption::DateTime(Local::now()); // set current time to
-                                                                        // time_finished.
-    println!(
+/// # check_task
+/// marks the active task with id `task_id` as complete as described in
+/// `check`, asking what to do with its open subtasks, and prints the completed task
+/// and the tasks that are no longer blocked by it (see `Tasks::unblocked_by`).
+/// # Arguments
+/// - **console**: The `Console` the result is printed to
+/// - **tasks**: A mutable ref to Tasks which stores the active and completed tasks.
+/// - **task_id**: The id of the task to complete e.g., as picked from a search.
+/// # Returns
+/// `Ok(task_ids)` where task_ids are the ids of the completed task, of the subtasks it
+/// changed and of any new instances of recurring tasks if the function completes
+/// successfully else a `TodoError`.
+pub fn check_task(
+    console: &mut dyn Console,
+    tasks: &mut Tasks,
+    task_id: TaskId,
+) -> TodoResult<Vec<TaskId>> {
+    let policy = input_subtask_policy(console, tasks, task_id, TaskStatus::Completed)?;
+    let mut changed = tasks.resolve_subtasks(task_id, TaskStatus::Completed, policy)?;
+    let target_task = tasks.complete(task_id)?;
+    console.println(&format!(
         "\nTask with details {} has been marked as complete",
         target_task.show()
-    );
-    completed_tasks.push(target_task);
-    Some(())
+    ));
+    changed.insert(0, task_id);
+
+    // add the next instance of each completed recurring task. Detached
+    // subtasks are among the changed tasks but stay active.
+    for completed_id in changed.clone() {
+        let is_completed = tasks
+            .find(completed_id)
+            .is_some_and(|task| task.status == TaskStatus::Completed);
+        if !is_completed {
+            continue;
+        }
+        if let Some(next_task_id) = tasks.recur(completed_id)? {
+            if let Some(next_task) = tasks.find(next_task_id) {
+                console.println(&format!("Added the next instance {}", next_task.show()));
+            }
+            changed.push(next_task_id);
+        }
+    }
+
+    // report the tasks that were waiting on the completed tasks
+    let mut unblocked = Vec::<TaskId>::new();
+    for completed_id in &changed {
+        for unblocked_id in tasks.unblocked_by(*completed_id) {
+            if !unblocked.contains(&unblocked_id) {
+                unblocked.push(unblocked_id);
+            }
+        }
+    }
+    for unblocked_task in unblocked
+        .iter()
+        .filter_map(|unblocked_id| tasks.find(*unblocked_id))
+    {
+        console.println(&format!(
+            "Task with details {} is now ready to work on",
+            unblocked_task.show()
+        ));
+    }
+    Ok(changed)
 }
@@ -0,0 +1,333 @@
+use crate::internal::error::{TodoError, TodoResult};
+use chrono::prelude::*;
+use chrono::{Days, Duration, LocalResult, Months};
+
+/// the time a task is due at when only its due date is given.
+pub const END_OF_DAY: (u32, u32, u32) = (23, 59, 59);
+
+/// the formats accepted for absolute due dates.
+const DATE_FMTS: [&str; 2] = ["%Y-%m-%d", "%Y/%m/%d"];
+
+/// the words that may appear in a due date without changing its meaning
+/// e.g., the 'at' in 'tomorrow at 5pm'.
+const FILLER_WORDS: [&str; 3] = ["at", "on", "by"];
+
+/// # due_error
+/// creates the `TodoError::InvalidInput` returned for a due date that
+/// could not be understood.
+fn due_error(input: &str) -> TodoError {
+    TodoError::invalid(format!(
+        "Could not understand the due date '{input}'. Try e.g., 'tomorrow 5pm', \
+         'next friday', 'in 3 days' or '2024/12/31 17:00'"
+    ))
+}
+
+/// # from_wall_clock
+/// converts a date and time as shown on a clock in `timezone` to a datetime,
+/// using the offset `timezone` had at that date rather than its current one.
+/// A time that occurs twice because the clocks went back takes its first
+/// occurrence and a time that does not exist because the clocks went forward
+/// is moved forward by an hour.
+/// # Arguments
+/// - **datetime**: the wall clock date and time
+/// - **timezone**: the time zone of the clock e.g., `Local`
+/// # Returns
+/// `Some(datetime)` else `None` if the datetime is out of range.
+pub fn from_wall_clock<Tz: TimeZone>(
+    datetime: NaiveDateTime,
+    timezone: &Tz,
+) -> Option<DateTime<Tz>> {
+    match timezone.from_local_datetime(&datetime) {
+        LocalResult::Single(datetime) => Some(datetime),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest),
+        LocalResult::None => timezone
+            .from_local_datetime(&datetime.checked_add_signed(Duration::hours(1))?)
+            .earliest(),
+    }
+}
+
+/// # utc_timestamp
+/// the serde format of the timestamps of tasks and of the history of the
+/// program. Timestamps are written in UTC e.g., '2024-07-01T09:00:00Z' so
+/// that stored data means the same in every time zone, and read with any
+/// offset before being converted to local time for display.
+pub mod utc_timestamp {
+    use chrono::{DateTime, FixedOffset, Local, Utc};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        datetime: &DateTime<Local>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        datetime.with_timezone(&Utc).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DateTime<Local>, D::Error> {
+        DateTime::<FixedOffset>::deserialize(deserializer)
+            .map(|datetime| datetime.with_timezone(&Local))
+    }
+}
+
+/// # parse_time
+/// parses a time of day such as '5pm', '5:30 pm', '17:00', '17:00:30',
+/// 'noon' or 'midnight'. Any spaces in `time_str` must already be removed.
+/// # Returns
+/// `Some(time)` if `time_str` is a time of day else `None`.
+fn parse_time(time_str: &str) -> Option<NaiveTime> {
+    match time_str {
+        "noon" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return NaiveTime::from_hms_opt(0, 0, 0),
+        _ => (),
+    }
+    for (suffix, offset) in [("am", 0), ("pm", 12)] {
+        if let Some(clock) = time_str.strip_suffix(suffix) {
+            let (hour, minute) = match clock.split_once(':') {
+                Some((hour, minute)) => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+                None => (clock.parse::<u32>().ok()?, 0),
+            };
+            if !(1..=12).contains(&hour) {
+                return None;
+            }
+            return NaiveTime::from_hms_opt(hour % 12 + offset, minute, 0);
+        }
+    }
+    NaiveTime::parse_from_str(time_str, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(time_str, "%H:%M"))
+        .ok()
+}
+
+/// # parse_date
+/// parses the date at the start of `words` i.e., 'today', 'tomorrow',
+/// 'next week', a weekday such as 'friday' or 'fri' (the next such day,
+/// which is today if today is that day), 'next' followed by a weekday (the
+/// next such day after today) or an absolute date such as '2024-12-31' or
+/// '2024/12/31'.
+/// # Returns
+/// `Some((date, rest))` where rest are the words after the date if `words`
+/// starts with a date else `None`.
+fn parse_date<'a>(words: &'a [&'a str], today: NaiveDate) -> Option<(NaiveDate, &'a [&'a str])> {
+    let next_weekday = |weekday: Weekday, skip_today: bool| {
+        let start = if skip_today { today.succ_opt()? } else { today };
+        let days_ahead =
+            (7 + weekday.num_days_from_monday() - start.weekday().num_days_from_monday()) % 7;
+        start.checked_add_days(Days::new(days_ahead as u64))
+    };
+    match words {
+        ["today", rest @ ..] => Some((today, rest)),
+        ["tomorrow", rest @ ..] => Some((today.succ_opt()?, rest)),
+        ["next", "week", rest @ ..] => Some((today.checked_add_days(Days::new(7))?, rest)),
+        ["next", day, rest @ ..] => Some((next_weekday(day.parse::<Weekday>().ok()?, true)?, rest)),
+        [day, rest @ ..] => {
+            if let Ok(weekday) = day.parse::<Weekday>() {
+                return Some((next_weekday(weekday, false)?, rest));
+            }
+            let date = DATE_FMTS
+                .iter()
+                .find_map(|fmt| NaiveDate::parse_from_str(day, fmt).ok())?;
+            Some((date, rest))
+        }
+        [] => None,
+    }
+}
+
+/// # parse_offset
+/// parses a due date relative to `now` i.e., the words after 'in' in
+/// phrases like 'in 3 days', 'in 2 weeks 9am', 'in a month' or 'in 90
+/// minutes'. The units may be shortened to d, w, mo, h and min. Offsets in
+/// days, weeks and months may be followed by a time of day; offsets in
+/// hours and minutes are relative to the current time.
+fn parse_offset(words: &[&str], now: DateTime<Local>, input: &str) -> TodoResult<DateTime<Local>> {
+    let (count, unit, rest) = match words {
+        [count, unit, rest @ ..] => (*count, unit.trim_end_matches('s'), rest),
+        _ => return Err(due_error(input)),
+    };
+    let count = match count {
+        "a" | "an" => 1,
+        count => count.parse::<u32>().map_err(|_| due_error(input))?,
+    };
+    let date = match unit {
+        "hour" | "hr" | "h" | "minute" | "min" => {
+            if !rest.is_empty() {
+                return Err(due_error(input));
+            }
+            let offset = if unit.starts_with('h') {
+                Duration::hours(count as i64)
+            } else {
+                Duration::minutes(count as i64)
+            };
+            return now
+                .checked_add_signed(offset)
+                .ok_or_else(|| due_error(input));
+        }
+        "day" | "d" => now.date_naive().checked_add_days(Days::new(count as u64)),
+        "week" | "w" => now
+            .date_naive()
+            .checked_add_days(Days::new(7 * count as u64)),
+        "month" | "mo" => now.date_naive().checked_add_months(Months::new(count)),
+        _ => None,
+    }
+    .ok_or_else(|| due_error(input))?;
+    at_time(date, rest, input)
+}
+
+/// # at_time
+/// combines `date` with the time of day in `words` (or the end of the day
+/// if `words` is empty) to a local datetime (see `from_wall_clock`), so a
+/// time skipped when the clocks went forward is moved forward by an hour.
+fn at_time(date: NaiveDate, words: &[&str], input: &str) -> TodoResult<DateTime<Local>> {
+    let time = if words.is_empty() {
+        let (hour, minute, second) = END_OF_DAY;
+        NaiveTime::from_hms_opt(hour, minute, second)
+    } else {
+        parse_time(&words.concat())
+    }
+    .ok_or_else(|| due_error(input))?;
+    from_wall_clock(date.and_time(time), &Local)
+        .ok_or_else(|| TodoError::invalid(format!("The due date '{input}' is out of range")))
+}
+
+/// # parse_due
+/// parses a due date entered by the user, either as an absolute date or
+/// as a phrase relative to `now`. Accepted inputs include:
+///
+/// - absolute dates with an optional time e.g., '2024-12-31',
+///   '2024/12/31 17:00' or an RFC 3339 timestamp
+/// - 'today', 'tomorrow' or 'next week' with an optional time
+///   e.g., 'tomorrow 5pm' or 'tomorrow at 17:30'
+/// - weekdays e.g., 'friday', 'fri 9am' or 'next friday'
+/// - offsets e.g., 'in 3 days', 'in 2 weeks', 'in a month' or 'in 2 hours',
+///   or shortened to '+3d', '+2w', '+1mo' or '+2h'
+/// - a time on its own e.g., '5pm', which is due today
+///
+/// Dates given without a time are due at the end of the day.
+/// # Arguments
+/// - **input**: the due date entered by the user
+/// - **now**: the current local datetime relative phrases are resolved against
+/// # Returns
+/// `Ok(datetime)` where datetime is the local due datetime else
+/// `TodoError::InvalidInput` if `input` could not be understood.
+pub fn parse_due(input: &str, now: DateTime<Local>) -> TodoResult<DateTime<Local>> {
+    let input = input.trim();
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(input) {
+        return Ok(datetime.with_timezone(&Local));
+    }
+    let lowered = input.to_lowercase();
+    let words = lowered
+        .split_whitespace()
+        .filter(|word| !FILLER_WORDS.contains(word))
+        .collect::<Vec<&str>>();
+    match words.as_slice() {
+        [] => Err(due_error(input)),
+        ["in", rest @ ..] => parse_offset(rest, now, input),
+        [offset, rest @ ..] if offset.starts_with('+') => {
+            // '+3d' is short for 'in 3 d'
+            let offset = &offset[1..];
+            let unit_idx = offset
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(offset.len());
+            let (count, unit) = offset.split_at(unit_idx);
+            parse_offset(&[&[count, unit], rest].concat(), now, input)
+        }
+        _ => match parse_date(&words, now.date_naive()) {
+            Some((date, rest)) => at_time(date, rest, input),
+            // a time on its own is due today
+            None => at_time(now.date_naive(), &words, input),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wednesday, 31 January 2024 10:00 local time.
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 1, 31, 10, 0, 0).unwrap()
+    }
+
+    fn at(month: u32, day: u32, hour: u32, minute: u32, second: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, month, day)
+            .and_then(|date| date.and_hms_opt(hour, minute, second))
+            .unwrap()
+    }
+
+    fn due(input: &str) -> NaiveDateTime {
+        parse_due(input, now()).unwrap().naive_local()
+    }
+
+    #[test]
+    fn today_and_tomorrow_are_due_at_the_end_of_the_day_or_the_given_time() {
+        assert_eq!(due("today"), at(1, 31, 23, 59, 59));
+        assert_eq!(due("Tomorrow"), at(2, 1, 23, 59, 59));
+        assert_eq!(due("tomorrow 5pm"), at(2, 1, 17, 0, 0));
+        assert_eq!(due("tomorrow at 5:30 pm"), at(2, 1, 17, 30, 0));
+        assert_eq!(due("today noon"), at(1, 31, 12, 0, 0));
+        assert_eq!(due("9pm"), at(1, 31, 21, 0, 0));
+        assert_eq!(due("next week"), at(2, 7, 23, 59, 59));
+    }
+
+    #[test]
+    fn weekdays_are_the_next_such_day() {
+        // today is a wednesday
+        assert_eq!(due("wednesday"), at(1, 31, 23, 59, 59));
+        assert_eq!(due("next wednesday"), at(2, 7, 23, 59, 59));
+        assert_eq!(due("friday"), at(2, 2, 23, 59, 59));
+        assert_eq!(due("fri 9am"), at(2, 2, 9, 0, 0));
+        assert_eq!(due("on monday at 08:15"), at(2, 5, 8, 15, 0));
+        let words = ["next", "sun", "9am"];
+        assert_eq!(
+            parse_date(&words, now().date_naive()),
+            Some((at(2, 4, 0, 0, 0).date(), &words[2..]))
+        );
+    }
+
+    #[test]
+    fn offsets_count_from_now() {
+        assert_eq!(due("+3d"), at(2, 3, 23, 59, 59));
+        assert_eq!(due("in 3 days"), at(2, 3, 23, 59, 59));
+        assert_eq!(due("in 2 weeks"), at(2, 14, 23, 59, 59));
+        assert_eq!(due("+2w 9am"), at(2, 14, 9, 0, 0));
+        assert_eq!(due("in 2 hours"), at(1, 31, 12, 0, 0));
+        assert_eq!(due("+90min"), at(1, 31, 11, 30, 0));
+        assert_eq!(
+            parse_offset(&["an", "hour"], now(), "in an hour")
+                .unwrap()
+                .naive_local(),
+            at(1, 31, 11, 0, 0)
+        );
+    }
+
+    #[test]
+    fn months_end_on_the_last_day_of_shorter_months() {
+        assert_eq!(due("in a month"), at(2, 29, 23, 59, 59));
+        assert_eq!(due("+2mo"), at(3, 31, 23, 59, 59));
+        assert_eq!(due("2024-02-29"), at(2, 29, 23, 59, 59));
+        assert_eq!(due("2024/12/31 17:00"), at(12, 31, 17, 0, 0));
+        assert!(parse_due("2023-02-29", now()).is_err());
+        assert!(parse_due("2024/04/31", now()).is_err());
+    }
+
+    #[test]
+    fn invalid_input_is_rejected() {
+        for input in [
+            "",
+            "someday",
+            "next blursday",
+            "tomorrow 25:00",
+            "tomorrow 13pm",
+            "in 3 fortnights",
+            "in two days",
+            "in 2 hours 5pm",
+            "+3",
+            "+d",
+            "+-3d",
+        ] {
+            assert!(
+                matches!(parse_due(input, now()), Err(TodoError::InvalidInput(_))),
+                "'{input}' was accepted"
+            );
+        }
+    }
+}
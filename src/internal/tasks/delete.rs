@@ -1,28 +1,66 @@
+use crate::internal::error::TodoResult;
+use crate::internal::inputs::prelude::Console;
+use crate::internal::tasks::depends::input_unlink;
+use crate::internal::tasks::parent::input_subtask_policy;
 use crate::internal::tasks::prelude::Tasks;
-use crate::internal::tasks::structs::TaskStatus;
+use crate::internal::tasks::structs::{TaskId, TaskStatus};
 use crate::internal::tasks::utils::extract_task;
 
 /// # delete
 /// deletes a task from the active tasks. Prints out a view of active tasks
 /// to the user and then prompts the user to enter the task id of the target task
-/// to be deleted. The target task status is set to `TaskStatus::Deleted` before
-/// being removed from the active tasks and added to the deleted tasks.
+/// to be deleted. The target task is then deleted with `Tasks::delete` i.e., its
+/// status is set to `TaskStatus::Deleted` before being removed from the active
+/// tasks and added to the deleted tasks. If the task has open subtasks the user is asked
+/// whether to delete them too, keep the task or detach them (see `Tasks::resolve_subtasks`).
+/// If other tasks depend on the task or on the subtasks deleted with it, the user is asked
+/// whether to remove them from their dependencies or keep the task (see
+/// `Tasks::unlink_dependents`).
 /// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the result
 /// - **name_in**: The name of the user
 /// - **tasks**: A mutable ref to Tasks which stores the active, completed and deleted tasks.
 /// # Returns
-/// `Some(())` or Some unit type if the function completes successfully else `None`.
-pub fn delete(name_in: &str, tasks: &mut Tasks) -> Option<()> {
-    let active_tasks = &mut tasks.active;
-    let deleted_tasks = &mut tasks.deleted;
+/// `Ok(task_ids)` where task_ids are the ids of the deleted task and of the subtasks and
+/// dependents it changed if the function completes successfully else a `TodoError`.
+pub fn delete(
+    console: &mut dyn Console,
+    name_in: &str,
+    tasks: &mut Tasks,
+) -> TodoResult<Vec<TaskId>> {
     let prompt = "Please enter the Task ID of the task you wish to delete: ";
-    let (task_idx, _) = extract_task(name_in, prompt, active_tasks)?;
-    let mut target_task = active_tasks.remove(task_idx);
-    target_task.status = TaskStatus::Deleted;
-    println!(
+    let task_id = extract_task(console, name_in, prompt, &tasks.active)?;
+    delete_task(console, tasks, task_id)
+}
+
+/// # delete_task
+/// deletes the active task with id `task_id` as described in `delete`, asking
+/// what to do with the tasks that depend on it and with its open subtasks, and
+/// prints the deleted task.
+/// # Arguments
+/// - **console**: The `Console` the result is printed to
+/// - **tasks**: A mutable ref to Tasks which stores the active and deleted tasks.
+/// - **task_id**: The id of the task to delete e.g., as picked from a search.
+/// # Returns
+/// `Ok(task_ids)` where task_ids are the ids of the deleted task and of the subtasks and
+/// dependents it changed if the function completes successfully else a `TodoError`.
+pub fn delete_task(
+    console: &mut dyn Console,
+    tasks: &mut Tasks,
+    task_id: TaskId,
+) -> TodoResult<Vec<TaskId>> {
+    let policy = input_subtask_policy(console, tasks, task_id, TaskStatus::Deleted)?;
+    let unlink = input_unlink(console, tasks, task_id, policy)?;
+    let deleted_ids = tasks.deleted_with(task_id, policy);
+    let mut changed = tasks.resolve_subtasks(task_id, TaskStatus::Deleted, policy)?;
+    if unlink {
+        changed.extend(tasks.unlink_dependents(&deleted_ids));
+    }
+    let target_task = tasks.delete(task_id)?;
+    console.println(&format!(
         "\nTask with details {} has been deleted",
         target_task.show()
-    );
-    deleted_tasks.push(target_task);
-    Some(())
+    ));
+    changed.insert(0, task_id);
+    Ok(changed)
 }
@@ -0,0 +1,253 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::tasks::structs::{Task, TaskId, TaskStatus, Tasks};
+use crate::internal::tasks::subtasks::SubtaskPolicy;
+use chrono::Local;
+
+/// # parse_dependencies
+/// parses the dependency changes entered by the user as Task IDs separated
+/// by spaces or commas, where an ID adds a prerequisite and an ID starting
+/// with '-' removes one e.g., '7 9 -3'.
+/// # Returns
+/// `Ok((added, removed))` where added and removed are the ids of the
+/// prerequisites to add and remove else `TodoError::InvalidInput` if a
+/// word is not a Task ID.
+pub fn parse_dependencies(input: &str) -> TodoResult<(Vec<TaskId>, Vec<TaskId>)> {
+    let mut added = Vec::<TaskId>::new();
+    let mut removed = Vec::<TaskId>::new();
+    for word in input.split([' ', ',']).filter(|word| !word.is_empty()) {
+        let (ids, id_str) = match word.strip_prefix('-') {
+            Some(id_str) => (&mut removed, id_str),
+            None => (&mut added, word.strip_prefix('+').unwrap_or(word)),
+        };
+        let task_id = id_str
+            .parse::<TaskId>()
+            .ok()
+            .filter(|task_id| *task_id > 0)
+            .ok_or_else(|| TodoError::invalid(format!("'{word}' is not a Task ID")))?;
+        ids.push(task_id);
+    }
+    Ok((added, removed))
+}
+
+/// The operations on the dependencies between tasks i.e., the `depends_on`
+/// of each task. A task is blocked while any of its prerequisites is still
+/// active and ready to work on once they are all completed.
+impl Tasks {
+    /// # change_dependencies
+    /// adds and removes prerequisites of the active task with id `task_id`
+    /// and updates its `last_time_modified`. Nothing is changed if any
+    /// prerequisite cannot be added.
+    /// # Arguments
+    /// - **task_id**: the id of the task
+    /// - **added**: the ids of the tasks `task_id` will depend on
+    /// - **removed**: the ids of the tasks `task_id` will no longer depend on
+    /// # Returns
+    /// `Ok(task)` where task is a reference to the changed task else
+    /// `TodoError::NotFound` if the task is not active or an added
+    /// prerequisite does not exist, or `TodoError::InvalidInput` if an added
+    /// prerequisite is deleted or would create a cycle of dependencies.
+    pub fn change_dependencies(
+        &mut self,
+        task_id: TaskId,
+        added: &[TaskId],
+        removed: &[TaskId],
+    ) -> TodoResult<&Task> {
+        if !self.active.iter().any(|task| task.id == task_id) {
+            return Err(TodoError::NotFound(task_id));
+        }
+        for &prerequisite_id in added {
+            let prerequisite = self
+                .find(prerequisite_id)
+                .ok_or(TodoError::NotFound(prerequisite_id))?;
+            if prerequisite.status == TaskStatus::Deleted {
+                return Err(TodoError::invalid(format!(
+                    "Task {prerequisite_id} is deleted and cannot be a prerequisite"
+                )));
+            }
+            if prerequisite_id == task_id || self.prerequisites(prerequisite_id).contains(&task_id)
+            {
+                return Err(TodoError::invalid(format!(
+                    "Task {task_id} cannot depend on task {prerequisite_id} as task \
+                     {prerequisite_id} already depends on task {task_id}"
+                )));
+            }
+        }
+        let target_task = self
+            .active
+            .iter_mut()
+            .find(|task| task.id == task_id)
+            .ok_or(TodoError::NotFound(task_id))?;
+        for prerequisite_id in removed {
+            target_task.depends_on.remove(prerequisite_id);
+        }
+        target_task.depends_on.extend(added);
+        target_task.last_time_modified = Local::now();
+        Ok(target_task)
+    }
+
+    /// # prerequisites
+    /// returns the ids of the tasks the task with id `task_id` depends on,
+    /// the tasks they depend on and so on.
+    pub fn prerequisites(&self, task_id: TaskId) -> Vec<TaskId> {
+        let mut prerequisites = Vec::<TaskId>::new();
+        let mut pending = vec![task_id];
+        while let Some(next_id) = pending.pop() {
+            let Some(task) = self.find(next_id) else {
+                continue;
+            };
+            for &prerequisite_id in &task.depends_on {
+                if !prerequisites.contains(&prerequisite_id) {
+                    prerequisites.push(prerequisite_id);
+                    pending.push(prerequisite_id);
+                }
+            }
+        }
+        prerequisites
+    }
+
+    /// # blockers
+    /// returns the ids of the prerequisites of `task` that are not completed.
+    /// A deleted prerequisite still blocks the task until it is removed from
+    /// its dependencies (see `Tasks::unlink_dependents`).
+    pub fn blockers(&self, task: &Task) -> Vec<TaskId> {
+        task.depends_on
+            .iter()
+            .copied()
+            .filter(|prerequisite_id| {
+                self.find(*prerequisite_id)
+                    .is_some_and(|prerequisite| prerequisite.status != TaskStatus::Completed)
+            })
+            .collect()
+    }
+
+    /// # ready
+    /// returns a copy of the active tasks that are not blocked i.e., all of
+    /// whose prerequisites are completed, in stored order.
+    pub fn ready(&self) -> Vec<Task> {
+        self.active
+            .iter()
+            .filter(|task| self.blockers(task).is_empty())
+            .cloned()
+            .collect()
+    }
+
+    /// # blocked
+    /// returns a copy of the active tasks that are waiting on at least one
+    /// prerequisite, in stored order.
+    pub fn blocked(&self) -> Vec<Task> {
+        self.active
+            .iter()
+            .filter(|task| !self.blockers(task).is_empty())
+            .cloned()
+            .collect()
+    }
+
+    /// # dependents
+    /// returns the ids of the active tasks that depend on the task with id
+    /// `task_id`.
+    pub fn dependents(&self, task_id: TaskId) -> Vec<TaskId> {
+        self.active
+            .iter()
+            .filter(|task| task.depends_on.contains(&task_id))
+            .map(|task| task.id)
+            .collect()
+    }
+
+    /// # unblocked_by
+    /// returns the ids of the active tasks that depend on the task with id
+    /// `task_id` and are no longer blocked, e.g., after it was completed.
+    pub fn unblocked_by(&self, task_id: TaskId) -> Vec<TaskId> {
+        self.active
+            .iter()
+            .filter(|task| task.depends_on.contains(&task_id) && self.blockers(task).is_empty())
+            .map(|task| task.id)
+            .collect()
+    }
+
+    /// # deleted_with
+    /// returns the ids of the tasks deleting the task with id `task_id`
+    /// deletes i.e., the task itself and with `SubtaskPolicy::Cascade` also
+    /// its open subtasks (see `Tasks::resolve_subtasks`).
+    pub fn deleted_with(&self, task_id: TaskId, policy: SubtaskPolicy) -> Vec<TaskId> {
+        let mut deleted_ids = vec![task_id];
+        if policy == SubtaskPolicy::Cascade {
+            deleted_ids.extend(self.open_subtasks(task_id));
+        }
+        deleted_ids
+    }
+
+    /// # dependents_of
+    /// returns the ids of the active tasks that depend on any of the tasks
+    /// with ids `task_ids` and are not among them e.g., the tasks left waiting
+    /// on the tasks a cascading delete deletes (see `Tasks::deleted_with`).
+    pub fn dependents_of(&self, task_ids: &[TaskId]) -> Vec<TaskId> {
+        self.active
+            .iter()
+            .filter(|task| !task_ids.contains(&task.id))
+            .filter(|task| {
+                task_ids
+                    .iter()
+                    .any(|task_id| task.depends_on.contains(task_id))
+            })
+            .map(|task| task.id)
+            .collect()
+    }
+
+    /// # unlink_dependents
+    /// removes the tasks with ids `task_ids` from the dependencies of the
+    /// active tasks that depend on them, e.g., before they are deleted, and
+    /// updates their `last_time_modified`.
+    /// # Returns
+    /// the ids of the changed tasks.
+    pub fn unlink_dependents(&mut self, task_ids: &[TaskId]) -> Vec<TaskId> {
+        let mut unlinked = Vec::<TaskId>::new();
+        for task in self.active.iter_mut().filter(|task| {
+            task_ids
+                .iter()
+                .any(|task_id| task.depends_on.contains(task_id))
+        }) {
+            task.depends_on
+                .retain(|prerequisite_id| !task_ids.contains(prerequisite_id));
+            task.last_time_modified = Local::now();
+            unlinked.push(task.id);
+        }
+        unlinked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a parent with one subtask which a third task depends on.
+    fn parent_child_other() -> Tasks {
+        let mut tasks = Tasks::new();
+        let parent_id = tasks.add(String::from("parent"));
+        let child_id = tasks.add(String::from("child"));
+        let other_id = tasks.add(String::from("other"));
+        tasks.set_parent(child_id, Some(parent_id)).unwrap();
+        tasks
+            .change_dependencies(other_id, &[child_id], &[])
+            .unwrap();
+        tasks
+    }
+
+    #[test]
+    fn cascading_delete_includes_the_dependents_of_subtasks() {
+        let tasks = parent_child_other();
+        assert!(tasks
+            .dependents_of(&tasks.deleted_with(1, SubtaskPolicy::Block))
+            .is_empty());
+        let deleted_ids = tasks.deleted_with(1, SubtaskPolicy::Cascade);
+        assert_eq!(deleted_ids, vec![1, 2]);
+        assert_eq!(tasks.dependents_of(&deleted_ids), vec![3]);
+    }
+
+    #[test]
+    fn unlinking_removes_every_deleted_task() {
+        let mut tasks = parent_child_other();
+        let deleted_ids = tasks.deleted_with(1, SubtaskPolicy::Cascade);
+        assert_eq!(tasks.unlink_dependents(&deleted_ids), vec![3]);
+        assert!(tasks.find(3).unwrap().depends_on.is_empty());
+    }
+}
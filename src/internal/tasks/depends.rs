@@ -0,0 +1,80 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::inputs::prelude::Console;
+use crate::internal::tasks::dependencies::parse_dependencies;
+use crate::internal::tasks::structs::{TaskId, Tasks};
+use crate::internal::tasks::subtasks::SubtaskPolicy;
+use crate::internal::tasks::utils::extract_task;
+
+/// # input_unlink
+/// asks the user what to do with the active tasks that depend on the task
+/// with id `task_id` or on the subtasks deleted with it before it is deleted,
+/// which is either to remove them from their dependencies or to keep the task.
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user
+/// - **tasks**: A ref to Tasks which stores the task and its dependents
+/// - **task_id**: The id of the task being deleted
+/// - **policy**: what is done with the open subtasks of the task (see `Tasks::deleted_with`)
+/// # Returns
+/// `Ok(unlink)` where unlink is `true` if the deleted tasks should be removed
+/// from the dependencies of their dependents and `false` if they have none,
+/// else `TodoError::InvalidInput` if the user chose to keep the task.
+pub fn input_unlink(
+    console: &mut dyn Console,
+    tasks: &Tasks,
+    task_id: TaskId,
+    policy: SubtaskPolicy,
+) -> TodoResult<bool> {
+    let dependents = tasks.dependents_of(&tasks.deleted_with(task_id, policy));
+    if dependents.is_empty() {
+        return Ok(false);
+    }
+    let dependent_ids = dependents
+        .iter()
+        .map(|dependent_id| dependent_id.to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+    let options = ["Remove it from their dependencies", "Keep the task"];
+    let prompt = format!(
+        "Tasks {dependent_ids} depend on task {task_id} or its subtasks. Select an option: "
+    );
+    match console.input_option(&prompt, &options, "Dependents")? {
+        "Remove it from their dependencies" => Ok(true),
+        _ => Err(TodoError::invalid(format!(
+            "Task {task_id} was kept as tasks {dependent_ids} depend on it"
+        ))),
+    }
+}
+
+/// # set_dependencies
+/// adds and removes the prerequisites of an active task. A view of the active
+/// tasks is printed out to the user and the user is prompted to enter the task
+/// ID of the target task before being prompted to enter the Task IDs it
+/// depends on, where an ID starting with '-' removes a prerequisite (see
+/// `Tasks::change_dependencies`).
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the result
+/// - **name_in**: The name of the user
+/// - **tasks**: A mutable ref to Tasks which stores the active tasks.
+/// # Returns
+/// `Ok(task_id)` where task_id is the id of the changed task if the function
+/// completes successfully else a `TodoError`.
+pub fn set_dependencies(
+    console: &mut dyn Console,
+    name_in: &str,
+    tasks: &mut Tasks,
+) -> TodoResult<TaskId> {
+    let prompt = "Please enter the Task ID of the task whose dependencies you wish to set: ";
+    let task_id = extract_task(console, name_in, prompt, &tasks.active)?;
+    let dependency_input = console
+        .input_str("Enter the Task IDs it depends on, and -id to remove one e.g., '7 9 -3': ")?;
+    let (added, removed) = parse_dependencies(&dependency_input)?;
+    if added.is_empty() && removed.is_empty() {
+        return Err(TodoError::invalid("Nothing to change was entered"));
+    }
+    let target_task = tasks.change_dependencies(task_id, &added, &removed)?;
+    console.println(&format!(
+        "\nTask with details {} has been updated",
+        target_task.show()
+    ));
+    Ok(task_id)
+}
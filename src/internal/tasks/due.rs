@@ -0,0 +1,54 @@
+use crate::internal::error::TodoResult;
+use crate::internal::inputs::prelude::Console;
+use crate::internal::tasks::dates::parse_due;
+use crate::internal::tasks::structs::{DateTimeOption, TaskId, Tasks};
+use crate::internal::tasks::utils::extract_task;
+use chrono::Local;
+
+/// the prompt used to ask the user for a due date.
+const DUE_PROMPT: &str = "Enter a due date e.g., 'tomorrow 5pm', 'next friday', 'in 3 days' \
+                          or '2024/12/31 17:00' (leave empty for no due date): ";
+
+/// # input_due
+/// prompts the user to enter a due date (see `parse_due` for the accepted
+/// inputs). An empty input means the task has no due date.
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user
+/// # Returns
+/// `Ok(due)` where due is the entered due date or `DateTimeOption::None` if
+/// the input was empty, else a `TodoError` if the input could not be read or
+/// understood.
+pub fn input_due(console: &mut dyn Console) -> TodoResult<DateTimeOption> {
+    let due_input = console.input_str(DUE_PROMPT)?;
+    if due_input.is_empty() {
+        return Ok(DateTimeOption::None);
+    }
+    Ok(DateTimeOption::DateTime(parse_due(
+        &due_input,
+        Local::now(),
+    )?))
+}
+
+/// # set_due
+/// sets or removes the due date of an active task. A view of the active tasks
+/// is printed out to the user and the user is prompted to enter the task ID of
+/// the target task before being prompted to enter its due date (see
+/// `Tasks::set_due`).
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the result
+/// - **name_in**: The name of the user
+/// - **tasks**: A mutable ref to Tasks which stores the active tasks.
+/// # Returns
+/// `Ok(task_id)` where task_id is the id of the changed task if the function
+/// completes successfully else a `TodoError`.
+pub fn set_due(console: &mut dyn Console, name_in: &str, tasks: &mut Tasks) -> TodoResult<TaskId> {
+    let prompt = "Please enter the Task ID of the task whose due date you wish to set: ";
+    let task_id = extract_task(console, name_in, prompt, &tasks.active)?;
+    let due = input_due(console)?;
+    let target_task = tasks.set_due(task_id, due)?;
+    console.println(&format!(
+        "\nTask with details {} has been updated",
+        target_task.show()
+    ));
+    Ok(task_id)
+}
@@ -1,26 +1,53 @@
-use crate::internal::inputs::prelude::input_str;
-use crate::internal::tasks::structs::Task;
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::inputs::prelude::Console;
+use crate::internal::tasks::labels::Labels;
+use crate::internal::tasks::structs::{TaskId, Tasks};
 use crate::internal::tasks::utils::extract_task;
 
 /// # edit
 /// edits a task's name and changes the task name to a new name specified by the user.
 /// A view of the active tasks is printed out to the user and the user is prompted to enter
 /// the task ID of the target task to be edited before being prompted to enter a new name
-/// for the task.
+/// for the task (see `Tasks::relabel`). The new name may contain `+tag`, `-tag` and
+/// `project:x` words which add or remove tags and set the project of the task, and
+/// the name is kept if only such words are entered (see `Labels::parse_changes`).
 /// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the result
 /// - **name_in**: The name of the user
-/// - **active_tasks**: A mutable reference to a list of active tasks.
+/// - **tasks**: A mutable ref to Tasks which stores the active tasks.
 /// # Returns
-/// `Some(())` or Some unit type if the function completes successfully else `None`.
-pub fn edit(name_in: &str, active_tasks: &mut Vec<Task>) -> Option<()> {
+/// `Ok(task_id)` where task_id is the id of the edited task if the function completes
+/// successfully else a `TodoError`.
+pub fn edit(console: &mut dyn Console, name_in: &str, tasks: &mut Tasks) -> TodoResult<TaskId> {
     let prompt = "Please enter the Task ID of the task you wish to edit: ";
-    let (_, target_task) = extract_task(name_in, prompt, active_tasks)?;
-    let new_task_name = input_str("Enter the new task name: ")?;
-    let old_task_name = target_task.name.clone();
-    target_task.set(new_task_name);
-    println!(
-        "\nTask with ID '{}' and old Name '{}' has been edited to new name '{}'",
-        target_task.id, old_task_name, target_task.name,
-    );
-    Some(())
+    let task_id = extract_task(console, name_in, prompt, &tasks.active)?;
+    edit_task(console, tasks, task_id)
+}
+
+/// # edit_task
+/// prompts the user to enter the new name and/or label changes of the active
+/// task with id `task_id` and applies them as described in `edit`.
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the result
+/// - **tasks**: A mutable ref to Tasks which stores the active tasks.
+/// - **task_id**: The id of the task to edit e.g., as picked from a search.
+/// # Returns
+/// `Ok(task_id)` if the function completes successfully else a `TodoError`.
+pub fn edit_task(
+    console: &mut dyn Console,
+    tasks: &mut Tasks,
+    task_id: TaskId,
+) -> TodoResult<TaskId> {
+    let edit_input = console.input_str("Enter the new task name and/or +tag, -tag, project:x: ")?;
+    if edit_input.is_empty() {
+        return Err(TodoError::invalid("Nothing to change was entered"));
+    }
+    let labels = Labels::parse_changes(&edit_input)?;
+    let edited_task = tasks.relabel(task_id, &labels)?;
+    console.println(&format!(
+        "\nTask with ID '{}' has been edited to {}",
+        task_id,
+        edited_task.show(),
+    ));
+    Ok(task_id)
 }
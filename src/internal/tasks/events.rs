@@ -0,0 +1,487 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::paths::get_file_path;
+use crate::internal::tasks::dates::utc_timestamp;
+use crate::internal::tasks::structs::{Task, TaskId, Tasks, DATE_FMT};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+
+/// the append-only JSON Lines file recording every change to every task.
+pub const EVENTS_FILE: &str = "events.jsonl";
+
+/// the action recorded for the tasks that existed before the event log was
+/// started, and for the tasks imported from the legacy text files.
+pub const IMPORT_ACTION: &str = "import";
+
+/// the action recorded for the tasks that shared a Task ID when the ids were
+/// repaired (see `Tasks::repair_ids`).
+pub const REPAIR_ACTION: &str = "repair ids";
+
+/// the actor recorded for the changes the program makes on its own e.g.,
+/// when data written by older versions is migrated.
+const PROGRAM_ACTOR: &str = "todolist";
+
+/// the field that changes with every other field and is therefore left out
+/// when a change is shown.
+const MODIFIED_FIELD: &str = "last_time_modified";
+
+/// # FieldChange
+/// a change to one stored field of a task.
+///
+/// # Members
+/// - **field**: the name of the field in the stored record e.g., 'name' or 'due'
+/// - **old**: the stored value of the field before the change
+/// - **new**: the stored value of the field after the change
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// enum `EventKind`
+/// what happened to a task.
+///
+/// # Variants
+/// - **Created**: the task was added, holding the whole new task
+/// - **Changed**: fields of the task changed e.g., it was renamed, completed or given a due date
+/// - **Removed**: the task was removed for good e.g., it was purged or its adding was undone
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum EventKind {
+    Created { task: Box<Task> },
+    Changed { changes: Vec<FieldChange> },
+    Removed,
+}
+
+/// # Event
+/// one entry of the event log.
+///
+/// # Members
+/// - **time**: when the change was made
+/// - **actor**: who made the change i.e., the name of the user
+/// - **action**: the operation that made the change e.g., 'Mark task as complete' or 'done 12'
+/// - **task_id**: the id of the changed task
+/// - **kind**: what happened to the task
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Event {
+    #[serde(with = "utc_timestamp")]
+    pub time: DateTime<Local>,
+    pub actor: String,
+    pub action: String,
+    pub task_id: TaskId,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+/// # to_record
+/// converts a task to the JSON object it is stored as.
+fn to_record(task: &Task) -> TodoResult<Map<String, Value>> {
+    match serde_json::to_value(task) {
+        Ok(Value::Object(record)) => Ok(record),
+        _ => Err(TodoError::Storage(format!(
+            "Could not serialize task {}",
+            task.id
+        ))),
+    }
+}
+
+/// # show_value
+/// converts a stored value to a String for display. Dates are shown in the
+/// format used by `Task::show`, lists are joined by commas and a missing
+/// value is shown as 'none'. Newlines are shown as '\\n' so that a change to
+/// multi-line notes is shown on one line.
+fn show_value(value: &Value) -> String {
+    match value {
+        Value::Null => "none".to_string(),
+        Value::String(text) => match DateTime::parse_from_rfc3339(text) {
+            Ok(datetime) => datetime.with_timezone(&Local).format(DATE_FMT).to_string(),
+            Err(_) => format!("'{}'", text.replace('\n', "\\n")),
+        },
+        Value::Array(values) if values.is_empty() => "none".to_string(),
+        Value::Array(values) => values
+            .iter()
+            .map(show_value)
+            .collect::<Vec<String>>()
+            .join(", "),
+        Value::Object(_) => value.to_string(),
+        other => other.to_string(),
+    }
+}
+
+impl FieldChange {
+    /// # show
+    /// converts a `FieldChange` to a String for display e.g.,
+    /// "renamed from 'write docs' to 'write the docs'" or "timer started".
+    pub fn show(&self) -> String {
+        let (old, new) = (show_value(&self.old), show_value(&self.new));
+        let work_len = |value: &Value| value.as_array().map_or(0, Vec::len);
+        match self.field.as_str() {
+            "name" => format!("renamed from {old} to {new}"),
+            "work" if work_len(&self.new) > work_len(&self.old) => "timer started".to_string(),
+            "work" if work_len(&self.new) == work_len(&self.old) => "timer stopped".to_string(),
+            field => format!("{} changed from {old} to {new}", field.replace('_', " ")),
+        }
+    }
+}
+
+impl Event {
+    /// # show
+    /// converts what happened in an `Event` to a String for display e.g.,
+    /// "created 'write docs'" or "status changed from 'Active' to 'Completed'".
+    pub fn show(&self) -> String {
+        match &self.kind {
+            EventKind::Created { task } if self.action == IMPORT_ACTION => {
+                format!(
+                    "imported '{}' as {}",
+                    task.name,
+                    task.status.show().to_lowercase()
+                )
+            }
+            EventKind::Created { task } if self.action == REPAIR_ACTION => {
+                format!("recorded '{}' after its Task ID was repaired", task.name)
+            }
+            EventKind::Created { task } => format!("created '{}'", task.name),
+            EventKind::Changed { changes } => {
+                let shown = changes
+                    .iter()
+                    .filter(|change| change.field != MODIFIED_FIELD)
+                    .map(FieldChange::show)
+                    .collect::<Vec<String>>();
+                if shown.is_empty() {
+                    "modified".to_string()
+                } else {
+                    shown.join("; ")
+                }
+            }
+            EventKind::Removed => "removed permanently".to_string(),
+        }
+    }
+}
+
+/// # diff
+/// returns the event recording the change of a task from `before` to
+/// `after`, or `None` if it did not change.
+fn diff(before: Option<&Task>, after: Option<&Task>) -> TodoResult<Option<EventKind>> {
+    Ok(match (before, after) {
+        (None, None) => None,
+        (None, Some(task)) => Some(EventKind::Created {
+            task: Box::new(task.clone()),
+        }),
+        (Some(_), None) => Some(EventKind::Removed),
+        (Some(before), Some(after)) => {
+            let (old_record, new_record) = (to_record(before)?, to_record(after)?);
+            let changes = new_record
+                .iter()
+                .filter(|(field, new)| old_record.get(*field) != Some(*new))
+                .map(|(field, new)| FieldChange {
+                    field: field.clone(),
+                    old: old_record.get(field).cloned().unwrap_or(Value::Null),
+                    new: new.clone(),
+                })
+                .collect::<Vec<FieldChange>>();
+            if changes.is_empty() {
+                None
+            } else {
+                Some(EventKind::Changed { changes })
+            }
+        }
+    })
+}
+
+/// # load_events
+/// reads every event from 'events.jsonl'. If the file has not been created
+/// yet, no events are returned.
+/// # Returns
+/// `Ok(events)` in the order they were recorded else `TodoError::Io` if the
+/// file cannot be read or `TodoError::Parse` naming the line that cannot be
+/// parsed.
+pub fn load_events() -> TodoResult<Vec<Event>> {
+    let events_path = get_file_path(EVENTS_FILE);
+    if !events_path.exists() {
+        return Ok(Vec::new());
+    }
+    let events_str =
+        fs::read_to_string(&events_path).map_err(|e| TodoError::io(&events_path, e))?;
+    let mut events = Vec::<Event>::new();
+    for (line_idx, line) in events_str.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event = serde_json::from_str::<Event>(line).map_err(|e| TodoError::Parse {
+            source: EVENTS_FILE.to_string(),
+            line: line_idx + 1,
+            field: None,
+            message: e.to_string(),
+        })?;
+        events.push(event);
+    }
+    Ok(events)
+}
+
+/// # log_events
+/// appends an event to 'events.jsonl' for every task with an id in `task_ids`
+/// that changed from `before` to `after`. The first time events are logged,
+/// every task in `before` is logged as created by an 'import' first so that
+/// the log holds the whole history of the tasks (see `replay`).
+/// # Arguments
+/// - **actor**: who made the change
+/// - **action**: the operation that made the change
+/// - **before**: a copy of the tasks taken before the operation
+/// - **after**: the tasks after the operation
+/// - **task_ids**: the ids of the tasks the operation changed
+/// # Returns
+/// `Ok(())` if the function completes successfully else a `TodoError`.
+pub fn log_events(
+    actor: &str,
+    action: &str,
+    before: &Tasks,
+    after: &Tasks,
+    task_ids: &[TaskId],
+) -> TodoResult<()> {
+    let events_path = get_file_path(EVENTS_FILE);
+    let time = Local::now();
+    let mut events = Vec::<Event>::new();
+    if !events_path.exists() {
+        for task in before.iter() {
+            events.push(Event {
+                time,
+                actor: actor.to_string(),
+                action: IMPORT_ACTION.to_string(),
+                task_id: task.id,
+                kind: EventKind::Created {
+                    task: Box::new(task.clone()),
+                },
+            });
+        }
+    }
+    let mut logged_ids = Vec::<TaskId>::new();
+    for &task_id in task_ids {
+        if logged_ids.contains(&task_id) {
+            continue;
+        }
+        logged_ids.push(task_id);
+        if let Some(kind) = diff(before.find(task_id), after.find(task_id))? {
+            events.push(Event {
+                time,
+                actor: actor.to_string(),
+                action: action.to_string(),
+                task_id,
+                kind,
+            });
+        }
+    }
+    append_events(&events)
+}
+
+/// # log_created
+/// appends an event to 'events.jsonl' recording each task with an id in
+/// `task_ids` as created by `action`, replacing what the log held for that
+/// id. This records the changes the program makes when it loads the tasks,
+/// such as the tasks imported from the legacy text files or the ids given
+/// by `Tasks::repair_ids`, so that `replay` gives the same tasks as the
+/// storage backend. Nothing is logged if the log has not been started yet,
+/// as the first logged change records every task (see `log_events`).
+/// # Arguments
+/// - **action**: the operation that made the change e.g., `IMPORT_ACTION`
+/// - **tasks**: the tasks after the change
+/// - **task_ids**: the ids of the tasks to be recorded
+/// # Returns
+/// `Ok(())` if the function completes successfully else a `TodoError`.
+pub fn log_created(action: &str, tasks: &Tasks, task_ids: &[TaskId]) -> TodoResult<()> {
+    if !get_file_path(EVENTS_FILE).exists() {
+        return Ok(());
+    }
+    let time = Local::now();
+    let mut events = Vec::<Event>::new();
+    for &task_id in task_ids {
+        let logged = events.iter().any(|event| event.task_id == task_id);
+        if let Some(task) = tasks.find(task_id).filter(|_| !logged) {
+            events.push(Event {
+                time,
+                actor: PROGRAM_ACTOR.to_string(),
+                action: action.to_string(),
+                task_id,
+                kind: EventKind::Created {
+                    task: Box::new(task.clone()),
+                },
+            });
+        }
+    }
+    append_events(&events)
+}
+
+/// # append_events
+/// appends `events` to 'events.jsonl' as one write so that they are logged
+/// together.
+/// # Returns
+/// `Ok(())` if the function completes successfully else a `TodoError`.
+fn append_events(events: &[Event]) -> TodoResult<()> {
+    if events.is_empty() {
+        return Ok(());
+    }
+    let events_path = get_file_path(EVENTS_FILE);
+    let mut lines = String::new();
+    for event in events {
+        let line = serde_json::to_string(event)
+            .map_err(|e| TodoError::Storage(format!("Could not serialize {EVENTS_FILE}: {e}")))?;
+        lines.push_str(&line);
+        lines.push('\n');
+    }
+    let io_error = |e| TodoError::io(&events_path, e);
+    if let Some(parent_path) = events_path.parent() {
+        fs::create_dir_all(parent_path).map_err(io_error)?;
+    }
+    let mut events_file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&events_path)
+        .map_err(io_error)?;
+    events_file.write_all(lines.as_bytes()).map_err(io_error)?;
+    events_file.sync_all().map_err(io_error)
+}
+
+/// # replay
+/// derives the tasks from `events` by applying each event in order to no
+/// tasks, giving the same tasks as the storage backend holds. Every change
+/// is logged, including the tasks imported by `migrate` and the ids given
+/// by `Tasks::repair_ids` (see `log_created`).
+/// # Arguments
+/// - **events**: the events to apply, usually read with `load_events`
+/// # Returns
+/// `Ok(Tasks)` if every event could be applied else `TodoError::Corrupt` if
+/// an event changes a task that does not exist or cannot be applied.
+pub fn replay(events: &[Event]) -> TodoResult<Tasks> {
+    let mut tasks = Tasks::new();
+    for event in events {
+        let task_id = event.task_id;
+        match &event.kind {
+            EventKind::Created { task } => {
+                tasks.restore_state(task_id, Some(task.as_ref().clone()))
+            }
+            EventKind::Changed { changes } => {
+                let task = tasks.find(task_id).ok_or_else(|| {
+                    TodoError::Corrupt(format!(
+                        "{EVENTS_FILE} changes task {task_id} before it was created"
+                    ))
+                })?;
+                let mut record = to_record(task)?;
+                for change in changes {
+                    record.insert(change.field.clone(), change.new.clone());
+                }
+                let task = serde_json::from_value::<Task>(Value::Object(record)).map_err(|e| {
+                    TodoError::Corrupt(format!("Could not apply a change to task {task_id}: {e}"))
+                })?;
+                tasks.restore_state(task_id, Some(task));
+            }
+            EventKind::Removed => tasks.restore_state(task_id, None),
+        }
+    }
+    Ok(tasks)
+}
+
+/// # task_events
+/// returns the events of the task with id `task_id` in the order they were
+/// recorded.
+/// # Returns
+/// `Ok(events)` else `TodoError::NotFound` if no event was recorded for the
+/// task, or the error reading the events failed with.
+pub fn task_events(task_id: TaskId) -> TodoResult<Vec<Event>> {
+    let events = load_events()?
+        .into_iter()
+        .filter(|event| event.task_id == task_id)
+        .collect::<Vec<Event>>();
+    if events.is_empty() {
+        return Err(TodoError::NotFound(task_id));
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::paths::with_test_dir;
+    use crate::internal::storage::backend::Storage;
+    use crate::internal::storage::memory::MemoryStorage;
+    use crate::internal::tasks::format::to_line;
+    use crate::internal::tasks::load::load_tasks;
+    use crate::internal::tasks::migrate::migrate;
+
+    /// the stored records of `tasks` in a fixed order.
+    fn records(tasks: &Tasks) -> Vec<String> {
+        let mut records = tasks
+            .iter()
+            .map(|task| to_line(task).unwrap())
+            .collect::<Vec<String>>();
+        records.sort();
+        records
+    }
+
+    /// runs `operation` on `tasks` and logs the change as the menu does.
+    fn change(tasks: &mut Tasks, operation: impl FnOnce(&mut Tasks) -> TaskId) {
+        let before = tasks.clone();
+        let task_id = operation(tasks);
+        log_events("bob", "test", &before, tasks, &[task_id]).unwrap();
+    }
+
+    /// the tasks rebuilt from the event log.
+    fn replayed() -> Tasks {
+        replay(&load_events().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn replay_gives_the_logged_tasks() {
+        with_test_dir(|_| {
+            let mut tasks = Tasks::new();
+            change(&mut tasks, |tasks| tasks.add(String::from("first")));
+            change(&mut tasks, |tasks| tasks.add(String::from("second")));
+            change(&mut tasks, |tasks| tasks.add(String::from("third")));
+            change(&mut tasks, |tasks| tasks.complete(1).unwrap().id);
+            change(&mut tasks, |tasks| tasks.delete(2).unwrap().id);
+            change(&mut tasks, |tasks| tasks.take(3).unwrap().id);
+
+            assert_eq!(records(&replayed()), records(&tasks));
+        });
+    }
+
+    #[test]
+    fn replay_gives_the_repaired_ids() {
+        with_test_dir(|_| {
+            let mut tasks = Tasks::new();
+            change(&mut tasks, |tasks| tasks.add(String::from("first")));
+            // a task sharing the id of a logged task, as older versions stored
+            let mut stored = tasks.clone();
+            stored.push(Task::from_name_id(String::from("second"), 1));
+            let mut storage = MemoryStorage::new();
+            storage.save_all(&stored).unwrap();
+            let loaded = load_tasks(&mut storage).unwrap();
+
+            assert_eq!(loaded.find(2).unwrap().name, "second");
+            assert_eq!(records(&replayed()), records(&loaded));
+        });
+    }
+
+    #[test]
+    fn replay_gives_the_migrated_tasks() {
+        with_test_dir(|data_dir| {
+            let mut tasks = Tasks::new();
+            change(&mut tasks, |tasks| tasks.add(String::from("first")));
+            let mut storage = MemoryStorage::new();
+            storage.save_all(&tasks).unwrap();
+            // an older legacy task with the same id keeps the id
+            let legacy_line = "{ Task ID: 1, Name: legacy, Status: Active, \
+                 Created: 2024/01/02 10:00:00, Last Modified: 2024/01/02 10:00:00, \
+                 Finished: None }";
+            fs::write(data_dir.join("Active.txt"), legacy_line).unwrap();
+            migrate(&mut storage).unwrap();
+            let loaded = load_tasks(&mut storage).unwrap();
+
+            assert_eq!(loaded.find(1).unwrap().name, "legacy");
+            assert_eq!(loaded.find(2).unwrap().name, "first");
+            assert_eq!(records(&replayed()), records(&loaded));
+        });
+    }
+}
@@ -0,0 +1,48 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::inputs::prelude::Console;
+use crate::internal::tasks::labels::Filter;
+use crate::internal::tasks::query::Query;
+use crate::internal::tasks::structs::{TaskStatus, Tasks};
+use crate::internal::tasks::view::view;
+use chrono::Local;
+
+/// # view_filtered
+/// prompts the user to pick a status list and to enter a filter of `+tag`
+/// and `project:x` words, and then prints out the tasks in that list that
+/// have the tags and are in the project or one of its sub projects (see
+/// `Filter::matches`).
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the tasks
+/// - **name_in**: The name of the user
+/// - **tasks**: A ref to Tasks which stores the active, completed and deleted tasks.
+/// # Returns
+/// `Ok(())` if the function completes successfully else a `TodoError`.
+pub fn view_filtered(console: &mut dyn Console, name_in: &str, tasks: &Tasks) -> TodoResult<()> {
+    let statuses = ["Active", "Completed", "Deleted"];
+    let status = console.input_option("Select a list: ", &statuses, "Lists")?;
+    let status = TaskStatus::parse(status)
+        .ok_or_else(|| TodoError::invalid(format!("Unknown list '{status}'")))?;
+    let filter_input =
+        console.input_str("Enter a filter e.g., '+urgent project:work.backend': ")?;
+    let filter = Filter::parse(&filter_input)?;
+    view(console, name_in, &tasks.filtered(status, &filter));
+    Ok(())
+}
+
+/// # view_query
+/// prompts the user to enter a query e.g., 'tag:urgent or due.before:friday'
+/// and then prints out the tasks that match it (see `Tasks::query`).
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the tasks
+/// - **name_in**: The name of the user
+/// - **tasks**: A ref to Tasks which stores the active, completed and deleted tasks.
+/// # Returns
+/// `Ok(())` if the function completes successfully else a `TodoError`,
+/// which is `TodoError::Query` if the query could not be parsed.
+pub fn view_query(console: &mut dyn Console, name_in: &str, tasks: &Tasks) -> TodoResult<()> {
+    let query_input = console
+        .input_str("Enter a query e.g., 'status:active and (tag:urgent or due.before:friday)': ")?;
+    let query = Query::parse(&query_input, Local::now())?;
+    view(console, name_in, &tasks.query(&query));
+    Ok(())
+}
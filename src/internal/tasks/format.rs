@@ -0,0 +1,175 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::tasks::structs::{DateTimeOption, Task};
+
+/// # to_line
+/// serializes a `Task` to a single line of JSON i.e., one record of the
+/// JSON Lines storage format. Every field is escaped by the serializer so
+/// names containing commas, braces, quotes, newlines or any other Unicode
+/// text are written out safely.
+/// # Arguments
+/// - **task**: the task to be serialized
+/// # Returns
+/// `Ok(String)` holding the serialized task without a trailing newline
+/// if the function completes successfully else `TodoError::Storage`.
+pub fn to_line(task: &Task) -> TodoResult<String> {
+    serde_json::to_string(task)
+        .map_err(|e| TodoError::Storage(format!("Could not serialize task {}: {e}", task.id)))
+}
+
+/// # from_line
+/// parses a single line of JSON (which is expected to have been generated
+/// by the `to_line` function) back to a `Task` instance.
+/// # Arguments
+/// - **line**: a line read from storage
+/// # Returns
+/// `Ok(Task)` if the line is parsed successfully else `TodoError::Parse`
+/// naming the field that could not be parsed if there is one. The error
+/// has no source or line number; set them with `TodoError::at`.
+pub fn from_line(line: &str) -> TodoResult<Task> {
+    let mut deserializer = serde_json::Deserializer::from_str(line.trim());
+    let task = serde_path_to_error::deserialize::<_, Task>(&mut deserializer).map_err(|e| {
+        let path = e.path().to_string();
+        TodoError::Parse {
+            source: String::new(),
+            line: 0,
+            // "." is the record itself and "?" an unknown location
+            field: if path == "." || path == "?" {
+                None
+            } else {
+                Some(path)
+            },
+            message: e.into_inner().to_string(),
+        }
+    })?;
+    // reject anything after the record
+    deserializer.end().map_err(|e| TodoError::Parse {
+        source: String::new(),
+        line: 0,
+        field: None,
+        message: e.to_string(),
+    })?;
+    Ok(task)
+}
+
+/// # to_lines
+/// serializes a list of tasks to JSON Lines i.e., one `to_line` record per
+/// task with each record terminated by a newline.
+/// # Arguments
+/// - **tasks_list**: the tasks to be serialized
+/// # Returns
+/// `Ok(String)` holding all serialized tasks if the function completes
+/// successfully else `TodoError::Storage`.
+pub fn to_lines(tasks_list: &[Task]) -> TodoResult<String> {
+    let mut lines = String::new();
+    for task in tasks_list {
+        lines.push_str(to_line(task)?.as_str());
+        lines.push('\n');
+    }
+    Ok(lines)
+}
+
+/// # escape_tsv
+/// escapes backslashes, tabs and newlines in `field` so that the field can
+/// be written as one column of a tab separated line.
+fn escape_tsv(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// # to_tsv
+/// converts a `Task` to a single tab separated line for scripts, with the
+/// columns id, status, created, last modified, finished, due, priority, project,
+/// tags, parent, depends on, repeats and name. Tags are written as '+tag' words separated
+/// by spaces, as are the ids of the tasks a task depends on. Times are written
+/// in RFC 3339 and an unfinished task has an empty finished column just as a
+/// task without a due date, parent or recurrence has an empty due, parent or
+/// repeats column. The repeats column describes the rule e.g., 'every week'.
+/// Tabs, newlines and backslashes in the name are escaped with a backslash.
+/// # Arguments
+/// - **task**: the task to be converted
+/// # Returns
+/// the tab separated line without a trailing newline.
+pub fn to_tsv(task: &Task) -> String {
+    let optional_time = |time: &DateTimeOption| match time {
+        DateTimeOption::DateTime(dt) => dt.to_rfc3339(),
+        DateTimeOption::None => String::new(),
+    };
+    format!(
+        "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+        task.id,
+        task.status.show(),
+        task.time_created.to_rfc3339(),
+        task.last_time_modified.to_rfc3339(),
+        optional_time(&task.time_finished),
+        optional_time(&task.due),
+        task.priority.show(),
+        escape_tsv(task.project.as_deref().unwrap_or_default()),
+        escape_tsv(&task.show_tags()),
+        task.parent
+            .map(|parent| parent.to_string())
+            .unwrap_or_default(),
+        task.show_depends_on(" "),
+        task.recurrence
+            .as_ref()
+            .map(|recurrence| recurrence.show())
+            .unwrap_or_default(),
+        escape_tsv(&task.name)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAMES: [&str; 5] = [
+        "buy milk, eggs, bread",
+        "fix { Task ID: 2, Name: x } in the parser",
+        "first line\nsecond line\r\n\tindented",
+        "say \"hi\" to José 👋 and 東京",
+        "back\\slash",
+    ];
+
+    #[test]
+    fn names_round_trip_through_a_line() {
+        for (name_idx, name) in NAMES.iter().enumerate() {
+            let task = Task::from_name_id(name.to_string(), name_idx as i32 + 1);
+            let line = to_line(&task).unwrap();
+            assert!(!line.contains('\n'), "{line} spans more than one line");
+            let parsed = from_line(&line).unwrap();
+            assert_eq!(parsed.name, *name);
+            assert_eq!(to_line(&parsed).unwrap(), line);
+        }
+    }
+
+    #[test]
+    fn lines_hold_one_task_each() {
+        let tasks_list = NAMES
+            .iter()
+            .enumerate()
+            .map(|(name_idx, name)| Task::from_name_id(name.to_string(), name_idx as i32 + 1))
+            .collect::<Vec<Task>>();
+        let lines = to_lines(&tasks_list).unwrap();
+        let names = lines
+            .lines()
+            .map(|line| from_line(line).unwrap().name)
+            .collect::<Vec<String>>();
+        assert_eq!(names, NAMES);
+    }
+
+    #[test]
+    fn trailing_text_and_bad_fields_are_reported() {
+        let line = to_line(&Task::from_name_id(String::from("x"), 1)).unwrap();
+        assert!(matches!(
+            from_line(&format!("{line} {{}}")),
+            Err(TodoError::Parse { field: None, .. })
+        ));
+        let bad_id = line.replacen("\"id\":1", "\"id\":\"one\"", 1);
+        assert!(matches!(
+            from_line(&bad_id),
+            Err(TodoError::Parse { field: Some(field), .. }) if field == "id"
+        ));
+    }
+}
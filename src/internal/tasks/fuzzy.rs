@@ -0,0 +1,213 @@
+use crate::internal::tasks::structs::Task;
+
+/// the score of every matched character.
+const MATCH_SCORE: i64 = 1;
+
+/// the bonus for a matched character that directly follows the previous
+/// matched character e.g., 'dep' in 'deploy'.
+const CONSECUTIVE_BONUS: i64 = 4;
+
+/// the bonus for a matched character at the start of a word e.g., the 'a'
+/// of 'api' in 'deploy api'.
+const WORD_START_BONUS: i64 = 6;
+
+/// the largest penalty for the characters skipped between two matched
+/// characters, one point per skipped character.
+const MAX_GAP_PENALTY: i64 = 5;
+
+/// the characters that start a new word in a task name.
+const WORD_SEPARATORS: [char; 6] = [' ', '-', '_', '.', '/', ':'];
+
+/// # FuzzyMatch
+/// the result of matching a search pattern against a text.
+///
+/// # Members
+/// - **score**: how well the pattern matches. Higher is better.
+/// - **positions**: the indices in characters of the matched characters of
+///   the text in ascending order, used to highlight the match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    pub positions: Vec<usize>,
+}
+
+/// # SearchHit
+/// a task found by a search, together with how well it matched.
+///
+/// # Members
+/// - **task**: a copy of the matching task
+/// - **score**: the score of the match (see `FuzzyMatch`)
+/// - **positions**: the indices in characters of the matched characters of
+///   the name of the task, which is empty if the notes matched
+/// - **notes_line**: the first line of the notes of the task that matched if
+///   the search matched its notes rather than its name (see `match_notes`)
+#[derive(Clone)]
+pub struct SearchHit {
+    pub task: Task,
+    pub score: i64,
+    pub positions: Vec<usize>,
+    pub notes_line: Option<String>,
+}
+
+/// # lowercase
+/// lowercases `text` one character at a time so that the indices of the
+/// characters stay the same.
+fn lowercase(text: &str) -> Vec<char> {
+    text.chars()
+        .map(|each_char| each_char.to_lowercase().next().unwrap_or(each_char))
+        .collect()
+}
+
+/// # score_positions
+/// scores the characters of `text` at `positions` as a match.
+fn score_positions(text: &[char], positions: &[usize]) -> i64 {
+    let mut score = 0;
+    for (idx, &position) in positions.iter().enumerate() {
+        score += MATCH_SCORE;
+        if position == 0 || WORD_SEPARATORS.contains(&text[position - 1]) {
+            score += WORD_START_BONUS;
+        }
+        if idx > 0 {
+            let gap = (position - positions[idx - 1] - 1) as i64;
+            if gap == 0 {
+                score += CONSECUTIVE_BONUS;
+            } else {
+                score -= gap.min(MAX_GAP_PENALTY);
+            }
+        }
+    }
+    score
+}
+
+/// # match_word
+/// matches a single lowercased word of a pattern against lowercased `text`.
+/// The word is matched as a substring where possible, picking the best
+/// scoring occurrence, else as a subsequence of the characters of `text`.
+fn match_word(word: &[char], text: &[char]) -> Option<FuzzyMatch> {
+    if word.is_empty() || word.len() > text.len() {
+        return None;
+    }
+
+    // the best occurrence of the word as a substring
+    let substring_match = (0..=text.len() - word.len())
+        .filter(|&start| text[start..start + word.len()] == *word)
+        .map(|start| {
+            let positions = (start..start + word.len()).collect::<Vec<usize>>();
+            FuzzyMatch {
+                score: score_positions(text, &positions),
+                positions,
+            }
+        })
+        .max_by_key(|found| found.score);
+    if substring_match.is_some() {
+        return substring_match;
+    }
+
+    // else the first occurrence of the word as a subsequence
+    let mut positions = Vec::<usize>::new();
+    let mut text_idx = 0;
+    for word_char in word {
+        let offset = text[text_idx..]
+            .iter()
+            .position(|text_char| text_char == word_char)?;
+        positions.push(text_idx + offset);
+        text_idx += offset + 1;
+    }
+    Some(FuzzyMatch {
+        score: score_positions(text, &positions),
+        positions,
+    })
+}
+
+/// # fuzzy_match
+/// matches `pattern` against `text` ignoring case. Every whitespace
+/// separated word of the pattern must match, either as a substring of the
+/// text or as its characters in order e.g., 'dply' matches 'deploy'.
+/// # Arguments
+/// - **pattern**: the text searched for
+/// - **text**: the text searched in e.g., the name of a task
+/// # Returns
+/// `Some(FuzzyMatch)` if every word of `pattern` matches `text` else `None`,
+/// which is also returned for a pattern without any words.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let text = lowercase(text);
+    let mut matched = FuzzyMatch {
+        score: 0,
+        positions: Vec::new(),
+    };
+    let mut words = pattern.split_whitespace().peekable();
+    words.peek()?;
+    for word in words {
+        let found = match_word(&lowercase(word), &text)?;
+        matched.score += found.score;
+        matched.positions.extend(found.positions);
+    }
+    matched.positions.sort_unstable();
+    matched.positions.dedup();
+    Some(matched)
+}
+
+/// # match_notes
+/// matches `pattern` against the notes of a task ignoring case. Every
+/// whitespace separated word of the pattern must be found in the notes as a
+/// substring; unlike `fuzzy_match` the characters of a word are not matched
+/// apart, as they would be found in almost any long text.
+/// # Returns
+/// `Some(line)` where line is the first line of `notes` holding a word of the
+/// pattern, trimmed, if every word is found else `None`, which is also
+/// returned for a pattern without any words.
+pub fn match_notes(pattern: &str, notes: &str) -> Option<String> {
+    let words = pattern
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<String>>();
+    let lowercase_notes = notes.to_lowercase();
+    if words.is_empty() || !words.iter().all(|word| lowercase_notes.contains(word)) {
+        return None;
+    }
+    notes
+        .lines()
+        .find(|line| {
+            let line = line.to_lowercase();
+            words.iter().any(|word| line.contains(word))
+        })
+        .map(|line| line.trim().to_string())
+}
+
+/// # highlight
+/// marks the characters of `text` at `positions` by wrapping each run of
+/// matched characters in square brackets e.g., '[dep]loy [a]pi'.
+pub fn highlight(text: &str, positions: &[usize]) -> String {
+    let mut highlighted = String::with_capacity(text.len() + 2 * positions.len());
+    let mut in_match = false;
+    for (char_idx, each_char) in text.chars().enumerate() {
+        let matched = positions.binary_search(&char_idx).is_ok();
+        if matched != in_match {
+            highlighted.push(if matched { '[' } else { ']' });
+            in_match = matched;
+        }
+        highlighted.push(each_char);
+    }
+    if in_match {
+        highlighted.push(']');
+    }
+    highlighted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_notes_needs_every_word_as_a_substring() {
+        let notes = "Call the vendor\nAsk about the Invoice number";
+        assert_eq!(
+            match_notes("invoice vendor", notes),
+            Some("Call the vendor".to_string())
+        );
+        assert_eq!(match_notes("invoice refund", notes), None);
+        // the letters of a word are not matched apart
+        assert_eq!(match_notes("cvr", notes), None);
+        assert_eq!(match_notes("  ", notes), None);
+    }
+}
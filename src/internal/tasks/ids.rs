@@ -0,0 +1,57 @@
+use crate::internal::tasks::structs::{TaskId, TaskStatus, Tasks};
+use chrono::Local;
+
+/// The operations that keep every Task ID unique. New tasks are given ids by
+/// `Tasks::allocate_id`; these find and repair the ids that data written by
+/// older versions of the program may share between tasks.
+impl Tasks {
+    /// # duplicate_ids
+    /// returns the Task IDs that are shared by more than one task, in
+    /// ascending order.
+    pub fn duplicate_ids(&self) -> Vec<TaskId> {
+        let mut task_ids = self.iter().map(|task| task.id).collect::<Vec<TaskId>>();
+        task_ids.sort_unstable();
+        let mut duplicates = task_ids
+            .windows(2)
+            .filter(|pair| pair[0] == pair[1])
+            .map(|pair| pair[0])
+            .collect::<Vec<TaskId>>();
+        duplicates.dedup();
+        duplicates
+    }
+
+    /// # repair_ids
+    /// gives a new Task ID to every task that shares its id with an older
+    /// task and updates its `last_time_modified`. The oldest task keeps the
+    /// id, together with the subtasks and dependents that refer to it, as
+    /// the newer tasks were given an id that was already in use.
+    /// # Returns
+    /// the (old id, new id) pairs of the tasks that were given new ids.
+    pub fn repair_ids(&mut self) -> Vec<(TaskId, TaskId)> {
+        let mut repaired = Vec::<(TaskId, TaskId)>::new();
+        for task_id in self.duplicate_ids() {
+            // find every task with the id, keeping the oldest one first
+            let mut sharing = Vec::<(TaskStatus, usize)>::new();
+            for status in [
+                TaskStatus::Active,
+                TaskStatus::Completed,
+                TaskStatus::Deleted,
+            ] {
+                for (task_idx, task) in self.list(status).iter().enumerate() {
+                    if task.id == task_id {
+                        sharing.push((status, task_idx));
+                    }
+                }
+            }
+            sharing.sort_by_key(|(status, task_idx)| self.list(*status)[*task_idx].time_created);
+            for (status, task_idx) in sharing.into_iter().skip(1) {
+                let new_task_id = self.allocate_id();
+                let task = &mut self.list_mut(status)[task_idx];
+                task.id = new_task_id;
+                task.last_time_modified = Local::now();
+                repaired.push((task_id, new_task_id));
+            }
+        }
+        repaired
+    }
+}
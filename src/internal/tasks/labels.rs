@@ -0,0 +1,287 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::tasks::structs::Task;
+
+/// the prefix of a word that adds a tag e.g., '+work'.
+const TAG_PREFIX: char = '+';
+
+/// the prefix of a word that removes a tag e.g., '-work'.
+const UNTAG_PREFIX: char = '-';
+
+/// the prefix of a word that sets the project e.g., 'project:work.api'.
+const PROJECT_PREFIX: &str = "project:";
+
+/// the prefix of a word kept in the name as it is written e.g., '\+1'.
+const ESCAPE_PREFIX: char = '\\';
+
+/// # check_tag
+/// checks that `tag` is a valid tag i.e., it is not empty and does not
+/// contain whitespace or start with '+' or '-'.
+fn check_tag(tag: &str) -> TodoResult<String> {
+    if tag.is_empty() || tag.starts_with([TAG_PREFIX, UNTAG_PREFIX]) {
+        return Err(TodoError::invalid(format!("'{tag}' is not a valid tag")));
+    }
+    Ok(tag.to_string())
+}
+
+/// # check_project
+/// checks that `project` is a valid project i.e., one or more non-empty
+/// names separated by '.' e.g., 'work.backend.api'.
+fn check_project(project: &str) -> TodoResult<String> {
+    if project.split('.').any(|part| part.is_empty()) {
+        return Err(TodoError::invalid(format!(
+            "'{project}' is not a valid project. Expected names separated by '.' e.g., work.backend"
+        )));
+    }
+    Ok(project.to_string())
+}
+
+/// # label_tag
+/// returns the tag of `word` if it is `prefix` followed by a tag i.e., a
+/// character other than whitespace, a digit, '+' or '-' and then anything
+/// but whitespace. So '+work' is a label but '+1', '-5' and a lone '+' are
+/// words of the name.
+fn label_tag(word: &str, prefix: char) -> Option<&str> {
+    let tag = word.strip_prefix(prefix)?;
+    let first = tag.chars().next()?;
+    if first.is_whitespace()
+        || first.is_ascii_digit()
+        || first == TAG_PREFIX
+        || first == UNTAG_PREFIX
+    {
+        return None;
+    }
+    Some(tag)
+}
+
+/// # in_project
+/// checks if `project` is `parent` or one of its sub projects e.g.,
+/// 'work.backend.api' is in 'work' and 'work.backend' but not in 'work.back'.
+pub fn in_project(project: &str, parent: &str) -> bool {
+    match project.strip_prefix(parent) {
+        Some(rest) => rest.is_empty() || rest.starts_with('.'),
+        None => false,
+    }
+}
+
+/// # Labels
+/// the changes to a task entered by the user as a name mixed with `+tag`,
+/// `-tag` and `project:x` words e.g., 'write report +work project:work.docs'.
+/// A word starting with '\' is kept in the name without the '\' e.g.,
+/// '\+work' or '\project:x'.
+///
+/// # Members
+/// - **name**: the input without its labels, if anything else was entered.
+///   The input is kept as it is if it has no labels or escaped words, else
+///   the whitespace before each remaining word is kept.
+/// - **added_tags**: the tags to add i.e., the words starting with '+'
+/// - **removed_tags**: the tags to remove i.e., the words starting with '-'
+/// - **project**: the project to set if a `project:` word was entered. An
+///   empty `project:` removes the project, which is `Some(None)`.
+#[derive(Debug, Clone, Default)]
+pub struct Labels {
+    pub name: Option<String>,
+    pub added_tags: Vec<String>,
+    pub removed_tags: Vec<String>,
+    pub project: Option<Option<String>>,
+}
+
+impl Labels {
+    /// # parse
+    /// splits `input` i.e., the text entered for a new task into its name
+    /// and labels. Words starting with '-' are kept in the name as a new
+    /// task has no tags to remove.
+    /// # Arguments
+    /// - **input**: the text entered by the user
+    /// # Returns
+    /// `Ok(Labels)` else `TodoError::InvalidInput` if a tag or project is
+    /// not valid.
+    pub fn parse(input: &str) -> TodoResult<Labels> {
+        Labels::parse_words(input, false)
+    }
+
+    /// # parse_changes
+    /// splits `input` i.e., the text entered to edit a task into the new name
+    /// of the task and the changes to its labels, where words starting with
+    /// '-' remove a tag.
+    /// # Arguments
+    /// - **input**: the text entered by the user
+    /// # Returns
+    /// `Ok(Labels)` else `TodoError::InvalidInput` if a tag or project is
+    /// not valid.
+    pub fn parse_changes(input: &str) -> TodoResult<Labels> {
+        Labels::parse_words(input, true)
+    }
+
+    /// # parse_words
+    /// splits `input` into a name and labels, treating '-tag' words as tags
+    /// to remove if `removals` is `true`.
+    fn parse_words(input: &str, removals: bool) -> TodoResult<Labels> {
+        let mut labels = Labels::default();
+        let mut name = String::new();
+        let mut rewritten = false;
+        let mut rest = input;
+        while !rest.is_empty() {
+            // the whitespace before the next word and the word itself
+            let word_start = rest
+                .find(|c: char| !c.is_whitespace())
+                .unwrap_or(rest.len());
+            let (space, after_space) = rest.split_at(word_start);
+            let word_end = after_space
+                .find(char::is_whitespace)
+                .unwrap_or(after_space.len());
+            let (word, after_word) = after_space.split_at(word_end);
+            rest = after_word;
+            if word.is_empty() {
+                break;
+            }
+
+            let removed_tag = label_tag(word, UNTAG_PREFIX).filter(|_| removals);
+            let kept_word = if let Some(tag) = label_tag(word, TAG_PREFIX) {
+                labels.added_tags.push(check_tag(tag)?);
+                None
+            } else if let Some(tag) = removed_tag {
+                labels.removed_tags.push(check_tag(tag)?);
+                None
+            } else if let Some(project) = word.strip_prefix(PROJECT_PREFIX) {
+                labels.project = match project {
+                    "" => Some(None),
+                    project => Some(Some(check_project(project)?)),
+                };
+                None
+            } else if let Some(escaped) = word.strip_prefix(ESCAPE_PREFIX) {
+                Some(escaped)
+            } else {
+                Some(word)
+            };
+            rewritten |= kept_word != Some(word);
+            if let Some(kept_word) = kept_word.filter(|kept_word| !kept_word.is_empty()) {
+                if !name.is_empty() {
+                    name.push_str(space);
+                }
+                name.push_str(kept_word);
+            }
+        }
+        if !rewritten && !input.trim().is_empty() {
+            labels.name = Some(input.to_string());
+        } else if !name.is_empty() {
+            labels.name = Some(name);
+        }
+        Ok(labels)
+    }
+
+    /// # apply
+    /// adds and removes the tags of `task` and sets its project as recorded
+    /// in the labels. The name of `task` is left unchanged.
+    pub fn apply(&self, task: &mut Task) {
+        for tag in &self.added_tags {
+            task.tags.insert(tag.clone());
+        }
+        for tag in &self.removed_tags {
+            task.tags.remove(tag);
+        }
+        if let Some(project) = &self.project {
+            task.project = project.clone();
+        }
+    }
+}
+
+/// # Filter
+/// restricts a list of tasks to the tasks with some tags and/or in a
+/// project subtree (see `in_project`).
+///
+/// # Members
+/// - **tags**: the tags every matching task must have
+/// - **project**: the project every matching task must be in
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub tags: Vec<String>,
+    pub project: Option<String>,
+}
+
+impl Filter {
+    /// # parse
+    /// creates a `Filter` from `+tag` and `project:x` words e.g.,
+    /// '+urgent project:work'. An empty input matches every task.
+    /// # Returns
+    /// `Ok(Filter)` else `TodoError::InvalidInput` if `input` holds other
+    /// words or an invalid tag or project.
+    pub fn parse(input: &str) -> TodoResult<Filter> {
+        let labels = Labels::parse(input)?;
+        if labels.name.is_some() {
+            return Err(TodoError::invalid(format!(
+                "Could not understand the filter '{input}'. Expected e.g., +tag and/or project:name"
+            )));
+        }
+        Ok(Filter {
+            tags: labels.added_tags,
+            project: labels.project.flatten(),
+        })
+    }
+
+    /// # matches
+    /// checks if `task` has the tags and is in the project of the filter.
+    pub fn matches(&self, task: &Task) -> bool {
+        let tag_matches = self.tags.iter().all(|tag| task.tags.contains(tag));
+        let project_matches = match (&self.project, &task.project) {
+            (Some(parent), Some(project)) => in_project(project, parent),
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        tag_matches && project_matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_are_taken_out_of_the_name() {
+        let labels = Labels::parse("write report +work project:work.docs").unwrap();
+        assert_eq!(labels.name.as_deref(), Some("write report"));
+        assert_eq!(labels.added_tags, vec!["work"]);
+        assert_eq!(labels.project, Some(Some(String::from("work.docs"))));
+    }
+
+    #[test]
+    fn words_that_are_not_tags_stay_in_the_name() {
+        let labels = Labels::parse("call +1 555").unwrap();
+        assert_eq!(labels.name.as_deref(), Some("call +1 555"));
+        assert!(labels.added_tags.is_empty());
+
+        let labels = Labels::parse("learn C++ + Rust").unwrap();
+        assert_eq!(labels.name.as_deref(), Some("learn C++ + Rust"));
+
+        let labels = Labels::parse_changes("reply to -1 votes").unwrap();
+        assert_eq!(labels.name.as_deref(), Some("reply to -1 votes"));
+        assert!(labels.removed_tags.is_empty());
+    }
+
+    #[test]
+    fn whitespace_is_kept_without_labels() {
+        let labels = Labels::parse("a  name\twith   gaps").unwrap();
+        assert_eq!(labels.name.as_deref(), Some("a  name\twith   gaps"));
+
+        let labels = Labels::parse("a  name +tag   gaps").unwrap();
+        assert_eq!(labels.name.as_deref(), Some("a  name   gaps"));
+    }
+
+    #[test]
+    fn escaped_words_stay_in_the_name() {
+        let labels = Labels::parse(r"buy \+work gloves \project:x").unwrap();
+        assert_eq!(labels.name.as_deref(), Some("buy +work gloves project:x"));
+        assert!(labels.added_tags.is_empty());
+        assert_eq!(labels.project, None);
+    }
+
+    #[test]
+    fn removals_only_apply_to_changes() {
+        let labels = Labels::parse_changes("-bug +urgent").unwrap();
+        assert_eq!(labels.name, None);
+        assert_eq!(labels.removed_tags, vec!["bug"]);
+        assert_eq!(labels.added_tags, vec!["urgent"]);
+
+        let labels = Labels::parse("fix -bug").unwrap();
+        assert_eq!(labels.name.as_deref(), Some("fix -bug"));
+    }
+}
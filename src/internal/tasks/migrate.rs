@@ -0,0 +1,486 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::paths::{get_file_path, get_path};
+use crate::internal::storage::atomic::write_atomic;
+use crate::internal::storage::backend::Storage;
+use crate::internal::tasks::events::{log_created, IMPORT_ACTION};
+use crate::internal::tasks::load::report_repaired;
+use crate::internal::tasks::structs::{Task, TaskId, TaskStatus};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+
+/// the version of the stored data written by this version of the program.
+/// Bump this and add a step to `migrate` whenever the storage format changes.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// the file recording the schema version of the stored data.
+pub const META_FILE: &str = "meta.json";
+
+/// the file that collects stored lines which could not be parsed.
+pub const QUARANTINE_FILE: &str = "quarantine.txt";
+
+/// the dir in the data dir the legacy files are moved to while they are
+/// imported, so that they are never read from the data dir twice.
+const LEGACY_IMPORT_DIR: &str = "legacy-import";
+
+/// the per-status text files written by versions of the program before
+/// the JSON Lines format, together with the status of the tasks in them.
+const LEGACY_FILES: [(&str, TaskStatus); 3] = [
+    ("Active.txt", TaskStatus::Active),
+    ("Completed.txt", TaskStatus::Completed),
+    ("Deleted.txt", TaskStatus::Deleted),
+];
+
+/// # Meta
+/// a struct that models the contents of 'meta.json'.
+///
+/// # Members
+/// - **schema_version**: the version of the storage format of the data
+/// - **last_task_id**: the highest Task ID ever assigned (see `Tasks::last_id`)
+#[derive(Serialize, Deserialize)]
+pub struct Meta {
+    pub schema_version: u32,
+    #[serde(default)]
+    pub last_task_id: TaskId,
+}
+
+/// # has_legacy_files
+/// checks if any of the legacy 'Active.txt', 'Completed.txt' or 'Deleted.txt'
+/// files exist in the data dir, or if an import of them was interrupted (see
+/// `migrate_legacy`).
+fn has_legacy_files() -> bool {
+    get_file_path(LEGACY_IMPORT_DIR).exists()
+        || LEGACY_FILES
+            .iter()
+            .any(|(filename, _)| get_file_path(filename).exists())
+}
+
+/// # is_same_task
+/// checks if `stored` is `task` as imported from the legacy files, which
+/// records the id, name, status and creation time of each task. The id is
+/// not compared as the import may have given the task a new one.
+fn is_same_task(stored: &Task, task: &Task) -> bool {
+    stored.name == task.name
+        && stored.status == task.status
+        && stored.time_created == task.time_created
+}
+
+/// # read_meta
+/// returns the contents of 'meta.json'. If it does not exist, the data is
+/// assumed to be schema version 0 if legacy files exist else the current
+/// `SCHEMA_VERSION` as there is nothing to migrate, and no Task ID is
+/// assumed to have been assigned.
+/// # Arguments
+/// none
+/// # Returns
+/// `Ok(Meta)` if the function completes successfully else `TodoError::Io`
+/// if 'meta.json' cannot be read or `TodoError::Corrupt` if it cannot be parsed.
+fn read_meta() -> TodoResult<Meta> {
+    let meta_path = get_file_path(META_FILE);
+    if !meta_path.exists() {
+        let version = if has_legacy_files() {
+            0
+        } else {
+            SCHEMA_VERSION
+        };
+        return Ok(Meta {
+            schema_version: version,
+            last_task_id: 0,
+        });
+    }
+    let meta_str = fs::read_to_string(&meta_path).map_err(|e| TodoError::io(&meta_path, e))?;
+    serde_json::from_str::<Meta>(&meta_str)
+        .map_err(|e| TodoError::Corrupt(format!("Could not parse {}: {e}", meta_path.display())))
+}
+
+/// # write_meta
+/// replaces the contents of 'meta.json' with `meta`.
+/// # Arguments
+/// - **meta**: the `Meta` to be recorded
+/// # Returns
+/// `Ok(())` if the function completes successfully else a `TodoError`.
+fn write_meta(meta: &Meta) -> TodoResult<()> {
+    let meta_path = get_file_path(META_FILE);
+    let meta_str = serde_json::to_string_pretty(meta)
+        .map_err(|e| TodoError::Storage(format!("Could not serialize {META_FILE}: {e}")))?;
+    write_atomic(&meta_path, meta_str.as_bytes())
+}
+
+/// # read_last_task_id
+/// returns the highest Task ID ever assigned as recorded in 'meta.json',
+/// or 0 if none was recorded yet.
+/// # Returns
+/// `Ok(task_id)` if the function completes successfully else a `TodoError`.
+pub fn read_last_task_id() -> TodoResult<TaskId> {
+    read_meta().map(|meta| meta.last_task_id)
+}
+
+/// # write_last_task_id
+/// records `task_id` as the highest Task ID ever assigned in 'meta.json'
+/// unless a higher one is already recorded.
+/// # Arguments
+/// - **task_id**: the highest Task ID assigned by the program
+/// # Returns
+/// `Ok(())` if the function completes successfully else a `TodoError`.
+pub fn write_last_task_id(task_id: TaskId) -> TodoResult<()> {
+    let mut meta = read_meta()?;
+    if meta.last_task_id >= task_id {
+        return Ok(());
+    }
+    meta.last_task_id = task_id;
+    write_meta(&meta)
+}
+
+/// # quarantine
+/// appends a stored line that could not be parsed to 'quarantine.txt' so that it
+/// is kept for manual recovery instead of aborting the whole load. The line is
+/// preceded by a comment recording when it was quarantined and why.
+/// # Arguments
+/// - **error**: the `TodoError::Parse` the line failed with, which records the
+///   file and line number the line was read from
+/// - **line**: the line that could not be parsed
+/// # Returns
+/// `Ok(())` if the function completes successfully else `TodoError::Io`.
+pub fn quarantine(error: &TodoError, line: &str) -> TodoResult<()> {
+    let quarantine_path = get_file_path(QUARANTINE_FILE);
+    eprintln!(
+        "WARNING: {error}. It has been moved to {}",
+        quarantine_path.display()
+    );
+    let io_error = |e| TodoError::io(&quarantine_path, e);
+    if let Some(parent_path) = quarantine_path.parent() {
+        fs::create_dir_all(parent_path).map_err(io_error)?;
+    }
+    let mut quarantine_file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&quarantine_path)
+        .map_err(io_error)?;
+    let timestamp = Local::now().format("%Y/%m/%d %H:%M:%S");
+    let entry = format!("# {timestamp} {error}\n{line}\n");
+    quarantine_file
+        .write_all(entry.as_bytes())
+        .map_err(io_error)
+}
+
+/// # migrate_legacy
+/// converts the legacy 'Active.txt', 'Completed.txt' and 'Deleted.txt' files
+/// to the configured storage backend i.e., migrates data from schema version
+/// 0 to 1.
+///
+/// The original files are first moved to the 'legacy-import' dir in the data
+/// dir and read from there. Every line is parsed with `Task::from_legacy` and
+/// given the status of the file it was read from. The parsed tasks are added
+/// after any tasks already in `storage`, leaving out the tasks an interrupted
+/// earlier import already added, and lines that fail to parse are
+/// quarantined. Tasks that share a Task ID are given new ids before they are
+/// stored (see `Tasks::repair_ids`), and the imported tasks are recorded in
+/// the event log if it was started (see `log_created`). The 'legacy-import' dir is then renamed to a timestamped
+/// 'backup-<timestamp>' dir, so an import that stops at any point is finished
+/// by the next run without adding a task twice.
+/// # Arguments
+/// - **storage**: the storage backend the converted tasks are written to
+/// # Returns
+/// `Ok(())` if the function completes successfully else a `TodoError`.
+fn migrate_legacy(storage: &mut dyn Storage) -> TodoResult<()> {
+    // move the originals out of the data dir before anything is imported
+    let import_dir = get_file_path(LEGACY_IMPORT_DIR);
+    fs::create_dir_all(&import_dir).map_err(|e| TodoError::io(&import_dir, e))?;
+    for (filename, _) in LEGACY_FILES {
+        let legacy_path = get_file_path(filename);
+        let import_path = import_dir.join(filename);
+        if !legacy_path.exists() {
+            continue;
+        }
+        if import_path.exists() {
+            return Err(TodoError::Corrupt(format!(
+                "Both {} and {} exist. Merge them into one of them to import the legacy tasks",
+                legacy_path.display(),
+                import_path.display()
+            )));
+        }
+        fs::rename(&legacy_path, &import_path).map_err(|e| TodoError::io(&legacy_path, e))?;
+    }
+
+    let mut migrated = Vec::<Task>::new();
+    let mut unparsed = Vec::<(TodoError, String)>::new();
+    for (filename, status) in LEGACY_FILES {
+        let import_path = import_dir.join(filename);
+        if !import_path.exists() {
+            continue;
+        }
+        let legacy_str =
+            fs::read_to_string(&import_path).map_err(|e| TodoError::io(&import_path, e))?;
+        for (line_idx, line) in legacy_str.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match Task::from_legacy(line) {
+                Ok(mut task) => {
+                    // the file a task is stored in decides its status
+                    task.status = status;
+                    migrated.push(task);
+                }
+                Err(e) => unparsed.push((e.at(filename, line_idx + 1), line.to_string())),
+            }
+        }
+    }
+
+    // add the converted tasks to any tasks already stored in the new format
+    let mut stored = storage.load_all()?;
+    let mut migrated_ids = Vec::<TaskId>::new();
+    for task in migrated {
+        if !stored
+            .iter()
+            .any(|stored_task| is_same_task(stored_task, &task))
+        {
+            migrated_ids.push(task.id);
+            stored.push(task);
+        }
+    }
+    // the legacy files may share a Task ID between tasks, which a backend
+    // that keeps every id unique refuses to store
+    stored.last_id = storage.last_task_id()?;
+    let repaired = stored.repair_ids();
+    storage.save_all(&stored)?;
+    log_created(IMPORT_ACTION, &stored, &migrated_ids)?;
+    report_repaired(&stored, &repaired)?;
+    // lines quarantined by an interrupted earlier import are not added again
+    let quarantined = fs::read_to_string(get_file_path(QUARANTINE_FILE)).unwrap_or_default();
+    for (error, line) in unparsed {
+        if !quarantined
+            .lines()
+            .any(|quarantined_line| quarantined_line == line)
+        {
+            quarantine(&error, &line)?;
+        }
+    }
+
+    // keep the originals in a timestamped backup dir
+    let timestamp = Local::now().format("%Y%m%d-%H%M%S");
+    let mut backup_dir = get_path().join(format!("backup-{timestamp}"));
+    let mut backup_idx = 1;
+    while backup_dir.exists() {
+        backup_idx += 1;
+        backup_dir = get_path().join(format!("backup-{timestamp}-{backup_idx}"));
+    }
+    fs::rename(&import_dir, &backup_dir).map_err(|e| TodoError::io(&import_dir, e))?;
+    eprintln!(
+        "Migrated {} tasks from the legacy text files. The originals were backed up to {}",
+        migrated_ids.len(),
+        backup_dir.display()
+    );
+    Ok(())
+}
+
+/// # migrate
+/// brings the stored data up to the current `SCHEMA_VERSION` by running each
+/// migration step from the stored schema version onwards, and records the new
+/// schema version in 'meta.json' after every step.
+///
+/// - version 0 => the legacy per-status text files
+/// - version 1 => the storage backends in `storage`
+///
+/// Data written by a newer version of the program is left untouched.
+/// # Arguments
+/// - **storage**: the storage backend holding the tasks
+/// # Returns
+/// `Ok(())` if the data is at the current schema version after the function
+/// completes else a `TodoError`, which is `TodoError::Corrupt` if the data
+/// was written by a newer version of the program.
+pub fn migrate(storage: &mut dyn Storage) -> TodoResult<()> {
+    let mut meta = read_meta()?;
+    let mut version = meta.schema_version;
+    if version > SCHEMA_VERSION {
+        return Err(TodoError::Corrupt(format!(
+            "The stored data uses schema version {version} which is newer than \
+             the supported version {SCHEMA_VERSION}. Please upgrade the program."
+        )));
+    }
+    while version < SCHEMA_VERSION {
+        match version {
+            0 => migrate_legacy(storage)?,
+            _ => {
+                return Err(TodoError::Corrupt(format!(
+                    "There is no migration from schema version {version}"
+                )))
+            }
+        }
+        version += 1;
+        meta.schema_version = version;
+        write_meta(&meta)?;
+    }
+    if !get_file_path(META_FILE).exists() {
+        write_meta(&meta)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::paths::with_test_dir;
+    use crate::internal::storage::memory::MemoryStorage;
+    use crate::internal::tasks::load::load_tasks;
+
+    /// a task in the legacy text format created and last modified at `created`.
+    fn legacy_line(task_id: TaskId, name: &str, created: &str) -> String {
+        format!(
+            "{{ Task ID: {task_id}, Name: {name}, Status: Active, Created: {created}, \
+             Last Modified: {created}, Finished: None }}\n"
+        )
+    }
+
+    /// imports two legacy tasks sharing Task ID 1 into `storage`.
+    fn import_shared_ids(storage: &mut dyn Storage) {
+        with_test_dir(|data_dir| {
+            let active = legacy_line(1, "water plants", "2024/01/02 10:00:00");
+            let completed = legacy_line(1, "buy milk", "2024/01/03 10:00:00");
+            fs::write(data_dir.join("Active.txt"), active).unwrap();
+            fs::write(data_dir.join("Completed.txt"), completed).unwrap();
+            migrate(storage).unwrap();
+            let tasks = load_tasks(storage).unwrap();
+
+            // the older task keeps the id
+            assert_eq!(tasks.find(1).unwrap().name, "water plants");
+            assert_eq!(tasks.find(2).unwrap().name, "buy milk");
+            assert_eq!(tasks.find(2).unwrap().status, TaskStatus::Completed);
+            assert!(!data_dir.join("Active.txt").exists());
+            assert!(!data_dir.join(LEGACY_IMPORT_DIR).exists());
+        });
+    }
+
+    /// the names of the tasks in `storage` with their status, in stored order.
+    fn stored_names(storage: &mut dyn Storage) -> Vec<(String, TaskStatus)> {
+        let tasks = storage.load_all().unwrap();
+        tasks
+            .iter()
+            .map(|task| (task.name.clone(), task.status))
+            .collect()
+    }
+
+    #[test]
+    fn legacy_files_are_imported_and_backed_up() {
+        with_test_dir(|data_dir| {
+            let active = legacy_line(1, "water plants", "2024/01/02 10:00:00")
+                + &legacy_line(2, "buy milk", "2024/01/03 10:00:00");
+            let deleted = legacy_line(3, "call bob", "2024/01/04 10:00:00");
+            fs::write(data_dir.join("Active.txt"), &active).unwrap();
+            fs::write(data_dir.join("Deleted.txt"), &deleted).unwrap();
+            let mut storage = MemoryStorage::new();
+            migrate(&mut storage).unwrap();
+
+            assert_eq!(
+                stored_names(&mut storage),
+                [
+                    ("water plants".to_string(), TaskStatus::Active),
+                    ("buy milk".to_string(), TaskStatus::Active),
+                    ("call bob".to_string(), TaskStatus::Deleted),
+                ]
+            );
+            assert_eq!(read_meta().unwrap().schema_version, SCHEMA_VERSION);
+            assert!(!data_dir.join("Active.txt").exists());
+            assert!(!data_dir.join(QUARANTINE_FILE).exists());
+            // the originals are kept unchanged in the backup dir
+            let backup_dir = fs::read_dir(data_dir)
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .find(|path| {
+                    path.file_name()
+                        .unwrap()
+                        .to_string_lossy()
+                        .starts_with("backup-")
+                })
+                .unwrap();
+            let backup = fs::read_to_string(backup_dir.join("Active.txt")).unwrap();
+            assert_eq!(backup, active);
+        });
+    }
+
+    #[test]
+    fn malformed_legacy_lines_are_quarantined() {
+        with_test_dir(|data_dir| {
+            let active = legacy_line(1, "water plants", "2024/01/02 10:00:00")
+                + "{ Task ID: two, Name: buy milk }\n";
+            fs::write(data_dir.join("Active.txt"), active).unwrap();
+            let mut storage = MemoryStorage::new();
+            migrate(&mut storage).unwrap();
+
+            assert_eq!(
+                stored_names(&mut storage),
+                [("water plants".to_string(), TaskStatus::Active)]
+            );
+            let quarantined = fs::read_to_string(data_dir.join(QUARANTINE_FILE)).unwrap();
+            let quarantined = quarantined.lines().collect::<Vec<&str>>();
+            assert_eq!(quarantined.len(), 2);
+            assert!(quarantined[0].starts_with("# ") && quarantined[0].contains("Active.txt"));
+            assert_eq!(quarantined[1], "{ Task ID: two, Name: buy milk }");
+        });
+    }
+
+    #[test]
+    fn an_interrupted_import_does_not_add_tasks_twice() {
+        with_test_dir(|data_dir| {
+            let import_dir = data_dir.join(LEGACY_IMPORT_DIR);
+            fs::create_dir_all(&import_dir).unwrap();
+            let active = legacy_line(1, "water plants", "2024/01/02 10:00:00")
+                + &legacy_line(2, "buy milk", "2024/01/03 10:00:00");
+            fs::write(import_dir.join("Active.txt"), active).unwrap();
+            // the first task was stored before the import was interrupted
+            let mut storage = MemoryStorage::new();
+            let mut stored = storage.load_all().unwrap();
+            stored.push(
+                Task::from_legacy(&legacy_line(1, "water plants", "2024/01/02 10:00:00")).unwrap(),
+            );
+            storage.save_all(&stored).unwrap();
+            migrate(&mut storage).unwrap();
+
+            assert_eq!(
+                stored_names(&mut storage),
+                [
+                    ("water plants".to_string(), TaskStatus::Active),
+                    ("buy milk".to_string(), TaskStatus::Active),
+                ]
+            );
+            assert!(!import_dir.exists());
+        });
+    }
+
+    #[test]
+    fn migrated_data_is_left_alone() {
+        with_test_dir(|data_dir| {
+            write_meta(&Meta {
+                schema_version: SCHEMA_VERSION,
+                last_task_id: 0,
+            })
+            .unwrap();
+            let active = legacy_line(1, "water plants", "2024/01/02 10:00:00");
+            fs::write(data_dir.join("Active.txt"), &active).unwrap();
+            let mut storage = MemoryStorage::new();
+            migrate(&mut storage).unwrap();
+
+            assert!(stored_names(&mut storage).is_empty());
+            assert_eq!(
+                fs::read_to_string(data_dir.join("Active.txt")).unwrap(),
+                active
+            );
+        });
+    }
+
+    #[test]
+    fn shared_legacy_ids_are_repaired() {
+        import_shared_ids(&mut MemoryStorage::new());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn shared_legacy_ids_are_repaired_before_sqlite_stores_them() {
+        use crate::internal::storage::sqlite::SqliteStorage;
+        use rusqlite::Connection;
+
+        let connection = Connection::open_in_memory().unwrap();
+        import_shared_ids(&mut SqliteStorage::from_connection(connection).unwrap());
+    }
+}
@@ -0,0 +1,238 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::inputs::prelude::Console;
+use crate::internal::tasks::structs::{Task, TaskId, Tasks};
+use crate::internal::tasks::utils::extract_task;
+use chrono::Local;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// the environment variables naming the editor notes are written in, in the
+/// order they are looked up.
+const EDITOR_VARS: [&str; 2] = ["VISUAL", "EDITOR"];
+
+/// the number of other names tried when the name of the temporary notes
+/// file is already taken.
+const MAX_FILE_ATTEMPTS: u32 = 100;
+
+/// the line that ends notes entered line by line when no editor is set.
+const END_OF_NOTES: &str = ".";
+
+/// # clean_notes
+/// removes the carriage returns, the blank lines around and the trailing
+/// spaces of each line of `notes` as written by an editor or entered line by
+/// line.
+/// # Returns
+/// `Some(notes)` else `None` if nothing is left i.e., the task has no notes.
+pub fn clean_notes(notes: &str) -> Option<String> {
+    let lines = notes
+        .replace('\r', "")
+        .lines()
+        .map(|line| line.trim_end().to_string())
+        .collect::<Vec<String>>();
+    let cleaned = lines.join("\n").trim_matches('\n').to_string();
+    if cleaned.is_empty() {
+        None
+    } else {
+        Some(cleaned)
+    }
+}
+
+/// # ret_editor
+/// returns the editor command set in `$VISUAL` or `$EDITOR`, if any.
+pub fn ret_editor() -> Option<String> {
+    EDITOR_VARS
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty())
+}
+
+/// # create_notes_file
+/// creates a new temporary file for the notes in the temporary directory,
+/// readable and writable by the user only on unix. The file is created with
+/// `create_new`, so an existing file or a symbolic link planted at the name
+/// is never opened; another name is tried instead.
+/// # Returns
+/// `Ok((path, file))` where path is the path of the created file and file
+/// the file opened for writing else `TodoError::Io` if no file could be
+/// created.
+fn create_notes_file() -> TodoResult<(PathBuf, File)> {
+    let nanos = Local::now().timestamp_nanos_opt().unwrap_or_default();
+    let mut attempt = 0;
+    loop {
+        let notes_path = std::env::temp_dir().join(format!(
+            "todolist-notes-{}-{nanos}-{attempt}.txt",
+            std::process::id()
+        ));
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        match options.open(&notes_path) {
+            Ok(file) => return Ok((notes_path, file)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempt < MAX_FILE_ATTEMPTS => {
+                attempt += 1;
+            }
+            Err(e) => return Err(TodoError::io(&notes_path, e)),
+        }
+    }
+}
+
+/// # edit_in_editor
+/// writes `text` to a new temporary file (see `create_notes_file`), opens it
+/// in `editor` and reads the file back once the editor exits. The editor
+/// command may contain arguments e.g., 'code --wait'. The temporary file is
+/// removed afterwards.
+/// # Arguments
+/// - **editor**: the editor command, usually returned by `ret_editor`
+/// - **text**: the text the file starts with
+/// # Returns
+/// `Ok(text)` where text is the saved content of the file else
+/// `TodoError::Io` if the file could not be written or read, or if the
+/// editor could not be started or failed.
+pub fn edit_in_editor(editor: &str, text: &str) -> TodoResult<String> {
+    let (notes_path, mut notes_file) = create_notes_file()?;
+    let edited = notes_file
+        .write_all(text.as_bytes())
+        .map_err(|e| TodoError::io(&notes_path, e))
+        .and_then(|_| {
+            drop(notes_file);
+            run_editor(editor, &notes_path)
+        })
+        .and_then(|_| fs::read_to_string(&notes_path).map_err(|e| TodoError::io(&notes_path, e)));
+    fs::remove_file(&notes_path).unwrap_or_default();
+    edited
+}
+
+/// # run_editor
+/// runs `editor` on the file at `notes_path` and waits for it to exit.
+fn run_editor(editor: &str, notes_path: &Path) -> TodoResult<()> {
+    let mut words = editor.split_whitespace();
+    let program = words
+        .next()
+        .ok_or_else(|| TodoError::invalid("No editor is set"))?;
+    let editor_error = |e| TodoError::io(Path::new(program), e);
+    let status = Command::new(program)
+        .args(words)
+        .arg(notes_path)
+        .status()
+        .map_err(editor_error)?;
+    if !status.success() {
+        return Err(editor_error(io::Error::other(format!(
+            "the editor exited with {status}. The notes were not changed"
+        ))));
+    }
+    Ok(())
+}
+
+/// # input_notes
+/// prompts the user to enter notes one line at a time, ending with a line
+/// holding only '.'. Used when no editor is set. Ending straight away
+/// removes the notes.
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user
+/// # Returns
+/// `Ok(notes)` where notes are the entered lines joined by newlines, else
+/// the error reading the user's input failed with.
+pub fn input_notes(console: &mut dyn Console) -> TodoResult<String> {
+    console.println(&format!(
+        "\nEnter the notes one line at a time and a line with only '{END_OF_NOTES}' to finish"
+    ));
+    let mut lines = Vec::<String>::new();
+    loop {
+        let line = console.input_str("> ")?;
+        if line == END_OF_NOTES {
+            return Ok(lines.join("\n"));
+        }
+        lines.push(line);
+    }
+}
+
+/// The operations on the notes of a task i.e., free-form text such as
+/// context, links or acceptance criteria kept next to its one-line name.
+impl Tasks {
+    /// # set_notes
+    /// sets or removes the notes of the active task with id `task_id` and
+    /// updates its `last_time_modified`. The notes are cleaned first (see
+    /// `clean_notes`), so blank notes remove them.
+    /// # Arguments
+    /// - **task_id**: the id of the task
+    /// - **notes**: the new notes of the task, or `None` to remove them
+    /// # Returns
+    /// `Ok(task)` where task is a reference to the changed task if an
+    /// active task with id `task_id` exists else `TodoError::NotFound`.
+    pub fn set_notes(&mut self, task_id: TaskId, notes: Option<&str>) -> TodoResult<&Task> {
+        let target_task = self
+            .active
+            .iter_mut()
+            .find(|task| task.id == task_id)
+            .ok_or(TodoError::NotFound(task_id))?;
+        target_task.notes = notes.and_then(clean_notes);
+        target_task.last_time_modified = Local::now();
+        Ok(target_task)
+    }
+}
+
+/// # edit_notes
+/// edits the notes of an active task. A view of the active tasks is printed
+/// out to the user and the user is prompted to enter the task ID of the
+/// target task. Its notes are then opened in the editor set in `$VISUAL` or
+/// `$EDITOR`, or entered line by line if neither is set (see `input_notes`).
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the result
+/// - **name_in**: The name of the user
+/// - **tasks**: A mutable ref to Tasks which stores the active tasks.
+/// # Returns
+/// `Ok(task_id)` where task_id is the id of the changed task if the function
+/// completes successfully else a `TodoError`.
+pub fn edit_notes(
+    console: &mut dyn Console,
+    name_in: &str,
+    tasks: &mut Tasks,
+) -> TodoResult<TaskId> {
+    let prompt = "Please enter the Task ID of the task whose notes you wish to edit: ";
+    let task_id = extract_task(console, name_in, prompt, &tasks.active)?;
+    let current_notes = tasks
+        .find(task_id)
+        .and_then(|task| task.notes.clone())
+        .unwrap_or_default();
+    let notes = match ret_editor() {
+        Some(editor) => edit_in_editor(&editor, &current_notes)?,
+        None => {
+            if !current_notes.is_empty() {
+                console.println("\nThe current notes, which the entered lines replace:");
+                console.println(&current_notes);
+            }
+            input_notes(console)?
+        }
+    };
+    let target_task = tasks.set_notes(task_id, Some(&notes))?;
+    console.println(&format!(
+        "\nNotes of task {} have been updated",
+        target_task.id
+    ));
+    console.println(&target_task.show_details());
+    Ok(task_id)
+}
+
+/// # view_task
+/// prompts the user to enter a Task ID and prints the detailed view of the
+/// task with that id whatever its status, including its notes (see
+/// `Task::show_details`).
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the task
+/// - **name_in**: The name of the user
+/// - **tasks**: A ref to Tasks which stores the tasks
+/// # Returns
+/// `Ok(())` if the function completes successfully else a `TodoError`, which
+/// is `TodoError::NotFound` if no task has the entered id.
+pub fn view_task(console: &mut dyn Console, name_in: &str, tasks: &Tasks) -> TodoResult<()> {
+    let task_id = console.input_num("Please enter the Task ID of the task you wish to view: ")?;
+    let task = tasks.find(task_id).ok_or(TodoError::NotFound(task_id))?;
+    console.println(&format!(
+        "\n{name_in}, the details of task {task_id} are printed below"
+    ));
+    console.println(&task.show_details());
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::inputs::prelude::Console;
+use crate::internal::tasks::structs::{TaskId, TaskStatus, Tasks};
+use crate::internal::tasks::subtasks::SubtaskPolicy;
+use crate::internal::tasks::utils::extract_task;
+
+/// # input_subtask_policy
+/// asks the user what to do with the open subtasks of the active task with
+/// id `task_id` before it is completed or deleted (see
+/// `Tasks::resolve_subtasks`).
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user
+/// - **tasks**: A ref to Tasks which stores the task and its subtasks
+/// - **task_id**: The id of the task being completed or deleted
+/// - **status**: `TaskStatus::Completed` if the task is being completed else
+///   `TaskStatus::Deleted`
+/// # Returns
+/// `Ok(policy)` where policy is the selected `SubtaskPolicy`, which is
+/// `SubtaskPolicy::Block` without asking if the task has no open subtasks,
+/// else the error reading the user's input failed with.
+pub fn input_subtask_policy(
+    console: &mut dyn Console,
+    tasks: &Tasks,
+    task_id: TaskId,
+    status: TaskStatus,
+) -> TodoResult<SubtaskPolicy> {
+    let open_count = tasks.open_subtasks(task_id).len();
+    if open_count == 0 {
+        return Ok(SubtaskPolicy::Block);
+    }
+    let cascade = if status == TaskStatus::Completed {
+        "Complete the subtasks too"
+    } else {
+        "Delete the subtasks too"
+    };
+    let options = [cascade, "Keep the task open", "Detach the subtasks"];
+    let prompt = format!("Task {task_id} has {open_count} open subtasks. Select an option: ");
+    let choice = console.input_option(&prompt, &options, "Subtasks")?;
+    Ok(match options.iter().position(|&option| option == choice) {
+        Some(0) => SubtaskPolicy::Cascade,
+        Some(2) => SubtaskPolicy::Detach,
+        _ => SubtaskPolicy::Block,
+    })
+}
+
+/// # set_parent
+/// makes an active task a subtask of another active task or a top level
+/// task. A view of the active tasks is printed out to the user and the user
+/// is prompted to enter the task ID of the target task before being prompted
+/// to enter the task ID of its parent (see `Tasks::set_parent`).
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the result
+/// - **name_in**: The name of the user
+/// - **tasks**: A mutable ref to Tasks which stores the active tasks.
+/// # Returns
+/// `Ok(task_id)` where task_id is the id of the changed task if the function
+/// completes successfully else a `TodoError`.
+pub fn set_parent(
+    console: &mut dyn Console,
+    name_in: &str,
+    tasks: &mut Tasks,
+) -> TodoResult<TaskId> {
+    let prompt = "Please enter the Task ID of the task whose parent you wish to set: ";
+    let task_id = extract_task(console, name_in, prompt, &tasks.active)?;
+    let parent_id =
+        console.input_num("Enter the Task ID of the parent task (0 for no parent): ")?;
+    let parent = match parent_id {
+        0 => None,
+        parent_id if parent_id > 0 => Some(parent_id),
+        _ => return Err(TodoError::invalid(format!("invalid Task ID '{parent_id}'"))),
+    };
+    let target_task = tasks.set_parent(task_id, parent)?;
+    console.println(&format!(
+        "\nTask with details {} has been updated",
+        target_task.show()
+    ));
+    Ok(task_id)
+}
@@ -1,10 +1,39 @@
 pub use super::add::add;
-pub use super::check::check;
-pub use super::delete::delete;
-pub use super::edit::edit;
-pub use super::load::load;
-pub use super::save::save;
+pub use super::check::{check, check_task};
+pub use super::dates::parse_due;
+pub use super::delete::{delete, delete_task};
+pub use super::dependencies::parse_dependencies;
+pub use super::depends::{input_unlink, set_dependencies};
+pub use super::due::set_due;
+pub use super::edit::{edit, edit_task};
+pub use super::events::{load_events, log_events, replay, task_events, Event, EventKind};
+pub use super::filter::{view_filtered, view_query};
+pub use super::format::{from_line, to_line, to_tsv};
+pub use super::fuzzy::{fuzzy_match, highlight, SearchHit};
+pub use super::labels::{Filter, Labels};
+pub use super::load::{load, load_tasks};
+pub use super::migrate::migrate;
+pub use super::notes::{edit_in_editor, edit_notes, input_notes, ret_editor, view_task};
+pub use super::parent::{input_subtask_policy, set_parent};
+pub use super::priority::set_priority;
+pub use super::purge::purge;
+pub use super::query::Query;
+pub use super::recurrence::{Frequency, Recurrence};
+pub use super::repeat::set_recurrence;
+pub use super::restore::{reopen, restore};
+pub use super::save::{save, save_task};
+pub use super::search::search;
 pub use super::structs;
+pub use super::structs::{TaskId, Tasks};
+pub use super::subtasks::SubtaskPolicy;
+pub use super::table::{print_table, show_table, TableRow};
+pub use super::timeline::view_history;
+pub use super::timer::{start_timer, stop_timer};
+pub use super::timesheet::{
+    parse_since, show_timesheet, to_csv, view_timesheet, Grouping, TimesheetEntry,
+};
+pub use super::undo::{redo, undo};
+pub use super::undo_stack::{record_undo, UndoStack};
+pub use super::urgency::urgency;
 pub use super::utils::ret_last_task_id;
-pub use super::view::view;
-pub use super::structs::Tasks;
+pub use super::view::{view, view_by_urgency, view_tree};
@@ -0,0 +1,49 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::inputs::prelude::Console;
+use crate::internal::tasks::structs::{Priority, TaskId, Tasks};
+use crate::internal::tasks::utils::extract_task;
+
+/// # input_priority
+/// prompts the user to enter a priority i.e., high, medium, low or their
+/// first letters. An empty input means the task has no priority.
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user
+/// # Returns
+/// `Ok(priority)` where priority is the entered priority, else a `TodoError`
+/// if the input could not be read or is not a priority.
+pub fn input_priority(console: &mut dyn Console) -> TodoResult<Priority> {
+    let prompt = "Enter a priority i.e., high, medium or low (leave empty for no priority): ";
+    let priority_input = console.input_str(prompt)?;
+    Priority::parse(&priority_input).ok_or_else(|| {
+        TodoError::invalid(format!(
+            "'{priority_input}' is not a priority. Expected high, medium, low or none"
+        ))
+    })
+}
+
+/// # set_priority
+/// sets the priority of an active task. A view of the active tasks is printed
+/// out to the user and the user is prompted to enter the task ID of the target
+/// task before being prompted to enter its priority (see `Tasks::set_priority`).
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the result
+/// - **name_in**: The name of the user
+/// - **tasks**: A mutable ref to Tasks which stores the active tasks.
+/// # Returns
+/// `Ok(task_id)` where task_id is the id of the changed task if the function
+/// completes successfully else a `TodoError`.
+pub fn set_priority(
+    console: &mut dyn Console,
+    name_in: &str,
+    tasks: &mut Tasks,
+) -> TodoResult<TaskId> {
+    let prompt = "Please enter the Task ID of the task whose priority you wish to set: ";
+    let task_id = extract_task(console, name_in, prompt, &tasks.active)?;
+    let priority = input_priority(console)?;
+    let target_task = tasks.set_priority(task_id, priority)?;
+    console.println(&format!(
+        "\nTask with details {} has been updated",
+        target_task.show()
+    ));
+    Ok(task_id)
+}
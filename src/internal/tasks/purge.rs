@@ -0,0 +1,51 @@
+use crate::internal::config::load_config;
+use crate::internal::error::TodoResult;
+use crate::internal::inputs::prelude::Console;
+use crate::internal::tasks::structs::{TaskId, Tasks};
+use chrono::{Local, TimeDelta};
+
+/// # purge
+/// permanently removes the tasks that were deleted more than
+/// `purge_after_days` days ago as set in 'config.json' (see `Tasks::purge`).
+/// The user is told how many tasks will be purged and asked to confirm first.
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the result
+/// - **name_in**: The name of the user
+/// - **tasks**: A mutable ref to Tasks which stores the active, completed and deleted tasks.
+/// # Returns
+/// `Ok(task_ids)` where task_ids are the ids of the purged tasks and of the
+/// tasks that referred to them, which is empty if nothing was purged, else
+/// a `TodoError`.
+pub fn purge(
+    console: &mut dyn Console,
+    name_in: &str,
+    tasks: &mut Tasks,
+) -> TodoResult<Vec<TaskId>> {
+    let purge_after_days = load_config().purge_after_days;
+    let age = TimeDelta::days(i64::from(purge_after_days));
+    let now = Local::now();
+    let expired_count = tasks
+        .deleted
+        .iter()
+        .filter(|task| task.last_time_modified < now - age)
+        .count();
+    if expired_count == 0 {
+        console.println(&format!(
+            "\n{name_in}, no tasks were deleted more than {purge_after_days} days ago"
+        ));
+        return Ok(Vec::new());
+    }
+    let options = ["Purge them", "Keep them"];
+    let prompt = format!(
+        "{expired_count} tasks were deleted more than {purge_after_days} days ago and will be \
+         removed permanently. Select an option: "
+    );
+    if console.input_option(&prompt, &options, "Purge")? != "Purge them" {
+        return Ok(Vec::new());
+    }
+    let changed = tasks.purge(age, now);
+    console.println(&format!(
+        "\n{name_in}, {expired_count} deleted tasks have been purged"
+    ));
+    Ok(changed)
+}
@@ -0,0 +1,701 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::tasks::dates::parse_due;
+use crate::internal::tasks::labels::in_project;
+use crate::internal::tasks::structs::{Priority, Task, TaskId, TaskStatus};
+use chrono::{DateTime, Local, NaiveDate};
+
+/// the fields that can be used in a condition of a query.
+const FIELDS: &str =
+    "status, name, tag, project, priority, due, created, modified, finished, id or parent";
+
+/// enum `TokenKind`
+/// The kinds of token a query is made of.
+///
+/// # Variants
+/// - **LParen**: '('
+/// - **RParen**: ')'
+/// - **And**: the keyword 'and'
+/// - **Or**: the keyword 'or'
+/// - **Not**: the keyword 'not'
+/// - **Term**: a condition e.g., 'tag:urgent', '+urgent' or 'name~"deploy"'
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Term,
+}
+
+/// # Token
+/// a token of a query.
+///
+/// # Members
+/// - **kind**: the kind of the token
+/// - **text**: the text of the token with the quotes of quoted parts removed
+/// - **op_idx**: the byte index in `text` of the first ':' or '~' outside
+///   quotes, which separates the field of a condition from its value
+/// - **position**: the offset in characters of the token in the query
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    text: String,
+    op_idx: Option<usize>,
+    position: usize,
+}
+
+/// # tokenize
+/// splits `query` into tokens. Terms run until whitespace or a parenthesis,
+/// except inside double quotes where `\"` and `\\` are escapes.
+fn tokenize(query: &str) -> TodoResult<Vec<Token>> {
+    let mut tokens = Vec::<Token>::new();
+    let mut chars = query.chars().enumerate().peekable();
+    while let Some(&(position, next_char)) = chars.peek() {
+        if next_char.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if next_char == '(' || next_char == ')' {
+            chars.next();
+            tokens.push(Token {
+                kind: if next_char == '(' {
+                    TokenKind::LParen
+                } else {
+                    TokenKind::RParen
+                },
+                text: next_char.to_string(),
+                op_idx: None,
+                position,
+            });
+            continue;
+        }
+
+        // read a term or keyword
+        let mut text = String::new();
+        let mut op_idx = None;
+        let mut quoted = false;
+        while let Some(&(char_idx, term_char)) = chars.peek() {
+            if term_char.is_whitespace() || term_char == '(' || term_char == ')' {
+                break;
+            }
+            chars.next();
+            if term_char != '"' {
+                if (term_char == ':' || term_char == '~') && op_idx.is_none() {
+                    op_idx = Some(text.len());
+                }
+                text.push(term_char);
+                continue;
+            }
+            // read the quoted part of the term
+            quoted = true;
+            loop {
+                match chars.next() {
+                    Some((_, '"')) => break,
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, escaped)) => text.push(escaped),
+                        None => break,
+                    },
+                    Some((_, quoted_char)) => text.push(quoted_char),
+                    None => {
+                        return Err(query_error(query, char_idx, "this quote is never closed"));
+                    }
+                }
+            }
+        }
+        let kind = match text.to_lowercase().as_str() {
+            "and" if !quoted => TokenKind::And,
+            "or" if !quoted => TokenKind::Or,
+            "not" if !quoted => TokenKind::Not,
+            _ => TokenKind::Term,
+        };
+        tokens.push(Token {
+            kind,
+            text,
+            op_idx,
+            position,
+        });
+    }
+    Ok(tokens)
+}
+
+/// # query_error
+/// creates the `TodoError::Query` for an error at `position` in `query`.
+fn query_error(query: &str, position: usize, message: &str) -> TodoError {
+    TodoError::Query {
+        query: query.to_string(),
+        position,
+        message: message.to_string(),
+    }
+}
+
+/// enum `DateField`
+/// The date fields of a `Task` that can be used in a query.
+///
+/// # Variants
+/// - **Due**: `Task::due`
+/// - **Created**: `Task::time_created`
+/// - **Modified**: `Task::last_time_modified`
+/// - **Finished**: `Task::time_finished`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateField {
+    Due,
+    Created,
+    Modified,
+    Finished,
+}
+
+impl DateField {
+    /// # get
+    /// returns the value of the field in `task`.
+    pub fn get(&self, task: &Task) -> Option<DateTime<Local>> {
+        match self {
+            DateField::Due => task.due.get(),
+            DateField::Created => Some(task.time_created),
+            DateField::Modified => Some(task.last_time_modified),
+            DateField::Finished => task.time_finished.get(),
+        }
+    }
+}
+
+/// enum `DateTest`
+/// A test on a date field. Dates are compared by calendar day.
+///
+/// # Variants
+/// - **Unset**: the field has no date e.g., `due:none`
+/// - **Set**: the field has a date e.g., `due:any`
+/// - **On**: the date is on the given day e.g., `due:today`
+/// - **Before**: the date is before the given day e.g., `due.before:friday`
+/// - **After**: the date is after the given day e.g., `created.after:2024-01-31`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateTest {
+    Unset,
+    Set,
+    On(NaiveDate),
+    Before(NaiveDate),
+    After(NaiveDate),
+}
+
+/// enum `Condition`
+/// A single condition on the fields of a `Task`.
+///
+/// # Variants
+/// - **Status**: the task has the status e.g., `status:active`
+/// - **NameIs**: the name equals the text, ignoring case e.g., `name:deploy`
+/// - **NameHas**: the name contains the text, ignoring case e.g., `name~deploy`
+/// - **Tag**: the task has the tag, or no tags for `None` e.g., `tag:urgent`
+///   or `+urgent`
+/// - **Project**: the task is in the project or one of its sub projects, or
+///   has no project for `None` e.g., `project:work`
+/// - **Priority**: the priority of the task compares to the priority as
+///   given by the `Ordering` e.g., `priority:high` (equal) or
+///   `priority.above:low` (greater)
+/// - **Date**: the date field passes the test e.g., `due.before:friday`
+/// - **Id**: the task has the Task ID e.g., `id:12`
+/// - **Parent**: the task is a subtask of the task with the Task ID, or a
+///   top level task for `None` e.g., `parent:12`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Status(TaskStatus),
+    NameIs(String),
+    NameHas(String),
+    Tag(Option<String>),
+    Project(Option<String>),
+    Priority(std::cmp::Ordering, Priority),
+    Date(DateField, DateTest),
+    Id(TaskId),
+    Parent(Option<TaskId>),
+}
+
+impl Condition {
+    /// # matches
+    /// checks if `task` passes the condition.
+    pub fn matches(&self, task: &Task) -> bool {
+        match self {
+            Condition::Status(status) => task.status == *status,
+            Condition::NameIs(text) => task.name.to_lowercase() == text.to_lowercase(),
+            Condition::NameHas(text) => task.name.to_lowercase().contains(&text.to_lowercase()),
+            Condition::Tag(Some(tag)) => task.tags.contains(tag),
+            Condition::Tag(None) => task.tags.is_empty(),
+            Condition::Project(Some(parent)) => task
+                .project
+                .as_deref()
+                .is_some_and(|project| in_project(project, parent)),
+            Condition::Project(None) => task.project.is_none(),
+            Condition::Priority(ordering, priority) => task.priority.cmp(priority) == *ordering,
+            Condition::Date(field, test) => {
+                let day = field.get(task).map(|date| date.date_naive());
+                match (test, day) {
+                    (DateTest::Unset, day) => day.is_none(),
+                    (DateTest::Set, day) => day.is_some(),
+                    (DateTest::On(target), Some(day)) => day == *target,
+                    (DateTest::Before(target), Some(day)) => day < *target,
+                    (DateTest::After(target), Some(day)) => day > *target,
+                    (_, None) => false,
+                }
+            }
+            Condition::Id(task_id) => task.id == *task_id,
+            Condition::Parent(parent) => task.parent == *parent,
+        }
+    }
+}
+
+/// enum `Expr`
+/// The abstract syntax tree of a query.
+///
+/// # Variants
+/// - **And**: both expressions match
+/// - **Or**: either expression matches
+/// - **Not**: the expression does not match
+/// - **Cond**: a single condition
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Cond(Condition),
+}
+
+impl Expr {
+    /// # matches
+    /// evaluates the expression against `task`.
+    pub fn matches(&self, task: &Task) -> bool {
+        match self {
+            Expr::And(left, right) => left.matches(task) && right.matches(task),
+            Expr::Or(left, right) => left.matches(task) || right.matches(task),
+            Expr::Not(expr) => !expr.matches(task),
+            Expr::Cond(condition) => condition.matches(task),
+        }
+    }
+
+    /// # mentions_status
+    /// checks if any condition in the expression is on the status of a task.
+    pub fn mentions_status(&self) -> bool {
+        match self {
+            Expr::And(left, right) | Expr::Or(left, right) => {
+                left.mentions_status() || right.mentions_status()
+            }
+            Expr::Not(expr) => expr.mentions_status(),
+            Expr::Cond(condition) => matches!(condition, Condition::Status(_)),
+        }
+    }
+}
+
+/// # Parser
+/// a recursive descent parser that turns the tokens of a query into an `Expr`
+/// with the grammar
+///
+/// ```text
+/// query   := or?
+/// or      := and ("or" and)*
+/// and     := not ("and"? not)*
+/// not     := "not" not | primary
+/// primary := "(" or ")" | term
+/// ```
+///
+/// # Members
+/// - **query**: the query being parsed, used in error messages
+/// - **tokens**: the tokens of the query
+/// - **next**: the index of the next token to be parsed
+/// - **now**: the current local datetime relative dates are resolved against
+struct Parser<'a> {
+    query: &'a str,
+    tokens: Vec<Token>,
+    next: usize,
+    now: DateTime<Local>,
+}
+
+impl Parser<'_> {
+    /// # peek
+    /// returns the next token without consuming it.
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next)
+    }
+
+    /// # error_at
+    /// creates an error pointing at `token`, or at the end of the query if
+    /// there is no token.
+    fn error_at(&self, token: Option<&Token>, message: &str) -> TodoError {
+        let position = match token {
+            Some(token) => token.position,
+            None => self.query.chars().count(),
+        };
+        query_error(self.query, position, message)
+    }
+
+    fn parse_or(&mut self) -> TodoResult<Expr> {
+        let mut expr = self.parse_and()?;
+        while self.peek().is_some_and(|token| token.kind == TokenKind::Or) {
+            self.next += 1;
+            let right = self.parse_and()?;
+            expr = Expr::Or(Box::new(expr), Box::new(right));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> TodoResult<Expr> {
+        let mut expr = self.parse_not()?;
+        loop {
+            match self.peek().map(|token| &token.kind) {
+                Some(TokenKind::And) => self.next += 1,
+                // terms next to each other are joined with 'and'
+                Some(TokenKind::Term | TokenKind::Not | TokenKind::LParen) => (),
+                _ => break,
+            }
+            let right = self.parse_not()?;
+            expr = Expr::And(Box::new(expr), Box::new(right));
+        }
+        Ok(expr)
+    }
+
+    fn parse_not(&mut self) -> TodoResult<Expr> {
+        if self
+            .peek()
+            .is_some_and(|token| token.kind == TokenKind::Not)
+        {
+            self.next += 1;
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> TodoResult<Expr> {
+        let token = match self.peek() {
+            Some(token) => token.clone(),
+            None => return Err(self.error_at(None, "expected a condition but the query ended")),
+        };
+        self.next += 1;
+        match token.kind {
+            TokenKind::LParen => {
+                let expr = self.parse_or()?;
+                match self.peek() {
+                    Some(closing) if closing.kind == TokenKind::RParen => {
+                        self.next += 1;
+                        Ok(expr)
+                    }
+                    other => Err(self.error_at(
+                        other,
+                        &format!(
+                            "expected ')' to close the '(' at position {}",
+                            token.position
+                        ),
+                    )),
+                }
+            }
+            TokenKind::Term
+                if token.op_idx.is_none()
+                    && token.text.len() > 1
+                    && token.text.starts_with('-') =>
+            {
+                // '-tag' is short for 'not tag:tag'
+                let tag = Condition::Tag(Some(token.text[1..].to_string()));
+                Ok(Expr::Not(Box::new(Expr::Cond(tag))))
+            }
+            TokenKind::Term => self.parse_term(&token).map(Expr::Cond),
+            _ => Err(self.error_at(
+                Some(&token),
+                &format!("expected a condition but found '{}'", token.text),
+            )),
+        }
+    }
+
+    /// # parse_date
+    /// parses the value of a condition on a date field.
+    fn parse_date(
+        &self,
+        token: &Token,
+        modifier: Option<&str>,
+        value: &str,
+    ) -> TodoResult<DateTest> {
+        let lowered = value.to_lowercase();
+        if modifier.is_none() && (lowered == "none" || lowered == "any") {
+            return Ok(if lowered == "none" {
+                DateTest::Unset
+            } else {
+                DateTest::Set
+            });
+        }
+        let day = parse_due(value, self.now)
+            .map_err(|e| {
+                let message = match e {
+                    TodoError::InvalidInput(message) => message,
+                    other => other.to_string(),
+                };
+                self.error_at(Some(token), &message)
+            })?
+            .date_naive();
+        match modifier {
+            None => Ok(DateTest::On(day)),
+            Some("before") => Ok(DateTest::Before(day)),
+            Some("after") => Ok(DateTest::After(day)),
+            Some(other) => Err(self.error_at(
+                Some(token),
+                &format!("unknown modifier '{other}'. Expected before or after"),
+            )),
+        }
+    }
+
+    /// # parse_term
+    /// parses a term i.e., `field[.modifier]:value`, `name~text`, `+tag`
+    /// or `-tag` to a `Condition`.
+    fn parse_term(&self, token: &Token) -> TodoResult<Condition> {
+        let error = |message: &str| self.error_at(Some(token), message);
+
+        // '+tag' and '-tag' are short for 'tag:tag' and 'not tag:tag'
+        let op_idx = match token.op_idx {
+            Some(op_idx) => op_idx,
+            None => {
+                if let Some(tag) = token.text.strip_prefix('+').filter(|tag| !tag.is_empty()) {
+                    return Ok(Condition::Tag(Some(tag.to_string())));
+                }
+                return Err(error(&format!(
+                    "expected a condition like field:value but found '{}'",
+                    token.text
+                )));
+            }
+        };
+        let field_spec = token.text[..op_idx].to_lowercase();
+        let op = &token.text[op_idx..op_idx + 1];
+        let value = &token.text[op_idx + 1..];
+        let (field, modifier) = match field_spec.split_once('.') {
+            Some((field, modifier)) => (field, Some(modifier)),
+            None => (field_spec.as_str(), None),
+        };
+        if op == "~" && field != "name" {
+            return Err(error("'~' can only be used with name e.g., name~deploy"));
+        }
+        if value.is_empty() {
+            return Err(error(&format!("the condition on '{field}' has no value")));
+        }
+        if modifier.is_some()
+            && !matches!(
+                field,
+                "priority" | "due" | "created" | "modified" | "finished"
+            )
+        {
+            return Err(error(&format!("'{field}' does not take a modifier")));
+        }
+        let none_or = |value: &str| {
+            if value.eq_ignore_ascii_case("none") {
+                None
+            } else {
+                Some(value.to_string())
+            }
+        };
+
+        match field {
+            "status" => TaskStatus::parse(value)
+                .map(Condition::Status)
+                .ok_or_else(|| {
+                    error(&format!(
+                        "unknown status '{value}'. Expected active, completed or deleted"
+                    ))
+                }),
+            "name" if op == "~" => Ok(Condition::NameHas(value.to_string())),
+            "name" => Ok(Condition::NameIs(value.to_string())),
+            "tag" => Ok(Condition::Tag(none_or(value))),
+            "project" => Ok(Condition::Project(none_or(value))),
+            "priority" => {
+                let priority = Priority::parse(value).ok_or_else(|| {
+                    error(&format!(
+                        "unknown priority '{value}'. Expected high, medium, low or none"
+                    ))
+                })?;
+                let ordering = match modifier {
+                    None => std::cmp::Ordering::Equal,
+                    Some("above") => std::cmp::Ordering::Greater,
+                    Some("below") => std::cmp::Ordering::Less,
+                    Some(other) => {
+                        return Err(error(&format!(
+                            "unknown modifier '{other}'. Expected above or below"
+                        )))
+                    }
+                };
+                Ok(Condition::Priority(ordering, priority))
+            }
+            "due" | "created" | "modified" | "finished" => {
+                let date_field = match field {
+                    "due" => DateField::Due,
+                    "created" => DateField::Created,
+                    "modified" => DateField::Modified,
+                    _ => DateField::Finished,
+                };
+                Ok(Condition::Date(
+                    date_field,
+                    self.parse_date(token, modifier, value)?,
+                ))
+            }
+            "id" => value
+                .parse::<TaskId>()
+                .map(Condition::Id)
+                .map_err(|_| error(&format!("'{value}' is not a Task ID"))),
+            "parent" if value.eq_ignore_ascii_case("none") => Ok(Condition::Parent(None)),
+            "parent" => value
+                .parse::<TaskId>()
+                .map(|parent| Condition::Parent(Some(parent)))
+                .map_err(|_| error(&format!("'{value}' is not a Task ID"))),
+            _ => Err(error(&format!(
+                "unknown field '{field}'. Expected {FIELDS}"
+            ))),
+        }
+    }
+}
+
+/// # Query
+/// a parsed query that selects tasks by their fields e.g.,
+/// `status:active and (tag:urgent or due.before:friday) and name~"deploy"`.
+///
+/// A query is made of conditions joined with `and`, `or` and `not` and
+/// grouped with parentheses. Conditions next to each other are joined with
+/// `and`. The conditions are
+///
+/// - `status:active|completed|deleted`
+/// - `name:text` (equals) and `name~text` (contains), ignoring case
+/// - `tag:x` or `+x` (has the tag) and `tag:none` (has no tags)
+/// - `project:x` (in the project or a sub project) and `project:none`
+/// - `priority:p`, `priority.above:p` and `priority.below:p`
+/// - `due`, `created`, `modified` and `finished` with `:date`, `.before:date`
+///   or `.after:date` where date is anything `parse_due` accepts, compared by
+///   calendar day, and `:none` or `:any`
+/// - `id:n`, `parent:n` (a subtask of task n) and `parent:none`
+///
+/// Values with spaces are quoted e.g., `due.before:"next friday"`.
+///
+/// # Members
+/// - **expr**: the parsed expression, or `None` for an empty query which
+///   matches every task
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub expr: Option<Expr>,
+}
+
+impl Query {
+    /// # parse
+    /// parses `query` to a `Query`.
+    /// # Arguments
+    /// - **query**: the text of the query
+    /// - **now**: the current local datetime relative dates are resolved against
+    /// # Returns
+    /// `Ok(Query)` else `TodoError::Query` pointing at the offending token.
+    pub fn parse(query: &str, now: DateTime<Local>) -> TodoResult<Query> {
+        let mut parser = Parser {
+            query,
+            tokens: tokenize(query)?,
+            next: 0,
+            now,
+        };
+        if parser.tokens.is_empty() {
+            return Ok(Query { expr: None });
+        }
+        let expr = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(parser.error_at(Some(token), &format!("unexpected '{}'", token.text)));
+        }
+        Ok(Query { expr: Some(expr) })
+    }
+
+    /// # matches
+    /// checks if `task` matches the query.
+    pub fn matches(&self, task: &Task) -> bool {
+        match &self.expr {
+            Some(expr) => expr.matches(task),
+            None => true,
+        }
+    }
+
+    /// # mentions_status
+    /// checks if the query has a condition on the status of a task.
+    pub fn mentions_status(&self) -> bool {
+        self.expr.as_ref().is_some_and(Expr::mentions_status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(query: &str) -> TodoResult<Option<Expr>> {
+        Query::parse(query, Local::now()).map(|parsed| parsed.expr)
+    }
+
+    fn tag(tag: &str) -> Box<Expr> {
+        Box::new(Expr::Cond(Condition::Tag(Some(tag.to_string()))))
+    }
+
+    /// the position and message of the `TodoError::Query` `query` fails with.
+    fn error(query: &str) -> (usize, String) {
+        match parse(query) {
+            Err(TodoError::Query {
+                position, message, ..
+            }) => (position, message),
+            other => panic!("expected a query error for '{query}' but got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let expected = Expr::Or(tag("a"), Box::new(Expr::And(tag("b"), tag("c"))));
+        assert_eq!(parse("+a or +b and +c").unwrap(), Some(expected.clone()));
+        // terms next to each other are joined with 'and' just as tightly
+        assert_eq!(parse("+a or +b +c").unwrap(), Some(expected));
+        assert_eq!(
+            parse("+a and +b or +c").unwrap(),
+            Some(Expr::Or(Box::new(Expr::And(tag("a"), tag("b"))), tag("c")))
+        );
+    }
+
+    #[test]
+    fn not_binds_tighter_than_and() {
+        assert_eq!(
+            parse("not +a and +b").unwrap(),
+            Some(Expr::And(Box::new(Expr::Not(tag("a"))), tag("b")))
+        );
+        // '-tag' is the same as 'not +tag'
+        assert_eq!(parse("-a +b").unwrap(), parse("not +a and +b").unwrap());
+    }
+
+    #[test]
+    fn parentheses_group_first() {
+        assert_eq!(
+            parse("(+a or +b) and +c").unwrap(),
+            Some(Expr::And(Box::new(Expr::Or(tag("a"), tag("b"))), tag("c")))
+        );
+        assert_eq!(
+            parse("not (+a or +b)").unwrap(),
+            Some(Expr::Not(Box::new(Expr::Or(tag("a"), tag("b")))))
+        );
+    }
+
+    #[test]
+    fn keywords_are_plain_text_when_quoted() {
+        assert_eq!(
+            parse("name:\"or\"").unwrap(),
+            Some(Expr::Cond(Condition::NameIs(String::from("or"))))
+        );
+        assert_eq!(parse("").unwrap(), None);
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_token() {
+        assert_eq!(error("+a and or +b").0, 7);
+        assert_eq!(error("+a )").0, 3);
+        assert_eq!(error("colour:red").0, 0);
+        assert_eq!(error("+a status:open").0, 3);
+        assert_eq!(error("tag.before:x").0, 0);
+        // 'é' is one character, so positions count characters not bytes
+        assert_eq!(error("name:é bogus").0, 7);
+    }
+
+    #[test]
+    fn errors_at_the_end_point_past_the_query() {
+        let (position, message) = error("+a and");
+        assert_eq!(position, 6);
+        assert!(message.contains("query ended"));
+        let (position, message) = error("(+a or +b");
+        assert_eq!(position, 9);
+        assert!(message.contains("position 0"));
+        let (position, message) = error("name:\"deploy");
+        assert_eq!(position, 5);
+        assert!(message.contains("never closed"));
+    }
+}
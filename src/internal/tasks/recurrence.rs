@@ -0,0 +1,503 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::tasks::dates::{from_wall_clock, END_OF_DAY};
+use crate::internal::tasks::structs::{DateTimeOption, Task, TaskId, Tasks};
+use chrono::prelude::*;
+use chrono::{Days, Duration, Months};
+use serde::{Deserialize, Serialize};
+
+/// the words that may appear in a recurrence without changing its meaning
+/// e.g., the 'on' in 'weekly on friday'.
+const FILLER_WORDS: [&str; 3] = ["on", "the", "and"];
+
+/// the weekdays of 'every weekday'.
+const WORKDAYS: [Weekday; 5] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+];
+
+/// # recurrence_error
+/// creates the `TodoError::InvalidInput` returned for a recurrence that
+/// could not be understood.
+fn recurrence_error(input: &str) -> TodoError {
+    TodoError::invalid(format!(
+        "Could not understand the recurrence '{input}'. Try e.g., 'daily', 'weekly on mon fri', \
+         'monthly on 15', 'every 3 days after completion' or 'FREQ=WEEKLY;INTERVAL=2;BYDAY=MO'"
+    ))
+}
+
+/// enum `Frequency`
+/// The unit a recurring task repeats in.
+///
+/// # Variants
+/// - **Daily**: the task repeats every `interval` days
+/// - **Weekly**: the task repeats every `interval` weeks
+/// - **Monthly**: the task repeats every `interval` months
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+/// # Recurrence
+/// the rule a recurring task repeats by. When a recurring task is completed
+/// the next instance of the task is added with its due date shifted by the
+/// rule (see `Tasks::recur`).
+///
+/// # Members
+/// - **frequency**: the unit the task repeats in
+/// - **interval**: how many units apart the instances are, at least 1
+/// - **weekdays**: the days of the week a weekly task is due on. Empty
+///   means the weekday of the previous due date.
+/// - **month_day**: the day of the month a monthly task is due on, which is
+///   the last day for shorter months. `None` means the day of the previous
+///   due date.
+/// - **after_completion**: `true` if the next instance is due `interval`
+///   units after the task was completed instead of after its due date
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    pub interval: u32,
+    #[serde(default)]
+    pub weekdays: Vec<Weekday>,
+    #[serde(default)]
+    pub month_day: Option<u32>,
+    #[serde(default)]
+    pub after_completion: bool,
+}
+
+impl Recurrence {
+    /// # parse
+    /// parses a recurrence entered by the user. Accepted inputs include:
+    ///
+    /// - 'daily', 'weekly' or 'monthly'
+    /// - 'every day', 'every 2 weeks' or 'every 3 months'
+    /// - weekdays e.g., 'weekly on mon fri', 'every 2 weeks on friday',
+    ///   'every monday and thursday' or 'every weekday'
+    /// - a day of the month e.g., 'monthly on 15' or 'every month on the 1st'
+    /// - 'after completion' at the end e.g., 'every 3 days after completion'
+    /// - a subset of iCalendar RRULEs with FREQ (DAILY, WEEKLY or MONTHLY),
+    ///   INTERVAL, BYDAY and BYMONTHDAY e.g., 'RRULE:FREQ=WEEKLY;BYDAY=MO,FR'
+    /// # Returns
+    /// `Ok(Recurrence)` else `TodoError::InvalidInput` if `input` could not
+    /// be understood.
+    pub fn parse(input: &str) -> TodoResult<Recurrence> {
+        let input = input.trim();
+        let upper = input.to_uppercase();
+        if upper.starts_with("RRULE:") || upper.starts_with("FREQ=") {
+            return Recurrence::parse_rrule(input);
+        }
+        let lowered = input.to_lowercase().replace(',', " ");
+        let mut words = lowered
+            .split_whitespace()
+            .filter(|word| !FILLER_WORDS.contains(word))
+            .collect::<Vec<&str>>();
+        let after_completion = words.ends_with(&["after", "completion"]);
+        if after_completion {
+            words.truncate(words.len() - 2);
+        }
+
+        let (frequency, interval, rest) = match words.as_slice() {
+            ["daily", rest @ ..] => (Frequency::Daily, 1, rest),
+            ["weekly", rest @ ..] => (Frequency::Weekly, 1, rest),
+            ["monthly", rest @ ..] => (Frequency::Monthly, 1, rest),
+            ["every", "weekday"] => (Frequency::Weekly, 1, &[][..]),
+            ["every", count, unit, rest @ ..] if count.parse::<u32>().is_ok() => {
+                let interval = count.parse::<u32>().map_err(|_| recurrence_error(input))?;
+                (
+                    parse_unit(unit).ok_or_else(|| recurrence_error(input))?,
+                    interval,
+                    rest,
+                )
+            }
+            ["every", unit, rest @ ..] if parse_unit(unit).is_some() => (
+                parse_unit(unit).ok_or_else(|| recurrence_error(input))?,
+                1,
+                rest,
+            ),
+            // 'every monday and thursday'
+            ["every", rest @ ..] => (Frequency::Weekly, 1, rest),
+            _ => return Err(recurrence_error(input)),
+        };
+        let mut recurrence = Recurrence {
+            frequency,
+            interval,
+            weekdays: Vec::new(),
+            month_day: None,
+            after_completion,
+        };
+        if words == ["every", "weekday"] {
+            recurrence.weekdays = WORKDAYS.to_vec();
+        }
+        match (frequency, rest) {
+            (_, []) => (),
+            (Frequency::Weekly, weekdays) => {
+                for weekday in weekdays {
+                    let weekday = parse_weekday(weekday).ok_or_else(|| recurrence_error(input))?;
+                    recurrence.weekdays.push(weekday);
+                }
+            }
+            (Frequency::Monthly, [month_day] | ["day", month_day]) => {
+                let digits = month_day.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+                recurrence.month_day =
+                    Some(digits.parse::<u32>().map_err(|_| recurrence_error(input))?);
+            }
+            _ => return Err(recurrence_error(input)),
+        }
+        recurrence.check(input)
+    }
+
+    /// # parse_rrule
+    /// parses the supported subset of an iCalendar RRULE.
+    fn parse_rrule(input: &str) -> TodoResult<Recurrence> {
+        let rule = input.trim();
+        let rule = rule
+            .get(..6)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("RRULE:"))
+            .map_or(rule, |_| &rule[6..]);
+        let mut frequency = None;
+        let mut recurrence = Recurrence {
+            frequency: Frequency::Daily,
+            interval: 1,
+            weekdays: Vec::new(),
+            month_day: None,
+            after_completion: false,
+        };
+        for part in rule.split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| recurrence_error(input))?;
+            match key.to_uppercase().as_str() {
+                "FREQ" => frequency = parse_unit(&value.to_lowercase()),
+                "INTERVAL" => {
+                    recurrence.interval = value.parse::<u32>().map_err(|_| recurrence_error(input))?;
+                }
+                "BYDAY" => {
+                    for weekday in value.split(',') {
+                        let weekday = parse_weekday(weekday).ok_or_else(|| recurrence_error(input))?;
+                        recurrence.weekdays.push(weekday);
+                    }
+                }
+                "BYMONTHDAY" => {
+                    recurrence.month_day = Some(value.parse::<u32>().map_err(|_| recurrence_error(input))?);
+                }
+                _ => {
+                    return Err(TodoError::invalid(format!(
+                        "The RRULE part '{part}' is not supported. Use FREQ, INTERVAL, BYDAY or BYMONTHDAY"
+                    )))
+                }
+            }
+        }
+        recurrence.frequency = frequency.ok_or_else(|| recurrence_error(input))?;
+        if (!recurrence.weekdays.is_empty() && recurrence.frequency != Frequency::Weekly)
+            || (recurrence.month_day.is_some() && recurrence.frequency != Frequency::Monthly)
+        {
+            return Err(recurrence_error(input));
+        }
+        recurrence.check(input)
+    }
+
+    /// # check
+    /// checks that the interval and day of the month are in range and that
+    /// a rule counted from completion has no weekdays or day of the month.
+    fn check(mut self, input: &str) -> TodoResult<Recurrence> {
+        let fixed_days = !self.weekdays.is_empty() || self.month_day.is_some();
+        if self.interval == 0
+            || self.month_day.is_some_and(|day| !(1..=31).contains(&day))
+            || (self.after_completion && fixed_days)
+        {
+            return Err(recurrence_error(input));
+        }
+        self.weekdays
+            .sort_by_key(|weekday| weekday.num_days_from_monday());
+        self.weekdays.dedup();
+        Ok(self)
+    }
+
+    /// # show
+    /// describes the recurrence e.g., 'every 2 weeks on Mon, Fri'. The
+    /// description can be parsed back with `Recurrence::parse`.
+    pub fn show(&self) -> String {
+        let unit = match self.frequency {
+            Frequency::Daily => "day",
+            Frequency::Weekly => "week",
+            Frequency::Monthly => "month",
+        };
+        let mut shown = match self.interval {
+            1 => format!("every {unit}"),
+            interval => format!("every {interval} {unit}s"),
+        };
+        if !self.weekdays.is_empty() {
+            let weekdays = self
+                .weekdays
+                .iter()
+                .map(|weekday| weekday.to_string())
+                .collect::<Vec<String>>();
+            shown.push_str(&format!(" on {}", weekdays.join(", ")));
+        }
+        if let Some(month_day) = self.month_day {
+            shown.push_str(&format!(" on day {month_day}"));
+        }
+        if self.after_completion {
+            shown.push_str(" after completion");
+        }
+        shown
+    }
+
+    /// # next_date
+    /// returns the first date after `date` the rule is due on, counting
+    /// weeks and months from the week or month of `date`.
+    fn next_date(&self, date: NaiveDate) -> Option<NaiveDate> {
+        match self.frequency {
+            Frequency::Daily => date.checked_add_days(Days::new(self.interval as u64)),
+            Frequency::Weekly if self.weekdays.is_empty() => {
+                date.checked_add_days(Days::new(7 * self.interval as u64))
+            }
+            Frequency::Weekly => {
+                let week_start = |day: NaiveDate| {
+                    day - Duration::days(day.weekday().num_days_from_monday() as i64)
+                };
+                (1..=7 * self.interval as u64 + 7)
+                    .filter_map(|offset| date.checked_add_days(Days::new(offset)))
+                    .find(|day| {
+                        let weeks_apart = (week_start(*day) - week_start(date)).num_days() / 7;
+                        self.weekdays.contains(&day.weekday())
+                            && weeks_apart % self.interval as i64 == 0
+                    })
+            }
+            Frequency::Monthly => {
+                let month_day = self.month_day.unwrap_or(date.day());
+                let month_start = date.with_day(1)?;
+                (0..=2)
+                    .filter_map(|step| {
+                        month_start.checked_add_months(Months::new(step * self.interval))
+                    })
+                    .map(|month| {
+                        // use the last day of months shorter than `month_day`
+                        (1..=month_day)
+                            .rev()
+                            .find_map(|day| month.with_day(day))
+                            .unwrap_or(month)
+                    })
+                    .find(|day| *day > date)
+            }
+        }
+    }
+
+    /// # next_due
+    /// returns the due date of the instance after a task that was due at
+    /// `due` and completed at `completed`. Rules counted from completion
+    /// start at `completed`; other rules start at `due` and skip the dates
+    /// that already passed when the task was completed, so that a task
+    /// completed late is not due again straight away. The time of day of
+    /// `due` is kept, or the end of the day is used if there is no due date.
+    /// # Returns
+    /// `Some(due)` where due is the next due date else `None` if it is out
+    /// of range.
+    pub fn next_due(
+        &self,
+        due: Option<DateTime<Local>>,
+        completed: DateTime<Local>,
+    ) -> Option<DateTime<Local>> {
+        let (hour, minute, second) = END_OF_DAY;
+        let time = match due {
+            Some(due) => due.time(),
+            None => NaiveTime::from_hms_opt(hour, minute, second)?,
+        };
+        let anchor = match due {
+            Some(due) if !self.after_completion => due,
+            _ => completed,
+        };
+        let mut date = anchor.date_naive();
+        loop {
+            date = self.next_date(date)?;
+            let next_due = from_wall_clock(date.and_time(time), &Local)?;
+            if self.after_completion || next_due > completed {
+                return Some(next_due);
+            }
+        }
+    }
+}
+
+/// # parse_unit
+/// parses the unit of a recurrence e.g., 'day', 'weeks' or 'monthly'.
+fn parse_unit(unit: &str) -> Option<Frequency> {
+    match unit {
+        "day" | "days" | "daily" => Some(Frequency::Daily),
+        "week" | "weeks" | "weekly" => Some(Frequency::Weekly),
+        "month" | "months" | "monthly" => Some(Frequency::Monthly),
+        _ => None,
+    }
+}
+
+/// # parse_weekday
+/// parses a weekday such as 'friday', 'fri' or the RRULE code 'FR'.
+fn parse_weekday(weekday: &str) -> Option<Weekday> {
+    let lowered = weekday.to_lowercase();
+    let codes = ["mo", "tu", "we", "th", "fr", "sa", "su"];
+    match codes.iter().position(|code| *code == lowered) {
+        Some(days_from_monday) => Weekday::try_from(days_from_monday as u8).ok(),
+        None => lowered.parse::<Weekday>().ok(),
+    }
+}
+
+/// The operations on recurring tasks.
+impl Tasks {
+    /// # set_recurrence
+    /// sets the rule the active task with id `task_id` repeats by, or stops
+    /// it from repeating if `recurrence` is `None`, and updates its
+    /// `last_time_modified`. As each instance of a recurring task is copied
+    /// from the one before it, this changes or stops the rest of the series.
+    /// # Arguments
+    /// - **task_id**: the id of the task
+    /// - **recurrence**: the new rule of the task
+    /// # Returns
+    /// `Ok(task)` where task is a reference to the changed task if an
+    /// active task with id `task_id` exists else `TodoError::NotFound`.
+    pub fn set_recurrence(
+        &mut self,
+        task_id: TaskId,
+        recurrence: Option<Recurrence>,
+    ) -> TodoResult<&Task> {
+        let target_task = self
+            .active
+            .iter_mut()
+            .find(|task| task.id == task_id)
+            .ok_or(TodoError::NotFound(task_id))?;
+        target_task.recurrence = recurrence;
+        target_task.last_time_modified = Local::now();
+        Ok(target_task)
+    }
+
+    /// # recur
+    /// adds the next instance of the completed recurring task with id
+    /// `task_id` to the active tasks. The instance is a copy of the task
+    /// with a new Task ID and its due date shifted by the rule of the task
+    /// (see `Recurrence::next_due`). It does not copy the dependencies of
+    /// the task.
+    /// # Returns
+    /// `Ok(Some(task_id))` where task_id is the id of the new instance,
+    /// `Ok(None)` if the task does not repeat, else `TodoError::NotFound` if
+    /// no completed task with id `task_id` exists.
+    pub fn recur(&mut self, task_id: TaskId) -> TodoResult<Option<TaskId>> {
+        let completed_task = self
+            .completed
+            .iter()
+            .find(|task| task.id == task_id)
+            .ok_or(TodoError::NotFound(task_id))?;
+        let Some(recurrence) = &completed_task.recurrence else {
+            return Ok(None);
+        };
+        let finished = completed_task
+            .time_finished
+            .get()
+            .unwrap_or_else(Local::now);
+        let next_due = recurrence
+            .next_due(completed_task.due.get(), finished)
+            .ok_or_else(|| TodoError::invalid(format!("Task {task_id} has no next due date")))?;
+
+        let mut next_task = Task::from_name_id(completed_task.name.clone(), 0);
+        next_task.due = DateTimeOption::DateTime(next_due);
+        next_task.priority = completed_task.priority;
+        next_task.tags = completed_task.tags.clone();
+        next_task.project = completed_task.project.clone();
+        next_task.parent = completed_task.parent;
+        next_task.recurrence = completed_task.recurrence.clone();
+        let next_task_id = self.allocate_id();
+        next_task.id = next_task_id;
+        self.active.push(next_task);
+        Ok(Some(next_task_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn at(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(year, month, day, hour, 0, 0)
+            .unwrap()
+    }
+
+    fn rule(input: &str) -> Recurrence {
+        Recurrence::parse(input).unwrap()
+    }
+
+    #[test]
+    fn monthly_uses_the_last_day_of_shorter_months() {
+        let month_end = rule("monthly on 31");
+        assert_eq!(
+            month_end.next_date(date(2024, 1, 31)),
+            Some(date(2024, 2, 29))
+        );
+        assert_eq!(
+            month_end.next_date(date(2024, 2, 29)),
+            Some(date(2024, 3, 31))
+        );
+        assert_eq!(
+            month_end.next_date(date(2024, 4, 30)),
+            Some(date(2024, 5, 31))
+        );
+        assert_eq!(
+            rule("every 2 months on 31").next_date(date(2024, 12, 31)),
+            Some(date(2025, 2, 28))
+        );
+        // a day of the month later in the same month is due first
+        assert_eq!(
+            rule("monthly on 15").next_date(date(2024, 3, 1)),
+            Some(date(2024, 3, 15))
+        );
+    }
+
+    #[test]
+    fn weekly_intervals_count_whole_weeks() {
+        assert_eq!(
+            rule("every 3 weeks").next_date(date(2024, 7, 1)),
+            Some(date(2024, 7, 22))
+        );
+        let fortnightly = rule("every 2 weeks on mon fri");
+        // 2024-07-01 is a Monday: the Friday of the same week comes next
+        assert_eq!(
+            fortnightly.next_date(date(2024, 7, 1)),
+            Some(date(2024, 7, 5))
+        );
+        // then the Monday two weeks after the week of 2024-07-01
+        assert_eq!(
+            fortnightly.next_date(date(2024, 7, 5)),
+            Some(date(2024, 7, 15))
+        );
+        assert_eq!(
+            rule("every weekday").next_date(date(2024, 7, 5)),
+            Some(date(2024, 7, 8))
+        );
+    }
+
+    #[test]
+    fn after_completion_counts_from_the_completion() {
+        let due = at(2024, 7, 1, 12);
+        let completed = at(2024, 7, 10, 9);
+        // the time of day of the due date is kept
+        assert_eq!(
+            rule("every 3 days after completion").next_due(Some(due), completed),
+            Some(at(2024, 7, 13, 12))
+        );
+        // other rules skip the dates that passed before the completion
+        assert_eq!(
+            rule("daily").next_due(Some(due), completed),
+            Some(at(2024, 7, 10, 12))
+        );
+        assert_eq!(
+            rule("weekly").next_due(Some(due), completed),
+            Some(at(2024, 7, 15, 12))
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use crate::internal::error::TodoResult;
+use crate::internal::inputs::prelude::Console;
+use crate::internal::tasks::recurrence::Recurrence;
+use crate::internal::tasks::structs::{TaskId, Tasks};
+use crate::internal::tasks::utils::extract_task;
+
+/// the prompt used to ask the user how a task repeats.
+const REPEAT_PROMPT: &str = "Enter how the task repeats e.g., 'weekly on mon fri', 'monthly on 15' \
+                             or 'every 3 days after completion' (leave empty to stop it repeating): ";
+
+/// # set_recurrence
+/// sets or stops the recurrence of an active task and with it the rest of its
+/// series. A view of the active tasks is printed out to the user and the user
+/// is prompted to enter the task ID of the target task before being prompted
+/// to enter how it repeats (see `Recurrence::parse` and `Tasks::set_recurrence`).
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the result
+/// - **name_in**: The name of the user
+/// - **tasks**: A mutable ref to Tasks which stores the active tasks.
+/// # Returns
+/// `Ok(task_id)` where task_id is the id of the changed task if the function
+/// completes successfully else a `TodoError`.
+pub fn set_recurrence(
+    console: &mut dyn Console,
+    name_in: &str,
+    tasks: &mut Tasks,
+) -> TodoResult<TaskId> {
+    let prompt = "Please enter the Task ID of the task whose recurrence you wish to set: ";
+    let task_id = extract_task(console, name_in, prompt, &tasks.active)?;
+    let repeat_input = console.input_str(REPEAT_PROMPT)?;
+    let recurrence = match repeat_input.as_str() {
+        "" => None,
+        repeat_input => Some(Recurrence::parse(repeat_input)?),
+    };
+    let target_task = tasks.set_recurrence(task_id, recurrence)?;
+    console.println(&format!(
+        "\nTask with details {} has been updated",
+        target_task.show()
+    ));
+    Ok(task_id)
+}
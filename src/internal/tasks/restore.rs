@@ -0,0 +1,49 @@
+use crate::internal::error::TodoResult;
+use crate::internal::inputs::prelude::Console;
+use crate::internal::tasks::structs::{TaskId, Tasks};
+use crate::internal::tasks::utils::extract_task;
+
+/// # restore
+/// moves a deleted task back to the active tasks. Prints out a view of the
+/// deleted tasks to the user and then prompts the user to enter the task id
+/// of the target task to be restored (see `Tasks::restore`).
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the result
+/// - **name_in**: The name of the user
+/// - **tasks**: A mutable ref to Tasks which stores the active and deleted tasks.
+/// # Returns
+/// `Ok(task_id)` where task_id is the id of the restored task if the function
+/// completes successfully else a `TodoError`.
+pub fn restore(console: &mut dyn Console, name_in: &str, tasks: &mut Tasks) -> TodoResult<TaskId> {
+    let prompt = "Please enter the Task ID of the task you wish to restore: ";
+    let task_id = extract_task(console, name_in, prompt, &tasks.deleted)?;
+    let target_task = tasks.restore(task_id)?;
+    console.println(&format!(
+        "\nTask with details {} has been restored",
+        target_task.show()
+    ));
+    Ok(task_id)
+}
+
+/// # reopen
+/// moves a completed task back to the active tasks and clears the time it
+/// was finished. Prints out a view of the completed tasks to the user and
+/// then prompts the user to enter the task id of the target task to be
+/// reopened (see `Tasks::reopen`).
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the result
+/// - **name_in**: The name of the user
+/// - **tasks**: A mutable ref to Tasks which stores the active and completed tasks.
+/// # Returns
+/// `Ok(task_id)` where task_id is the id of the reopened task if the function
+/// completes successfully else a `TodoError`.
+pub fn reopen(console: &mut dyn Console, name_in: &str, tasks: &mut Tasks) -> TodoResult<TaskId> {
+    let prompt = "Please enter the Task ID of the task you wish to reopen: ";
+    let task_id = extract_task(console, name_in, prompt, &tasks.completed)?;
+    let target_task = tasks.reopen(task_id)?;
+    console.println(&format!(
+        "\nTask with details {} has been reopened",
+        target_task.show()
+    ));
+    Ok(task_id)
+}
@@ -1,72 +1,47 @@
-use crate::internal::paths::get_file_path;
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::storage::backend::Storage;
 use crate::internal::tasks::prelude::Tasks;
-use crate::internal::tasks::structs::{Task, TaskStatus};
-use std::fs;
-use std::fs::File;
-use std::io::Write;
 
-/// # save_tasks
-/// writes a list of tasks to storage based on the `status`
-/// argument passed into the function
-///
-/// - if status == TaskStatus::Active
-///     - it reads the list of tasks from 'Active.txt'
-/// - elif status == TaskStatus::Completed
-///     - it reads the list of tasks from 'Completed.txt'
-/// - else (status == TaskStatus::Deleted)
-///     - it reads the list of tasks from 'Deleted.txt'
-///
-/// each task is converted to a string and appended with
-/// the newline separator to an empty string to create
-/// a printout of all the tasks. This printout is then
-/// written to memory.
+/// # save
+/// saves all different type of tasks available in the program to
+/// `storage`.
 /// # Arguments
-/// - **tasks_list**: a vector of tasks from which the tasks
-/// that are written to memory are gotten
-/// - **status**: a `TaskStatus` enum that is used to determine
-/// the name of the file to write tasks to. This filename is
-/// directly linked to the type of tasks being stored.
+/// - **storage**: the storage backend the tasks are written to
+/// - **tasks**: `Tasks` struct whose members hold a
+///   vector of tasks for each task type respectively. The
+///   saved tasks are gotten from its members.
 /// # Returns
-/// `Some(())` if the operation completes successfully else `None`.
-fn save_tasks(tasks_list: &Vec<Task>, status: TaskStatus) -> Option<()> {
-    let task_name = match status {
-        TaskStatus::Active => "Active",
-        TaskStatus::Completed => "Completed",
-        TaskStatus::Deleted => "Deleted",
-    };
-    let filename = String::from(task_name) + ".txt";
-    let save_path = get_file_path(filename.as_str());
-    let mut task_print = String::new();
-    for task in tasks_list {
-        task_print.push_str(task.show().as_str());
-        task_print.push_str("\n");
-    }
-    let parent_path = save_path.parent()?;
-    fs::create_dir_all(parent_path).ok()?;
-    let mut save_file = File::create(save_path).ok()?;
-    match save_file.write(task_print.as_bytes()) {
-        Ok(_) => Some(()),
-        Err(_) => {
-            println!("Error saving {task_name} tasks to {filename}");
-            None
-        }
-    }
+/// `Ok(())` if the function completes successfully else a `TodoError`
+pub fn save(storage: &mut dyn Storage, tasks: &Tasks) -> TodoResult<()> {
+    storage.save_all(tasks)?;
+    storage.record_task_id(tasks.last_id)
 }
 
-/// # save
-/// saves all different type of tasks available in the program to
-/// memory.
+/// # save_task
+/// persists a single task right after it has been added or changed so that no
+/// change is lost if the program stops before `save` is called on exit. The
+/// stored copy of the task is updated, or the task is inserted if it has not
+/// been stored yet, or removed if the task no longer exists e.g., after the
+/// task was added and the change then undone. The id of a newly stored task
+/// is recorded as assigned (see `Storage::record_task_id`).
 /// # Arguments
-/// - **name_in**: The name of the user
-/// - **tasks**: mutable `Tasks` struct whose members hold a
-/// vector of tasks for each task type respectively. The
-/// saved tasks are gotten from its members.
+/// - **storage**: the storage backend the task is written to
+/// - **tasks**: the `Tasks` struct holding the task
+/// - **task_id**: the id of the task to be persisted
 /// # Returns
-/// `Some(())` if the function completes successfully else `None`
-pub fn save(name_in: &str, tasks: &mut Tasks) -> Option<()> {
-    save_tasks(&tasks.active, TaskStatus::Active)?;
-    save_tasks(&tasks.completed, TaskStatus::Completed)?;
-    save_tasks(&tasks.deleted, TaskStatus::Deleted)?;
-    println!("So sad 😔 to see you go {name_in}. Visit again soon.");
-    Some(())
+/// `Ok(())` if the function completes successfully else a `TodoError`
+pub fn save_task(storage: &mut dyn Storage, tasks: &Tasks, task_id: i32) -> TodoResult<()> {
+    let Some(task) = tasks.find(task_id) else {
+        return match storage.delete(task_id) {
+            Err(TodoError::NotFound(_)) => Ok(()),
+            deleted => deleted,
+        };
+    };
+    match storage.update(task) {
+        Err(TodoError::NotFound(_)) => {
+            storage.insert(task)?;
+            storage.record_task_id(task_id)
+        }
+        updated => updated,
+    }
 }
@@ -0,0 +1,78 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::inputs::prelude::Console;
+use crate::internal::tasks::check::check_task;
+use crate::internal::tasks::delete::delete_task;
+use crate::internal::tasks::edit::edit_task;
+use crate::internal::tasks::fuzzy::highlight;
+use crate::internal::tasks::structs::{TaskId, TaskStatus, Tasks};
+
+/// the most search results printed at once.
+const MAX_RESULTS: usize = 10;
+
+/// # search
+/// prompts the user to enter a search text and prints the tasks of every
+/// status whose names match it best first, with the matched characters in
+/// square brackets, followed by the tasks whose notes match it together with
+/// the matching line of their notes (see `Tasks::search`). The user can then pick a result
+/// by its number to edit, complete or delete it without typing its Task ID.
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the results
+/// - **name_in**: The name of the user
+/// - **tasks**: A mutable ref to Tasks which stores the active, completed and deleted tasks.
+/// # Returns
+/// `Ok(task_ids)` where task_ids are the ids of the changed tasks, which is
+/// empty if no task was changed, else a `TodoError`.
+pub fn search(
+    console: &mut dyn Console,
+    name_in: &str,
+    tasks: &mut Tasks,
+) -> TodoResult<Vec<TaskId>> {
+    let pattern = console.input_str("Enter the text to search for: ")?;
+    let hits = tasks.search(&pattern);
+    console.println("\nSearch Results");
+    if hits.is_empty() {
+        console.println(&format!("{name_in}, no tasks match '{pattern}'"));
+        return Ok(Vec::new());
+    }
+    let shown = hits.len().min(MAX_RESULTS);
+    console.println(&format!(
+        "{name_in}, {shown} of {} matching tasks are printed below",
+        hits.len()
+    ));
+    for (hit_idx, hit) in hits.iter().take(shown).enumerate() {
+        console.println(&format!(
+            "{}. {} (Task ID: {}, Status: {})",
+            hit_idx + 1,
+            highlight(&hit.task.name, &hit.positions),
+            hit.task.id,
+            hit.task.status.show(),
+        ));
+        if let Some(notes_line) = &hit.notes_line {
+            console.println(&format!("    matched in the notes: {notes_line}"));
+        }
+    }
+
+    // let the user act on one of the results
+    let picked = console.input_num("Enter the number of a result to act on, or 0 to go back: ")?;
+    if picked == 0 {
+        return Ok(Vec::new());
+    }
+    let hit = usize::try_from(picked)
+        .ok()
+        .and_then(|picked| hits.get(picked - 1).filter(|_| picked <= shown))
+        .ok_or_else(|| TodoError::invalid(format!("There is no result number {picked}")))?;
+    console.println("You have selected the task with the following details: ");
+    console.println(&hit.task.show());
+    if hit.task.status != TaskStatus::Active {
+        console.println("Only active tasks can be edited, completed or deleted");
+        return Ok(Vec::new());
+    }
+    let task_id = hit.task.id;
+    let actions = ["Edit task", "Mark task as complete", "Delete task", "Back"];
+    match console.input_option("Select an action: ", &actions, "Actions")? {
+        "Edit task" => edit_task(console, tasks, task_id).map(|task_id| vec![task_id]),
+        "Mark task as complete" => check_task(console, tasks, task_id),
+        "Delete task" => delete_task(console, tasks, task_id),
+        _ => Ok(Vec::new()),
+    }
+}
@@ -1,22 +1,31 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::tasks::dates::{from_wall_clock, utc_timestamp};
+use crate::internal::tasks::recurrence::Recurrence;
 use chrono::prelude::*;
+use chrono::TimeDelta;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeSet;
 
-const DATE_FMT: &str = "%Y/%m/%d %H:%M:%S";
+/// the format dates are shown in e.g., '2024/12/31 17:00:00'.
+pub const DATE_FMT: &str = "%Y/%m/%d %H:%M:%S";
 
 /// # to_string
 /// private utility function to parse string and convert it to
 /// a `Task` instance. Parsing is done by removing the prefix
 /// of the string as specified by the `prefix` argument and
-/// trimming and returning the result
+/// trimming and returning the result. Only a leading `prefix`
+/// is removed so that the same text inside a task name is kept.
 /// # Arguments
 /// - **source_vec**: a vector of string literals that contains
-/// the target string
+///   the target string
 /// - **index**: the index of the target string in `source_vec`
 /// - **prefix**: the prefix that is to be removed from the
-/// target string.
+///   target string.
 /// # Returns
 /// the parsed string
-fn to_string(source_vec: &Vec<&str>, index: i32, prefix: &str) -> String {
-    let clean_str = source_vec[index as usize].replace(prefix, "");
+fn to_string(source_vec: &[&str], index: i32, prefix: &str) -> String {
+    let source_str = source_vec[index as usize].trim_start();
+    let clean_str = source_str.strip_prefix(prefix).unwrap_or(source_str);
     let trimmed_str = clean_str.trim();
     trimmed_str.to_string()
 }
@@ -29,14 +38,14 @@ fn to_string(source_vec: &Vec<&str>, index: i32, prefix: &str) -> String {
 /// a number.
 /// # Arguments
 /// - **source_vec**: a vector of string literals that contains
-/// the target string
+///   the target string
 /// - **index**: the index of the target string in `source_vec`
 /// - **prefix**: the prefix that is to be removed from the
-/// target string.
+///   target string.
 /// # Returns
 /// `Some(number)` where number is the parsed number if the
 /// function completes successfully else `None`.
-fn to_num(source_vec: &Vec<&str>, index: i32, prefix: &str) -> Option<i32> {
+fn to_num(source_vec: &[&str], index: i32, prefix: &str) -> Option<i32> {
     let trimmed_str = to_string(source_vec, index, prefix);
     trimmed_str.parse::<i32>().ok()
 }
@@ -45,28 +54,21 @@ fn to_num(source_vec: &Vec<&str>, index: i32, prefix: &str) -> Option<i32> {
 /// parses the argument `date_str` to a `DateTime<Local>>` instance
 /// i.e., a datetime that follows the system's local time settings.
 ///
-/// It parses the input `date_str` which is assumed to have been
-/// created as a local datetime but is being read as UTC datetime.
-///
-/// Hence, the result has the Local time offset removed from it
-/// before converting the UTC Datetime to Local DateTime again.
+/// The input `date_str` has no offset as it was written as the time shown
+/// on a clock in `timezone`, so it is read with the offset `timezone` had
+/// at that date (see `from_wall_clock`). A time written in summer is
+/// therefore read correctly in winter and vice versa.
 /// # Arguments
 /// - **date_str**: a string of datetime information which is
-/// expected to match the format "%Y/%m/%d %H:%M:%S". The datetime
-/// information should also include the local time offset which
-/// the function balances out when reading it as UTC before
-/// converting it back to Local DateTime.
+///   expected to match the format "%Y/%m/%d %H:%M:%S".
+/// - **timezone**: the time zone `date_str` was written in, usually `Local`
 /// # Returns
 /// `Some(datetime)` where datetime is the local datetime equivalent
 /// of `date_str` if the function completes successfully else `None`.
-fn to_datetime(date_str: String) -> Option<DateTime<Local>> {
-    let now = Local::now();
-    let offset = now.offset();
-    let timezone = now.timezone();
-    let mut naive_date = NaiveDateTime::parse_from_str(&date_str, DATE_FMT).ok()?;
-    naive_date = naive_date.checked_sub_offset(*offset)?;
-    let local_date = timezone.from_utc_datetime(&naive_date);
-    Some(local_date)
+fn to_datetime<Tz: TimeZone>(date_str: String, timezone: &Tz) -> Option<DateTime<Local>> {
+    let naive_date = NaiveDateTime::parse_from_str(&date_str, DATE_FMT).ok()?;
+    let datetime = from_wall_clock(naive_date, timezone)?;
+    Some(datetime.with_timezone(&Local))
 }
 
 /// # to_datetime_from_vec
@@ -75,33 +77,38 @@ fn to_datetime(date_str: String) -> Option<DateTime<Local>> {
 /// and trimming the result
 /// # Arguments
 /// - **source_vec**: the vector of string literals in which the
-/// target string is contained
+///   target string is contained
 /// - **index**: the index of the target string in `source_vec`
 /// - **prefix**: a string literal with which the target string is
-/// prefixed.
+///   prefixed.
+/// - **timezone**: the time zone the target string was written in
 /// # Returns
 /// `Some(datetime)` where datetime is the local datetime equivalent
 /// of `date_str` if the function completes successfully else `None`.
-fn to_datetime_from_vec(
-    source_vec: &Vec<&str>,
+fn to_datetime_from_vec<Tz: TimeZone>(
+    source_vec: &[&str],
     index: i32,
     prefix: &str,
+    timezone: &Tz,
 ) -> Option<DateTime<Local>> {
     let date_str = to_string(source_vec, index, prefix);
-    to_datetime(date_str)
+    to_datetime(date_str, timezone)
 }
 
+/// the type of the `id` member of a `Task`.
+pub type TaskId = i32;
+
 /// enum `TaskStatus`
 /// This enum represents both the type of task, and its state
 /// during the program
 ///
 /// # Variants
 /// - **Active**: the task is active and is yet to be done and
-/// has not been deleted.
+///   has not been deleted.
 /// - **Completed**: the task is completed.
 /// - **Deleted**: the task is removed from the active tasks and
-/// is not marked complete.
-#[derive(Debug)]
+///   is not marked complete.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum TaskStatus {
     Active,
     Completed,
@@ -120,6 +127,20 @@ impl TaskStatus {
         }
     }
 
+    /// # parse
+    /// creates a new instance of `TaskStatus` from a string input,
+    /// ignoring case. Unlike `from`, unknown input is rejected.
+    /// # Returns
+    /// `Some(TaskStatus)` if `status` names a status else `None`.
+    pub fn parse(status: &str) -> Option<Self> {
+        match status.to_lowercase().as_str() {
+            "active" => Some(TaskStatus::Active),
+            "completed" => Some(TaskStatus::Completed),
+            "deleted" => Some(TaskStatus::Deleted),
+            _ => None,
+        }
+    }
+
     /// # from
     /// creates a new instance of `TaskStatus` from a string input.
     pub fn from(status: &str) -> Self {
@@ -132,6 +153,53 @@ impl TaskStatus {
     }
 }
 
+/// enum `Priority`
+/// The priority of a task which adds to its urgency (see `urgency::urgency`).
+/// Priorities are ordered from `None` (the lowest) to `High`.
+///
+/// # Variants
+/// - **None**: the task has no priority (the default)
+/// - **Low**: the task has a low priority
+/// - **Medium**: the task has a medium priority
+/// - **High**: the task has a high priority
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+pub enum Priority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+}
+
+impl Priority {
+    /// # show
+    /// converts a `Priority` to a String for display or writing to stdout
+    pub fn show(&self) -> String {
+        match &self {
+            Priority::None => String::from("None"),
+            Priority::Low => String::from("Low"),
+            Priority::Medium => String::from("Medium"),
+            Priority::High => String::from("High"),
+        }
+    }
+
+    /// # parse
+    /// creates a new instance of `Priority` from a string input such as
+    /// 'high' or its first letter 'h', ignoring case. 'none' or an empty
+    /// input is `Priority::None`.
+    /// # Returns
+    /// `Some(Priority)` if `priority` names a priority else `None`.
+    pub fn parse(priority: &str) -> Option<Self> {
+        match priority.trim().to_lowercase().as_str() {
+            "" | "none" | "n" => Some(Priority::None),
+            "low" | "l" => Some(Priority::Low),
+            "medium" | "m" => Some(Priority::Medium),
+            "high" | "h" => Some(Priority::High),
+            _ => None,
+        }
+    }
+}
+
 /// enum `DateTimeOption`
 /// An enum for holding DateTime data or None if
 /// the datetime data does not need a value at the
@@ -140,13 +208,46 @@ impl TaskStatus {
 /// # Variants
 /// - **DateTime**: variant that holds the local datetime
 /// - **None**: variant for unfilled or unavailable
-/// datetime data
+///   datetime data
+#[derive(Clone, Default)]
 pub enum DateTimeOption {
     DateTime(DateTime<Local>),
+    #[default]
     None,
 }
 
+/// `DateTimeOption` is stored as an optional UTC timestamp (see
+/// `utc_timestamp`) i.e., the `None` variant is written out as `null`
+/// rather than as the display text returned by `DateTimeOption::show`.
+impl Serialize for DateTimeOption {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            DateTimeOption::DateTime(dt) => serializer.serialize_some(&dt.with_timezone(&Utc)),
+            DateTimeOption::None => serializer.serialize_none(),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for DateTimeOption {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Option::<DateTime<FixedOffset>>::deserialize(deserializer)?;
+        match value {
+            Some(dt) => Ok(DateTimeOption::DateTime(dt.with_timezone(&Local))),
+            None => Ok(DateTimeOption::None),
+        }
+    }
+}
+
 impl DateTimeOption {
+    /// # get
+    /// returns the held datetime as an `Option`.
+    pub fn get(&self) -> Option<DateTime<Local>> {
+        match self {
+            DateTimeOption::DateTime(dt) => Some(*dt),
+            DateTimeOption::None => None,
+        }
+    }
+
     /// # show
     /// converts the `DateTimeOption` to a String for
     /// easy display or writing to a file or stdout
@@ -161,37 +262,102 @@ impl DateTimeOption {
     /// converts a string literal to a `DateTimeOption` based
     /// of its parsing. If the string is of the same format
     /// as the form of `DateTimeOption::None` parsed into a
-    /// String i.e., `"Not Completed"`, the `None` variant
-    /// is returned else the `DateTime` variant is returned.
-    pub fn from(display: &str) -> DateTimeOption {
-        match display {
-            "Not Completed" => DateTimeOption::None,
-            _ => {
-                let datetime_value = to_datetime(display.to_string());
-                match datetime_value {
-                    Some(datetime) => DateTimeOption::DateTime(datetime),
-                    None => DateTimeOption::None,
-                }
-            }
+    /// String i.e., `"Not completed"` (matched regardless of
+    /// case), the `None` variant is returned else the `DateTime`
+    /// variant is returned, read as a time shown on a clock in
+    /// `timezone` (see `to_datetime`).
+    pub fn from<Tz: TimeZone>(display: &str, timezone: &Tz) -> DateTimeOption {
+        if display.eq_ignore_ascii_case("Not completed") {
+            return DateTimeOption::None;
+        }
+        let datetime_value = to_datetime(display.to_string(), timezone);
+        match datetime_value {
+            Some(datetime) => DateTimeOption::DateTime(datetime),
+            None => DateTimeOption::None,
         }
     }
 }
 
+/// # WorkInterval
+/// a period of time spent working on a task, recorded by starting and
+/// stopping its timer (see `Tasks::start_timer`).
+///
+/// # Members
+/// - **start**: when the timer was started
+/// - **end**: when the timer was stopped, or `DateTimeOption::None` while it is running
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WorkInterval {
+    #[serde(with = "utc_timestamp")]
+    pub start: DateTime<Local>,
+    #[serde(default)]
+    pub end: DateTimeOption,
+}
+
+impl WorkInterval {
+    /// # duration
+    /// returns the time spent in the interval, counting a running interval
+    /// up to `now`.
+    pub fn duration(&self, now: DateTime<Local>) -> TimeDelta {
+        let end = self.end.get().unwrap_or(now);
+        (end - self.start).max(TimeDelta::zero())
+    }
+}
+
+/// # show_duration
+/// converts a `TimeDelta` to a String of hours and minutes for display
+/// e.g., '12h 05m'. Seconds are left out.
+pub fn show_duration(duration: TimeDelta) -> String {
+    let minutes = duration.num_minutes().max(0);
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
 /// # Task
 /// a struct that represents the data model for a task.
 ///
 /// # Members
 /// - **time_created**: `DateTime<Local>`
 /// - **last_time_modified**: `DateTime<Local>`
-/// - **time_finished**: `DateTimeOption`,
+/// - **time_finished**: `DateTimeOption`, when the task was completed or deleted
+/// - **due**: `DateTimeOption`, when the task is due if it has a due date
+/// - **priority**: `Priority`,
+/// - **tags**: `BTreeSet<String>`, the tags of the task without the leading '+'
+/// - **project**: `Option<String>`, the project of the task e.g., 'work.backend.api'
+/// - **parent**: `Option<TaskId>`, the id of the task this task is a subtask of
+/// - **depends_on**: `BTreeSet<TaskId>`, the ids of the tasks that must be completed
+///   before this task can be worked on
+/// - **recurrence**: `Option<Recurrence>`, the rule the task repeats by if it is recurring
+/// - **notes**: `Option<String>`, free-form notes on the task which may span several lines
+/// - **work**: `Vec<WorkInterval>`, the time spent on the task, oldest first. The last
+///   interval has no end while the timer of the task is running
 /// - **name**: `String`,
 /// - **id**: `i32`,
 /// - **status**: `TaskStatus`
 ///
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Task {
+    #[serde(with = "utc_timestamp")]
     pub time_created: DateTime<Local>,
+    #[serde(with = "utc_timestamp")]
     pub last_time_modified: DateTime<Local>,
     pub time_finished: DateTimeOption,
+    #[serde(default)]
+    pub due: DateTimeOption,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    #[serde(default)]
+    pub project: Option<String>,
+    #[serde(default)]
+    pub parent: Option<TaskId>,
+    #[serde(default)]
+    pub depends_on: BTreeSet<TaskId>,
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub work: Vec<WorkInterval>,
     pub name: String,
     pub id: i32,
     pub status: TaskStatus,
@@ -208,7 +374,12 @@ impl Task {
     ///
     /// its `last_time_modified` is set to `Local::now()`
     ///
-    /// its `time_finished` is set to `DateTimeOption::None`
+    /// its `time_finished` and `due` are set to `DateTimeOption::None`
+    ///
+    /// its `priority` is set to `Priority::None`
+    ///
+    /// it has no `tags`, no `project`, no `parent`, no `depends_on`, no `recurrence`,
+    /// no `notes` and no `work`
     /// # Arguments
     /// - **name**: the name of the new task
     /// - **id**: the task id for the new task
@@ -222,28 +393,76 @@ impl Task {
             time_created: Local::now(),
             last_time_modified: Local::now(),
             time_finished: DateTimeOption::None,
+            due: DateTimeOption::None,
+            priority: Priority::None,
+            tags: BTreeSet::new(),
+            project: None,
+            parent: None,
+            depends_on: BTreeSet::new(),
+            recurrence: None,
+            notes: None,
+            work: Vec::new(),
         }
     }
 
-    /// # from_str
-    /// parses a string literal (which is expected to have been
-    /// generated from the `show` method) to a Task instance.
+    /// # from_legacy
+    /// parses a string literal written in the legacy brace-and-comma
+    /// line format (the format of the `show` method) to a Task instance.
+    /// This format is only kept for reading data written by older versions.
+    /// Its dates are read as local times (see `Task::from_legacy_in`).
+    pub fn from_legacy(display: &str) -> TodoResult<Task> {
+        Task::from_legacy_in(display, &Local)
+    }
+
+    /// # from_legacy_in
+    /// parses a string literal written in the legacy brace-and-comma
+    /// line format to a Task instance as described in `from_legacy`,
+    /// reading its dates, which have no offset, as times shown on a clock
+    /// in `timezone` at the date they were written.
+    ///
+    /// The format does not escape its fields, so only the outer braces are
+    /// removed and the fields around `Name` are split off from both ends of
+    /// the line. This recovers names that contain `", "` or braces.
     /// # Arguments
     /// - **display**: the string literal to be parsed to a
-    /// `Task` instance
+    ///   `Task` instance
+    /// - **timezone**: the time zone the line was written in
     /// # Returns
-    /// `Some(Task)` if the string is parsed successfully else
-    /// `None`.
-    pub fn from_str(display: &str) -> Option<Task> {
-        // remove the braces expected in the `display`
-        let display = display.replace("{", "");
-        let display = display.replace("}", "");
+    /// `Ok(Task)` if the string is parsed successfully else
+    /// `TodoError::Parse` naming the field that could not be parsed.
+    /// The error has no source or line number; set them with
+    /// `TodoError::at`.
+    pub fn from_legacy_in<Tz: TimeZone>(display: &str, timezone: &Tz) -> TodoResult<Task> {
+        let parse_error = |field: Option<&str>, message: &str| TodoError::Parse {
+            source: String::new(),
+            line: 0,
+            field: field.map(String::from),
+            message: message.to_string(),
+        };
+        let fields_error = || parse_error(None, "expected 6 fields separated by ', '");
+
+        // remove the outer braces expected in the `display`
         let display = display.trim();
-        let collection = display.split(", ").collect::<Vec<&str>>();
-        assert_eq!(collection.len(), 6);
+        let display = display
+            .strip_prefix('{')
+            .and_then(|display| display.strip_suffix('}'))
+            .ok_or_else(|| parse_error(None, "expected the line to be wrapped in braces"))?
+            .trim();
+
+        // split off `Task ID` from the left and the last four fields from
+        // the right so that whatever remains in between is the `Name`.
+        let (id_str, rest) = display.split_once(", ").ok_or_else(fields_error)?;
+        let mut tail = rest.rsplitn(5, ", ").collect::<Vec<&str>>();
+        if tail.len() != 5 {
+            return Err(fields_error());
+        }
+        tail.push(id_str);
+        tail.reverse();
+        let collection = tail;
 
         // parse Task members `id` and `name` as `task_id` and `task_name`
-        let task_id = to_num(&collection, 0, "Task ID: ")?;
+        let task_id = to_num(&collection, 0, "Task ID: ")
+            .ok_or_else(|| parse_error(Some("Task ID"), "expected a number"))?;
         let task_name = to_string(&collection, 1, "Name: ");
 
         // parse Task member `status` as `task_status`
@@ -252,38 +471,202 @@ impl Task {
 
         // parse Task members `time_created` and `last_time_modified`
         // as `time_created` and `time_modified`
-        let time_created = to_datetime_from_vec(&collection, 3, "Created: ")?;
-        let time_modified = to_datetime_from_vec(&collection, 4, "Last Modified: ")?;
+        let date_error =
+            |field| parse_error(Some(field), "expected a date like 2024/01/31 17:00:00");
+        let time_created = to_datetime_from_vec(&collection, 3, "Created: ", timezone)
+            .ok_or_else(|| date_error("Created"))?;
+        let time_modified = to_datetime_from_vec(&collection, 4, "Last Modified: ", timezone)
+            .ok_or_else(|| date_error("Last Modified"))?;
 
         // parse Task member `time_finished`
         let finished_date_str = to_string(&collection, 5, "Finished: ");
-        let time_finished = DateTimeOption::from(&finished_date_str);
+        let time_finished = DateTimeOption::from(&finished_date_str, timezone);
 
         // return Task
-        Some(Task {
+        Ok(Task {
             id: task_id,
             name: task_name,
             status: task_status,
             time_created,
             last_time_modified: time_modified,
             time_finished,
+            due: DateTimeOption::None,
+            priority: Priority::None,
+            tags: BTreeSet::new(),
+            project: None,
+            parent: None,
+            depends_on: BTreeSet::new(),
+            recurrence: None,
+            notes: None,
+            work: Vec::new(),
         })
     }
 
     /// # show
     /// converts a `Task` instance with its members to a String
+    /// for display. Use `format::to_line` to write a task to storage.
+    /// The due date, priority, tags, project, parent, dependencies,
+    /// recurrence and time spent are only shown if the task has them.
     pub fn show(&self) -> String {
+        let mut optional_fields = match &self.due {
+            DateTimeOption::DateTime(dt) => format!(", Due: {}", dt.format(DATE_FMT)),
+            DateTimeOption::None => String::new(),
+        };
+        if self.priority != Priority::None {
+            optional_fields.push_str(&format!(", Priority: {}", self.priority.show()));
+        }
+        if !self.tags.is_empty() {
+            optional_fields.push_str(&format!(", Tags: {}", self.show_tags()));
+        }
+        if let Some(project) = &self.project {
+            optional_fields.push_str(&format!(", Project: {project}"));
+        }
+        if let Some(parent) = self.parent {
+            optional_fields.push_str(&format!(", Parent: {parent}"));
+        }
+        if !self.depends_on.is_empty() {
+            optional_fields.push_str(&format!(", Depends on: {}", self.show_depends_on(", ")));
+        }
+        if let Some(recurrence) = &self.recurrence {
+            optional_fields.push_str(&format!(", Repeats: {}", recurrence.show()));
+        }
+        if !self.work.is_empty() {
+            optional_fields.push_str(&format!(", Time: {}", self.show_time_spent(Local::now())));
+        }
         format!(
-            "{{ Task ID: {}, Name: {}, Status: {}, Created: {}, Last Modified: {}, Finished: {} }}",
+            "{{ Task ID: {}, Name: {}, Status: {}, Created: {}, Last Modified: {}, Finished: {}{} }}",
             self.id,
             self.name,
             self.status.show(),
             self.time_created.format(DATE_FMT),
             self.last_time_modified.format(DATE_FMT),
-            self.time_finished.show()
+            self.time_finished.show(),
+            optional_fields
         )
     }
 
+    /// # show_tags
+    /// returns the tags of the task as '+tag' words separated by spaces.
+    pub fn show_tags(&self) -> String {
+        self.tags
+            .iter()
+            .map(|tag| format!("+{tag}"))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    /// # show_details
+    /// converts a `Task` instance to a String of several lines for the
+    /// detailed view of a single task, with one member per line followed by
+    /// the notes of the task. As in `show`, the optional members are only
+    /// shown if the task has them.
+    pub fn show_details(&self) -> String {
+        let mut lines = vec![
+            format!("Task ID:       {}", self.id),
+            format!("Name:          {}", self.name),
+            format!("Status:        {}", self.status.show()),
+            format!("Created:       {}", self.time_created.format(DATE_FMT)),
+            format!(
+                "Last Modified: {}",
+                self.last_time_modified.format(DATE_FMT)
+            ),
+            format!("Finished:      {}", self.time_finished.show()),
+        ];
+        if let DateTimeOption::DateTime(dt) = &self.due {
+            lines.push(format!("Due:           {}", dt.format(DATE_FMT)));
+        }
+        if self.priority != Priority::None {
+            lines.push(format!("Priority:      {}", self.priority.show()));
+        }
+        if !self.tags.is_empty() {
+            lines.push(format!("Tags:          {}", self.show_tags()));
+        }
+        if let Some(project) = &self.project {
+            lines.push(format!("Project:       {project}"));
+        }
+        if let Some(parent) = self.parent {
+            lines.push(format!("Parent:        {parent}"));
+        }
+        if !self.depends_on.is_empty() {
+            lines.push(format!("Depends on:    {}", self.show_depends_on(", ")));
+        }
+        if let Some(recurrence) = &self.recurrence {
+            lines.push(format!("Repeats:       {}", recurrence.show()));
+        }
+        if !self.work.is_empty() {
+            lines.push(format!(
+                "Time:          {}",
+                self.show_time_spent(Local::now())
+            ));
+        }
+        match &self.notes {
+            Some(notes) => {
+                lines.push("Notes:".to_string());
+                lines.extend(
+                    notes
+                        .lines()
+                        .map(|line| format!("  {line}").trim_end().to_string()),
+                );
+            }
+            None => lines.push("Notes:         none".to_string()),
+        }
+        lines.join("\n")
+    }
+
+    /// # time_spent
+    /// returns the total time spent on the task, counting a running timer
+    /// up to `now`.
+    pub fn time_spent(&self, now: DateTime<Local>) -> TimeDelta {
+        self.work
+            .iter()
+            .map(|interval| interval.duration(now))
+            .fold(TimeDelta::zero(), |total, duration| total + duration)
+    }
+
+    /// # is_timed
+    /// checks if the timer of the task is running.
+    pub fn is_timed(&self) -> bool {
+        self.work
+            .last()
+            .is_some_and(|interval| interval.end.get().is_none())
+    }
+
+    /// # stop_work
+    /// stops the timer of the task at `now` if it is running.
+    /// # Returns
+    /// `true` if the timer was running else `false`.
+    pub fn stop_work(&mut self, now: DateTime<Local>) -> bool {
+        match self.work.last_mut() {
+            Some(interval) if interval.end.get().is_none() => {
+                interval.end = DateTimeOption::DateTime(now);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// # show_time_spent
+    /// returns the total time spent on the task for display e.g., '1h 05m',
+    /// followed by '(running)' while its timer is running.
+    pub fn show_time_spent(&self, now: DateTime<Local>) -> String {
+        let time_spent = show_duration(self.time_spent(now));
+        if self.is_timed() {
+            format!("{time_spent} (running)")
+        } else {
+            time_spent
+        }
+    }
+
+    /// # show_depends_on
+    /// returns the ids of the tasks this task depends on joined by `separator`.
+    pub fn show_depends_on(&self, separator: &str) -> String {
+        self.depends_on
+            .iter()
+            .map(|task_id| task_id.to_string())
+            .collect::<Vec<String>>()
+            .join(separator)
+    }
+
     /// # set
     /// edits the value of the old value of `Task` member `name`
     /// to the value of `new_name` and modifies the member
@@ -303,10 +686,20 @@ impl Task {
 /// - **active**: vector of active tasks
 /// - **completed**: vector of completed tasks
 /// - **deleted**: vector of deleted tasks
+/// - **last_id**: the highest Task ID ever assigned, kept so that the ids of
+///   tasks that were removed for good are never reused (see `Tasks::next_id`)
+#[derive(Clone)]
 pub struct Tasks {
     pub active: Vec<Task>,
     pub completed: Vec<Task>,
     pub deleted: Vec<Task>,
+    pub last_id: TaskId,
+}
+
+impl Default for Tasks {
+    fn default() -> Self {
+        Tasks::new()
+    }
 }
 
 impl Tasks {
@@ -318,6 +711,99 @@ impl Tasks {
             active: Vec::<Task>::new(),
             completed: Vec::<Task>::new(),
             deleted: Vec::<Task>::new(),
+            last_id: 0,
+        }
+    }
+
+    /// # list
+    /// returns the member of `Tasks` that holds the tasks
+    /// with status `status`.
+    pub fn list(&self, status: TaskStatus) -> &Vec<Task> {
+        match status {
+            TaskStatus::Active => &self.active,
+            TaskStatus::Completed => &self.completed,
+            TaskStatus::Deleted => &self.deleted,
+        }
+    }
+
+    /// # list_mut
+    /// returns a mutable reference to the member of `Tasks`
+    /// that holds the tasks with status `status`.
+    pub fn list_mut(&mut self, status: TaskStatus) -> &mut Vec<Task> {
+        match status {
+            TaskStatus::Active => &mut self.active,
+            TaskStatus::Completed => &mut self.completed,
+            TaskStatus::Deleted => &mut self.deleted,
+        }
+    }
+
+    /// # push
+    /// appends `task` to the member of `Tasks` that matches
+    /// its `status` i.e., `active`, `completed` or `deleted`.
+    pub fn push(&mut self, task: Task) {
+        self.list_mut(task.status).push(task);
+    }
+
+    /// # find
+    /// returns the task with id `task_id` from whichever
+    /// member of `Tasks` holds it.
+    pub fn find(&self, task_id: i32) -> Option<&Task> {
+        self.iter().find(|task| task.id == task_id)
+    }
+
+    /// # find_mut
+    /// returns a mutable reference to the task with id `task_id`
+    /// from whichever member of `Tasks` holds it.
+    pub fn find_mut(&mut self, task_id: i32) -> Option<&mut Task> {
+        self.active
+            .iter_mut()
+            .chain(self.completed.iter_mut())
+            .chain(self.deleted.iter_mut())
+            .find(|task| task.id == task_id)
+    }
+
+    /// # take
+    /// removes and returns the task with id `task_id` from
+    /// whichever member of `Tasks` holds it.
+    pub fn take(&mut self, task_id: i32) -> Option<Task> {
+        for status in [
+            TaskStatus::Active,
+            TaskStatus::Completed,
+            TaskStatus::Deleted,
+        ] {
+            let tasks_list = self.list_mut(status);
+            if let Some(task_idx) = tasks_list.iter().position(|task| task.id == task_id) {
+                return Some(tasks_list.remove(task_idx));
+            }
         }
+        None
+    }
+
+    /// # replace
+    /// replaces the task with the same `id` as `task`. The task
+    /// is updated in place if its status did not change, else it
+    /// is moved to the end of the member matching its new status.
+    /// # Returns
+    /// `Ok(())` if a task with the same `id` was found else
+    /// `TodoError::NotFound`.
+    pub fn replace(&mut self, task: Task) -> TodoResult<()> {
+        let tasks_list = self.list_mut(task.status);
+        if let Some(stored_task) = tasks_list.iter_mut().find(|each| each.id == task.id) {
+            *stored_task = task;
+            return Ok(());
+        }
+        self.take(task.id).ok_or(TodoError::NotFound(task.id))?;
+        self.push(task);
+        Ok(())
+    }
+
+    /// # iter
+    /// returns an iterator over every task in the program
+    /// in the order active, completed and deleted tasks.
+    pub fn iter(&self) -> impl Iterator<Item = &Task> {
+        self.active
+            .iter()
+            .chain(self.completed.iter())
+            .chain(self.deleted.iter())
     }
 }
@@ -0,0 +1,317 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::tasks::structs::{Task, TaskId, TaskStatus, Tasks};
+use chrono::Local;
+
+/// enum `SubtaskPolicy`
+/// What to do with the open subtasks of a task that is being completed or
+/// deleted.
+///
+/// # Variants
+/// - **Cascade**: complete or delete the open subtasks with the task
+/// - **Block**: refuse to complete or delete the task while it has open subtasks
+/// - **Detach**: keep the open subtasks, which no longer have a parent
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SubtaskPolicy {
+    Cascade,
+    Block,
+    Detach,
+}
+
+impl SubtaskPolicy {
+    /// # parse
+    /// parses 'cascade', 'block' or 'detach' ignoring case.
+    /// # Returns
+    /// `Some(policy)` if `policy` names a policy else `None`.
+    pub fn parse(policy: &str) -> Option<Self> {
+        match policy.to_lowercase().as_str() {
+            "cascade" => Some(SubtaskPolicy::Cascade),
+            "block" => Some(SubtaskPolicy::Block),
+            "detach" => Some(SubtaskPolicy::Detach),
+            _ => None,
+        }
+    }
+}
+
+/// The operations on the hierarchy of tasks i.e., the `parent` of each task.
+/// A subtask may have a status different from its parent e.g., the
+/// completed steps of an active task.
+impl Tasks {
+    /// # set_parent
+    /// makes the active task with id `task_id` a subtask of the active task
+    /// with id `parent`, or a top level task if `parent` is `None`, and
+    /// updates its `last_time_modified`.
+    /// # Arguments
+    /// - **task_id**: the id of the task
+    /// - **parent**: the id of the new parent of the task
+    /// # Returns
+    /// `Ok(task)` where task is a reference to the changed task else
+    /// `TodoError::NotFound` if either task is not active, or
+    /// `TodoError::InvalidInput` if the task would become its own ancestor.
+    pub fn set_parent(&mut self, task_id: TaskId, parent: Option<TaskId>) -> TodoResult<&Task> {
+        if !self.active.iter().any(|task| task.id == task_id) {
+            return Err(TodoError::NotFound(task_id));
+        }
+        if let Some(parent_id) = parent {
+            if !self.active.iter().any(|task| task.id == parent_id) {
+                return Err(TodoError::NotFound(parent_id));
+            }
+            if parent_id == task_id || self.ancestors(parent_id).contains(&task_id) {
+                return Err(TodoError::invalid(format!(
+                    "Task {parent_id} cannot be the parent of task {task_id} as it is \
+                     task {task_id} or one of its subtasks"
+                )));
+            }
+        }
+        let target_task = self
+            .active
+            .iter_mut()
+            .find(|task| task.id == task_id)
+            .ok_or(TodoError::NotFound(task_id))?;
+        target_task.parent = parent;
+        target_task.last_time_modified = Local::now();
+        Ok(target_task)
+    }
+
+    /// # ancestors
+    /// returns the ids of the parent of the task with id `task_id`, its
+    /// parent and so on up to a top level task. Stops at a repeated id so
+    /// that corrupted data cannot loop forever.
+    pub fn ancestors(&self, task_id: TaskId) -> Vec<TaskId> {
+        let mut ancestors = Vec::<TaskId>::new();
+        let mut next = self.find(task_id).and_then(|task| task.parent);
+        while let Some(parent_id) = next {
+            if parent_id == task_id || ancestors.contains(&parent_id) {
+                break;
+            }
+            ancestors.push(parent_id);
+            next = self.find(parent_id).and_then(|task| task.parent);
+        }
+        ancestors
+    }
+
+    /// # subtasks
+    /// returns the direct subtasks of the task with id `task_id` of every
+    /// status, in the order active, completed and deleted.
+    pub fn subtasks(&self, task_id: TaskId) -> Vec<&Task> {
+        self.iter()
+            .filter(|task| task.parent == Some(task_id))
+            .collect()
+    }
+
+    /// # subtask_progress
+    /// counts the direct subtasks of the task with id `task_id` that are not
+    /// deleted.
+    /// # Returns
+    /// `Some((done, total))` where done is the number of completed subtasks
+    /// and total the number of active and completed subtasks, or `None` if
+    /// the task has no such subtasks.
+    pub fn subtask_progress(&self, task_id: TaskId) -> Option<(usize, usize)> {
+        let subtasks = self
+            .subtasks(task_id)
+            .into_iter()
+            .filter(|task| task.status != TaskStatus::Deleted)
+            .collect::<Vec<&Task>>();
+        if subtasks.is_empty() {
+            return None;
+        }
+        let done = subtasks
+            .iter()
+            .filter(|task| task.status == TaskStatus::Completed)
+            .count();
+        Some((done, subtasks.len()))
+    }
+
+    /// # open_subtasks
+    /// returns the ids of the active subtasks of the task with id `task_id`,
+    /// their active subtasks and so on, each subtask before its own subtasks.
+    pub fn open_subtasks(&self, task_id: TaskId) -> Vec<TaskId> {
+        let mut open = Vec::<TaskId>::new();
+        let mut pending = vec![task_id];
+        while let Some(parent_id) = pending.pop() {
+            for task in self
+                .active
+                .iter()
+                .filter(|task| task.parent == Some(parent_id))
+            {
+                if task.id != task_id && !open.contains(&task.id) {
+                    open.push(task.id);
+                    pending.push(task.id);
+                }
+            }
+        }
+        open
+    }
+
+    /// # resolve_subtasks
+    /// deals with the open subtasks of the active task with id `task_id`
+    /// before the task is completed or deleted, as set by `policy`. Does
+    /// nothing if the task has no open subtasks.
+    /// # Arguments
+    /// - **task_id**: the id of the task that is being completed or deleted
+    /// - **status**: `TaskStatus::Completed` if the task is being completed
+    ///   else `TaskStatus::Deleted`
+    /// - **policy**: what to do with the open subtasks
+    /// # Returns
+    /// `Ok(task_ids)` where task_ids are the ids of the subtasks that were
+    /// changed, else `TodoError::InvalidInput` if `policy` is
+    /// `SubtaskPolicy::Block` and the task has open subtasks.
+    pub fn resolve_subtasks(
+        &mut self,
+        task_id: TaskId,
+        status: TaskStatus,
+        policy: SubtaskPolicy,
+    ) -> TodoResult<Vec<TaskId>> {
+        let open = self.open_subtasks(task_id);
+        if open.is_empty() {
+            return Ok(open);
+        }
+        match policy {
+            SubtaskPolicy::Block => Err(TodoError::invalid(format!(
+                "Task {task_id} has {} open subtasks. Complete, delete or detach them first",
+                open.len()
+            ))),
+            SubtaskPolicy::Cascade => {
+                // finish the deepest subtasks first
+                for subtask_id in open.iter().rev() {
+                    if status == TaskStatus::Completed {
+                        self.complete(*subtask_id)?;
+                    } else {
+                        self.delete(*subtask_id)?;
+                    }
+                }
+                Ok(open)
+            }
+            SubtaskPolicy::Detach => {
+                let mut detached = Vec::<TaskId>::new();
+                for task in self
+                    .active
+                    .iter_mut()
+                    .filter(|task| task.parent == Some(task_id))
+                {
+                    task.parent = None;
+                    task.last_time_modified = Local::now();
+                    detached.push(task.id);
+                }
+                Ok(detached)
+            }
+        }
+    }
+
+    /// # tree
+    /// orders the tasks with status `status` as a tree, each task followed
+    /// by its subtasks with that status. A task whose parent does not have
+    /// that status is shown at the top level.
+    /// # Returns
+    /// the tasks in tree order, each with its depth in the tree where top
+    /// level tasks have a depth of 0.
+    pub fn tree(&self, status: TaskStatus) -> Vec<(usize, &Task)> {
+        let tasks_list = self.list(status);
+        let in_list = |task_id: TaskId| tasks_list.iter().any(|task| task.id == task_id);
+        let mut ordered = Vec::<(usize, &Task)>::with_capacity(tasks_list.len());
+        let mut pending = tasks_list
+            .iter()
+            .rev()
+            .filter(|task| !task.parent.is_some_and(in_list))
+            .map(|task| (0, task))
+            .collect::<Vec<(usize, &Task)>>();
+        loop {
+            while let Some((depth, task)) = pending.pop() {
+                if ordered.iter().any(|(_, shown)| shown.id == task.id) {
+                    continue;
+                }
+                ordered.push((depth, task));
+                for subtask in tasks_list
+                    .iter()
+                    .rev()
+                    .filter(|each| each.parent == Some(task.id))
+                {
+                    pending.push((depth + 1, subtask));
+                }
+            }
+            // tasks in a cycle of parents, which only corrupted data has,
+            // are shown at the top level
+            match tasks_list
+                .iter()
+                .find(|task| !ordered.iter().any(|(_, shown)| shown.id == task.id))
+            {
+                Some(task) => pending.push((0, task)),
+                None => return ordered,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// task 1 with the open subtask 2, which has the open subtask 3, and the
+    /// completed subtask 4, next to the top level task 5.
+    fn family() -> Tasks {
+        let mut tasks = Tasks::new();
+        for name in ["move house", "pack", "pack books", "find boxes", "call bob"] {
+            tasks.add(name.to_string());
+        }
+        tasks.set_parent(2, Some(1)).unwrap();
+        tasks.set_parent(3, Some(2)).unwrap();
+        tasks.set_parent(4, Some(1)).unwrap();
+        tasks.complete(4).unwrap();
+        tasks
+    }
+
+    fn status(tasks: &Tasks, task_id: TaskId) -> TaskStatus {
+        tasks.find(task_id).unwrap().status
+    }
+
+    #[test]
+    fn cascade_finishes_the_open_subtasks_at_every_depth() {
+        let mut tasks = family();
+        let changed = tasks
+            .resolve_subtasks(1, TaskStatus::Completed, SubtaskPolicy::Cascade)
+            .unwrap();
+        assert_eq!(changed, [2, 3]);
+        assert_eq!(status(&tasks, 2), TaskStatus::Completed);
+        assert_eq!(status(&tasks, 3), TaskStatus::Completed);
+        // the task itself is left to the caller
+        assert_eq!(status(&tasks, 1), TaskStatus::Active);
+        assert_eq!(status(&tasks, 5), TaskStatus::Active);
+
+        let mut tasks = family();
+        tasks
+            .resolve_subtasks(1, TaskStatus::Deleted, SubtaskPolicy::Cascade)
+            .unwrap();
+        assert_eq!(status(&tasks, 2), TaskStatus::Deleted);
+        assert_eq!(status(&tasks, 3), TaskStatus::Deleted);
+        assert_eq!(status(&tasks, 4), TaskStatus::Completed);
+    }
+
+    #[test]
+    fn block_refuses_while_subtasks_are_open() {
+        let mut tasks = family();
+        let result = tasks.resolve_subtasks(1, TaskStatus::Completed, SubtaskPolicy::Block);
+        assert!(matches!(result, Err(TodoError::InvalidInput(_))));
+        assert_eq!(status(&tasks, 2), TaskStatus::Active);
+        assert_eq!(status(&tasks, 3), TaskStatus::Active);
+
+        // a task whose subtasks are all done is not blocked
+        tasks.complete(3).unwrap();
+        let changed = tasks
+            .resolve_subtasks(2, TaskStatus::Completed, SubtaskPolicy::Block)
+            .unwrap();
+        assert!(changed.is_empty());
+    }
+
+    #[test]
+    fn detach_keeps_the_direct_subtasks_open_without_a_parent() {
+        let mut tasks = family();
+        let changed = tasks
+            .resolve_subtasks(1, TaskStatus::Completed, SubtaskPolicy::Detach)
+            .unwrap();
+        assert_eq!(changed, [2]);
+        let pack = tasks.find(2).unwrap();
+        assert_eq!((pack.status, pack.parent), (TaskStatus::Active, None));
+        // deeper subtasks stay with their own parent
+        assert_eq!(tasks.find(3).unwrap().parent, Some(2));
+        assert_eq!(tasks.find(4).unwrap().parent, Some(1));
+    }
+}
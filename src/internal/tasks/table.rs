@@ -0,0 +1,554 @@
+use crate::internal::config::{ColorMode, Column, Table};
+use crate::internal::inputs::prelude::Console;
+use crate::internal::tasks::structs::{show_duration, Priority, Task, TaskStatus};
+use chrono::{DateTime, Local};
+use std::env;
+use std::io::{stdout, IsTerminal};
+
+/// the format of the dates in a table, which leaves out the seconds of
+/// `DATE_FMT` to keep the columns narrow.
+const TABLE_DATE_FMT: &str = "%Y/%m/%d %H:%M";
+
+/// the space between two columns of a table.
+const COLUMN_GAP: &str = "  ";
+
+/// the narrowest the name column is made to fit the terminal.
+const MIN_NAME_WIDTH: usize = 16;
+
+/// the widest any column but the name column is made before it is cut.
+const MAX_COLUMN_WIDTH: usize = 24;
+
+/// enum `Color`
+/// the ANSI styles used to colour the cells of a table.
+///
+/// # Variants
+/// - **Plain**: the cell is not coloured
+/// - **Header**: the bold and underlined column headers
+/// - **Red**: overdue tasks and high priorities
+/// - **Yellow**: tasks due today and medium priorities
+/// - **Blue**: low priorities
+/// - **Green**: completed tasks and running timers
+/// - **Cyan**: active tasks
+/// - **Grey**: deleted tasks
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Color {
+    Plain,
+    Header,
+    Red,
+    Yellow,
+    Blue,
+    Green,
+    Cyan,
+    Grey,
+}
+
+impl Color {
+    /// # paint
+    /// wraps `text` in the ANSI escape codes of the color, or returns it as
+    /// it is for `Color::Plain`.
+    fn paint(&self, text: &str) -> String {
+        let code = match self {
+            Color::Plain => return text.to_string(),
+            Color::Header => "1;4",
+            Color::Red => "1;31",
+            Color::Yellow => "33",
+            Color::Blue => "34",
+            Color::Green => "32",
+            Color::Cyan => "36",
+            Color::Grey => "90",
+        };
+        format!("\x1b[{code}m{text}\x1b[0m")
+    }
+}
+
+/// # TableRow
+/// a task together with how it is shown in the name column of a table.
+///
+/// # Members
+/// - **task**: the task shown in the row
+/// - **depth**: how deep the task is in the tree of tasks, indenting its name
+/// - **marks**: text shown after the name e.g., '[2/3 subtasks done]'
+/// - **urgency**: the urgency score of the task, shown in an extra first column
+///   if any row of the table has one
+pub struct TableRow<'a> {
+    pub task: &'a Task,
+    pub depth: usize,
+    pub marks: String,
+    pub urgency: Option<f64>,
+}
+
+impl<'a> TableRow<'a> {
+    /// # from_task
+    /// creates a row for `task` that is not indented and has no marks.
+    pub fn from_task(task: &'a Task) -> TableRow<'a> {
+        TableRow {
+            task,
+            depth: 0,
+            marks: String::new(),
+            urgency: None,
+        }
+    }
+}
+
+/// # fit
+/// cuts `text` to at most `width` characters, ending it with '…' if it was
+/// cut, and pads it with spaces to `width`.
+pub fn fit(text: &str, width: usize) -> String {
+    let char_count = text.chars().count();
+    if char_count <= width {
+        return format!("{text}{}", " ".repeat(width - char_count));
+    }
+    let mut fitted = text
+        .chars()
+        .take(width.saturating_sub(1))
+        .collect::<String>();
+    if width > 0 {
+        fitted.push('…');
+    }
+    fitted
+}
+
+/// # wrap
+/// splits `text` into lines of at most `width` characters at its spaces.
+/// Words longer than `width` are split over several lines.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::<String>::new();
+    let mut line = String::new();
+    for word in text.split(' ').filter(|word| !word.is_empty()) {
+        let mut word = word.chars().collect::<Vec<char>>();
+        let line_len = line.chars().count();
+        if line_len > 0 && line_len + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut line));
+        } else if line_len > 0 {
+            line.push(' ');
+        }
+        while line.chars().count() + word.len() > width {
+            let split_at = width - line.chars().count();
+            line.extend(word.drain(..split_at));
+            lines.push(std::mem::take(&mut line));
+        }
+        line.extend(word);
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+impl Column {
+    /// # header
+    /// returns the header of the column.
+    fn header(&self) -> &'static str {
+        match self {
+            Column::Id => "ID",
+            Column::Name => "Name",
+            Column::Status => "Status",
+            Column::Created => "Created",
+            Column::Modified => "Modified",
+            Column::Finished => "Finished",
+            Column::Due => "Due",
+            Column::Priority => "Priority",
+            Column::Tags => "Tags",
+            Column::Project => "Project",
+            Column::Parent => "Parent",
+            Column::Depends => "Depends on",
+            Column::Repeats => "Repeats",
+            Column::Time => "Time",
+        }
+    }
+
+    /// # cell
+    /// returns the text of the column for `task` and the color it is shown
+    /// in. The text is empty if the task has no value for the column.
+    fn cell(&self, task: &Task, now: DateTime<Local>) -> (String, Color) {
+        let show_date = |dt: DateTime<Local>| dt.format(TABLE_DATE_FMT).to_string();
+        match self {
+            Column::Id => (task.id.to_string(), Color::Plain),
+            Column::Name => (task.name.clone(), Color::Plain),
+            Column::Status => {
+                let color = match task.status {
+                    TaskStatus::Active => Color::Cyan,
+                    TaskStatus::Completed => Color::Green,
+                    TaskStatus::Deleted => Color::Grey,
+                };
+                (task.status.show(), color)
+            }
+            Column::Created => (show_date(task.time_created), Color::Plain),
+            Column::Modified => (show_date(task.last_time_modified), Color::Plain),
+            Column::Finished => (
+                task.time_finished.get().map(show_date).unwrap_or_default(),
+                Color::Plain,
+            ),
+            Column::Due => match task.due.get() {
+                Some(due) if task.status != TaskStatus::Active => (show_date(due), Color::Plain),
+                Some(due) if due < now => (show_date(due), Color::Red),
+                Some(due) if due.date_naive() == now.date_naive() => {
+                    (show_date(due), Color::Yellow)
+                }
+                Some(due) => (show_date(due), Color::Plain),
+                None => (String::new(), Color::Plain),
+            },
+            Column::Priority => match task.priority {
+                Priority::None => (String::new(), Color::Plain),
+                Priority::Low => (task.priority.show(), Color::Blue),
+                Priority::Medium => (task.priority.show(), Color::Yellow),
+                Priority::High => (task.priority.show(), Color::Red),
+            },
+            Column::Tags => (task.show_tags(), Color::Plain),
+            Column::Project => (task.project.clone().unwrap_or_default(), Color::Plain),
+            Column::Parent => (
+                task.parent
+                    .map(|parent| parent.to_string())
+                    .unwrap_or_default(),
+                Color::Plain,
+            ),
+            Column::Depends => (task.show_depends_on(" "), Color::Plain),
+            Column::Repeats => (
+                task.recurrence
+                    .as_ref()
+                    .map(|recurrence| recurrence.show())
+                    .unwrap_or_default(),
+                Color::Plain,
+            ),
+            Column::Time if task.work.is_empty() => (String::new(), Color::Plain),
+            Column::Time if task.is_timed() => (task.show_time_spent(now), Color::Green),
+            Column::Time => (show_duration(task.time_spent(now)), Color::Plain),
+        }
+    }
+}
+
+/// # show_table
+/// converts the rows to the lines of a table with a header line and one
+/// aligned column per selected column. Columns that are empty in every row
+/// are left out, as are the last columns after the name column if the table
+/// does not fit in `width` otherwise. The name column takes the width left
+/// over by the other columns within `width`, and names longer than that are wrapped onto more
+/// lines or cut, as set by `table.wrap`.
+/// # Arguments
+/// - **rows**: the tasks shown in the table, in order
+/// - **table**: the columns of the table and whether names are wrapped
+/// - **width**: the width of the terminal, or `None` to never wrap or cut names
+/// - **color**: whether the header, statuses, due dates, priorities and timers are coloured
+/// - **now**: the current local datetime, used for overdue tasks and running timers
+/// # Returns
+/// the lines of the table without trailing newlines.
+pub fn show_table(
+    rows: &[TableRow],
+    table: &Table,
+    width: Option<usize>,
+    color: bool,
+    now: DateTime<Local>,
+) -> Vec<String> {
+    // the text and color of every cell, one column at a time
+    let mut headers = Vec::<&str>::new();
+    let mut cells = Vec::<Vec<(String, Color)>>::new();
+    if rows.iter().any(|row| row.urgency.is_some()) {
+        headers.push("Urgency");
+        cells.push(
+            rows.iter()
+                .map(|row| {
+                    (
+                        row.urgency
+                            .map(|score| format!("{score:.2}"))
+                            .unwrap_or_default(),
+                        Color::Plain,
+                    )
+                })
+                .collect(),
+        );
+    }
+    let mut name_idx = None;
+    for column in &table.columns {
+        let column_cells = rows
+            .iter()
+            .map(|row| match column {
+                Column::Name => {
+                    let name = format!("{}{} {}", "  ".repeat(row.depth), row.task.name, row.marks);
+                    (name.trim_end().to_string(), Color::Plain)
+                }
+                _ => column.cell(row.task, now),
+            })
+            .collect::<Vec<(String, Color)>>();
+        if column_cells.iter().all(|(text, _)| text.is_empty()) {
+            continue;
+        }
+        if *column == Column::Name {
+            name_idx = Some(cells.len());
+        }
+        headers.push(column.header());
+        cells.push(column_cells);
+    }
+
+    // every column is as wide as its widest cell, up to a limit except for names
+    let mut widths = headers
+        .iter()
+        .zip(&cells)
+        .enumerate()
+        .map(|(column_idx, (header, column_cells))| {
+            let widest = column_cells
+                .iter()
+                .map(|(text, _)| text.chars().count())
+                .fold(header.chars().count(), usize::max);
+            if Some(column_idx) == name_idx {
+                widest
+            } else {
+                widest.min(MAX_COLUMN_WIDTH)
+            }
+        })
+        .collect::<Vec<usize>>();
+    if let (Some(name_idx), Some(width)) = (name_idx, width) {
+        let others_width = |widths: &[usize]| {
+            widths
+                .iter()
+                .enumerate()
+                .filter(|(column_idx, _)| *column_idx != name_idx)
+                .map(|(_, column_width)| column_width + COLUMN_GAP.len())
+                .sum::<usize>()
+        };
+        // the last columns after the names are left out if they do not fit
+        while others_width(&widths) + MIN_NAME_WIDTH > width && widths.len() > name_idx + 1 {
+            headers.pop();
+            cells.pop();
+            widths.pop();
+        }
+        let others_width = others_width(&widths);
+        let name_width = width.saturating_sub(others_width).max(MIN_NAME_WIDTH);
+        widths[name_idx] = widths[name_idx].min(name_width);
+    }
+
+    // joins the cells of a line, coloring their text but not their padding
+    let join = |line_cells: Vec<(String, Color)>| {
+        let line = line_cells
+            .into_iter()
+            .enumerate()
+            .map(|(column_idx, (text, cell_color))| {
+                let fitted = fit(&text, widths[column_idx]);
+                let cell_text = fitted.trim_end();
+                let padding = " ".repeat(fitted.chars().count() - cell_text.chars().count());
+                match (color, cell_text.is_empty()) {
+                    (true, false) => format!("{}{padding}", cell_color.paint(cell_text)),
+                    _ => fitted,
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(COLUMN_GAP);
+        line.trim_end().to_string()
+    };
+
+    let mut lines = vec![join(
+        headers
+            .iter()
+            .map(|header| (header.to_string(), Color::Header))
+            .collect(),
+    )];
+    for row_idx in 0..rows.len() {
+        // the name of the row wrapped onto as many lines as it needs
+        let name_lines = match name_idx {
+            Some(name_idx) if table.wrap => {
+                let depth_indent = "  ".repeat(rows[row_idx].depth);
+                let name = &cells[name_idx][row_idx].0;
+                let name_width = widths[name_idx].saturating_sub(depth_indent.len());
+                wrap(name.trim_start(), name_width)
+                    .into_iter()
+                    .map(|name_line| format!("{depth_indent}{name_line}"))
+                    .collect::<Vec<String>>()
+            }
+            _ => Vec::new(),
+        };
+        for line_idx in 0..name_lines.len().max(1) {
+            let line_cells = cells
+                .iter()
+                .enumerate()
+                .map(|(column_idx, column_cells)| {
+                    let (text, cell_color) = &column_cells[row_idx];
+                    match name_idx {
+                        Some(name_idx) if column_idx == name_idx && !name_lines.is_empty() => {
+                            (name_lines[line_idx].clone(), *cell_color)
+                        }
+                        _ if line_idx > 0 => (String::new(), Color::Plain),
+                        _ => (text.clone(), *cell_color),
+                    }
+                })
+                .collect::<Vec<(String, Color)>>();
+            lines.push(join(line_cells));
+        }
+    }
+    lines
+}
+
+/// # use_color
+/// decides whether the tables printed to stdout are coloured. With
+/// `ColorMode::Auto` they are coloured only if stdout is a terminal, the
+/// terminal is not 'dumb' and the `NO_COLOR` environment variable is not set
+/// to a non-empty value (see https://no-color.org).
+pub fn use_color(mode: ColorMode) -> bool {
+    match mode {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => {
+            let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+            let dumb = env::var("TERM").is_ok_and(|term| term == "dumb");
+            !no_color && !dumb && stdout().is_terminal()
+        }
+    }
+}
+
+/// # terminal_width
+/// returns the width of the terminal the tables are printed to, read from
+/// the `COLUMNS` environment variable if it is set or else from the terminal
+/// itself.
+/// # Returns
+/// `Some(width)` if stdout is a terminal or `COLUMNS` is set else `None`, so
+/// that names printed to a file or a pipe are never wrapped or cut.
+pub fn terminal_width() -> Option<usize> {
+    if let Some(columns) = env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse::<usize>().ok())
+        .filter(|columns| *columns > 0)
+    {
+        return Some(columns);
+    }
+    if !stdout().is_terminal() {
+        return None;
+    }
+    #[cfg(feature = "tui")]
+    if let Ok((width, _)) = crossterm::terminal::size() {
+        return Some(usize::from(width));
+    }
+    Some(80)
+}
+
+/// # print_table
+/// prints the rows as a table (see `show_table`) with the columns and colors
+/// of `table`, fitted to the width of the terminal.
+/// # Arguments
+/// - **console**: The `Console` the table is printed to
+/// - **rows**: the tasks shown in the table, in order
+/// - **table**: the table settings e.g., those read from 'config.json'
+/// # Returns
+/// none
+pub fn print_table(console: &mut dyn Console, rows: &[TableRow], table: &Table) {
+    let color = use_color(table.color);
+    for line in show_table(rows, table, terminal_width(), color, Local::now()) {
+        console.println(&line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::tasks::structs::Tasks;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 1, 31, 10, 0, 0).unwrap()
+    }
+
+    fn table(columns: &[Column], wrap: bool) -> Table {
+        Table {
+            columns: columns.to_vec(),
+            color: ColorMode::Never,
+            wrap,
+        }
+    }
+
+    /// a table with a long name and a nested subtask named 'call' that is in
+    /// the project 'home'. No task has a due date.
+    fn with_rows<T>(test: impl FnOnce(&[TableRow]) -> T) -> T {
+        let mut tasks = Tasks::new();
+        tasks.add("write the quarterly report for the team".to_string());
+        tasks.add("call".to_string());
+        tasks.active[1].project = Some("home".to_string());
+        let rows = [
+            TableRow::from_task(&tasks.active[0]),
+            TableRow {
+                depth: 2,
+                marks: "[1/2 subtasks done]".to_string(),
+                ..TableRow::from_task(&tasks.active[1])
+            },
+        ];
+        test(&rows)
+    }
+
+    const COLUMNS: [Column; 5] = [
+        Column::Id,
+        Column::Name,
+        Column::Status,
+        Column::Due,
+        Column::Project,
+    ];
+
+    #[test]
+    fn fit_pads_or_cuts_to_the_width() {
+        assert_eq!(fit("call", 6), "call  ");
+        assert_eq!(fit("call mum", 6), "call …");
+        assert_eq!(fit("call", 0), "");
+    }
+
+    #[test]
+    fn wrap_splits_at_spaces_and_long_words() {
+        assert_eq!(wrap("call mum  today", 8), ["call mum", "today"]);
+        assert_eq!(wrap("abcdefghij", 4), ["abcd", "efgh", "ij"]);
+        assert_eq!(wrap("", 4), [""]);
+    }
+
+    #[test]
+    fn empty_columns_are_left_out_and_names_are_not_fitted_without_a_width() {
+        let lines = with_rows(|rows| show_table(rows, &table(&COLUMNS, true), None, false, now()));
+        assert_eq!(
+            lines,
+            [
+                "ID  Name                                     Status  Project",
+                "1   write the quarterly report for the team  Active",
+                "2       call [1/2 subtasks done]             Active  home",
+            ]
+        );
+    }
+
+    #[test]
+    fn names_are_wrapped_below_the_depth_indent() {
+        let lines =
+            with_rows(|rows| show_table(rows, &table(&COLUMNS, true), Some(30), false, now()));
+        assert_eq!(
+            lines,
+            [
+                "ID  Name                Status",
+                "1   write the           Active",
+                "    quarterly report",
+                "    for the team",
+                "2       call [1/2       Active",
+                "        subtasks done]",
+            ]
+        );
+    }
+
+    #[test]
+    fn names_are_cut_unless_wrapped() {
+        let lines =
+            with_rows(|rows| show_table(rows, &table(&COLUMNS, false), Some(30), false, now()));
+        assert_eq!(
+            lines,
+            [
+                "ID  Name                Status",
+                "1   write the quarter…  Active",
+                "2       call [1/2 sub…  Active",
+            ]
+        );
+    }
+
+    #[test]
+    fn last_columns_are_left_out_when_narrow() {
+        let wide =
+            with_rows(|rows| show_table(rows, &table(&COLUMNS, false), Some(80), false, now()));
+        assert_eq!(
+            wide[0],
+            "ID  Name                                     Status  Project"
+        );
+        let narrow =
+            with_rows(|rows| show_table(rows, &table(&COLUMNS, false), Some(20), false, now()));
+        assert_eq!(narrow[0], "ID  Name");
+        // the name column is never made narrower than `MIN_NAME_WIDTH`
+        assert_eq!(narrow[1], "1   write the quart…");
+    }
+}
@@ -0,0 +1,40 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::inputs::prelude::Console;
+use crate::internal::tasks::events::task_events;
+use crate::internal::tasks::structs::DATE_FMT;
+
+/// # view_history
+/// prompts the user to enter a Task ID and prints the timeline of that task
+/// i.e., every change recorded for it in the event log, oldest first, with
+/// when it was made, who made it and by which operation (see `log_events`).
+/// Tasks that were purged can be looked up too.
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the timeline
+/// - **name_in**: The name of the user
+/// # Returns
+/// `Ok(())` if the function completes successfully else a `TodoError`, which
+/// is `TodoError::NotFound` if nothing was recorded for the task.
+pub fn view_history(console: &mut dyn Console, name_in: &str) -> TodoResult<()> {
+    let task_id = console
+        .input_num("Please enter the Task ID of the task whose history you wish to view: ")?;
+    if task_id <= 0 {
+        return Err(TodoError::invalid(format!("invalid Task ID '{task_id}'")));
+    }
+    let events = task_events(task_id)?;
+    console.println(&format!("\nHistory of Task {task_id}"));
+    console.println(&format!(
+        "{name_in}, {} changes are printed below",
+        events.len()
+    ));
+    for (event_idx, event) in events.iter().enumerate() {
+        console.println(&format!(
+            "{}. {} by {} ({}): {}",
+            event_idx + 1,
+            event.time.format(DATE_FMT),
+            event.actor,
+            event.action,
+            event.show()
+        ));
+    }
+    Ok(())
+}
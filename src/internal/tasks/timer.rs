@@ -0,0 +1,186 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::inputs::prelude::Console;
+use crate::internal::tasks::structs::{DateTimeOption, Task, TaskId, Tasks, WorkInterval};
+use crate::internal::tasks::utils::extract_task;
+use chrono::{DateTime, Local};
+
+/// The operations on the timers of tasks, which record the time spent on
+/// them as `WorkInterval`s. Only one timer runs at a time. A running timer
+/// is an interval without an end stored with its task, so it keeps running
+/// while the program is not.
+impl Tasks {
+    /// # timed_task
+    /// returns the task whose timer is running, if any.
+    pub fn timed_task(&self) -> Option<&Task> {
+        self.iter().find(|task| task.is_timed())
+    }
+
+    /// # start_timer
+    /// starts the timer of the active task with id `task_id` at `now` and
+    /// updates its `last_time_modified`. The timer of any other task is
+    /// stopped first so that only one timer runs at a time.
+    /// # Arguments
+    /// - **task_id**: the id of the task
+    /// - **now**: the current local datetime
+    /// # Returns
+    /// `Ok(stopped)` where stopped is the id of the task whose timer was
+    /// stopped, if any, else `TodoError::NotFound` if no active task with id
+    /// `task_id` exists or `TodoError::InvalidInput` if its timer is already
+    /// running.
+    pub fn start_timer(
+        &mut self,
+        task_id: TaskId,
+        now: DateTime<Local>,
+    ) -> TodoResult<Option<TaskId>> {
+        let task_idx = self
+            .active
+            .iter()
+            .position(|task| task.id == task_id)
+            .ok_or(TodoError::NotFound(task_id))?;
+        if self.active[task_idx].is_timed() {
+            return Err(TodoError::invalid(format!(
+                "The timer of task {task_id} is already running"
+            )));
+        }
+        let stopped = match self.timed_task() {
+            Some(_) => Some(self.stop_timer(now)?.id),
+            None => None,
+        };
+        let target_task = &mut self.active[task_idx];
+        target_task.work.push(WorkInterval {
+            start: now,
+            end: DateTimeOption::None,
+        });
+        target_task.last_time_modified = now;
+        Ok(stopped)
+    }
+
+    /// # stop_timer
+    /// stops the running timer at `now` and updates the `last_time_modified`
+    /// of its task.
+    /// # Returns
+    /// `Ok(task)` where task is a reference to the task whose timer was
+    /// stopped else `TodoError::InvalidInput` if no timer is running.
+    pub fn stop_timer(&mut self, now: DateTime<Local>) -> TodoResult<&Task> {
+        let task_id = self
+            .timed_task()
+            .map(|task| task.id)
+            .ok_or_else(|| TodoError::invalid("No timer is running"))?;
+        let target_task = self.find_mut(task_id).ok_or(TodoError::NotFound(task_id))?;
+        target_task.stop_work(now);
+        target_task.last_time_modified = now;
+        Ok(target_task)
+    }
+}
+
+/// # start_timer
+/// starts the timer of an active task. A view of the active tasks is printed
+/// out to the user and the user is prompted to enter the task ID of the
+/// target task. The running timer of another task is stopped first (see
+/// `Tasks::start_timer`).
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the result
+/// - **name_in**: The name of the user
+/// - **tasks**: A mutable ref to Tasks which stores the active tasks.
+/// # Returns
+/// `Ok(task_ids)` where task_ids are the ids of the tasks whose timers were
+/// started and stopped if the function completes successfully else a
+/// `TodoError`.
+pub fn start_timer(
+    console: &mut dyn Console,
+    name_in: &str,
+    tasks: &mut Tasks,
+) -> TodoResult<Vec<TaskId>> {
+    let prompt = "Please enter the Task ID of the task you are starting to work on: ";
+    let task_id = extract_task(console, name_in, prompt, &tasks.active)?;
+    let mut changed_ids = vec![task_id];
+    if let Some(stopped_id) = tasks.start_timer(task_id, Local::now())? {
+        let stopped_task = tasks
+            .find(stopped_id)
+            .ok_or(TodoError::NotFound(stopped_id))?;
+        console.println(&format!("\nStopped the timer of {}", stopped_task.show()));
+        changed_ids.push(stopped_id);
+    }
+    let target_task = tasks.find(task_id).ok_or(TodoError::NotFound(task_id))?;
+    console.println(&format!(
+        "\nTask with details {} is being timed",
+        target_task.show()
+    ));
+    Ok(changed_ids)
+}
+
+/// # stop_timer
+/// stops the running timer and prints the task it was running for.
+/// # Arguments
+/// - **console**: The `Console` used to print the result
+/// - **tasks**: A mutable ref to Tasks which stores the timed task.
+/// # Returns
+/// `Ok(task_id)` where task_id is the id of the task whose timer was stopped
+/// else `TodoError::InvalidInput` if no timer is running.
+pub fn stop_timer(console: &mut dyn Console, tasks: &mut Tasks) -> TodoResult<TaskId> {
+    let target_task = tasks.stop_timer(Local::now())?;
+    console.println(&format!(
+        "\nTask with details {} is no longer being timed",
+        target_task.show()
+    ));
+    Ok(target_task.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeDelta, TimeZone};
+
+    fn start() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 1, 31, 10, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn starting_a_timer_stops_the_running_one() {
+        let mut tasks = Tasks::new();
+        tasks.add("write report".to_string());
+        tasks.add("call bob".to_string());
+
+        assert_eq!(tasks.start_timer(1, start()).unwrap(), None);
+        let stopped = tasks.start_timer(2, start() + Duration::hours(1)).unwrap();
+        assert_eq!(stopped, Some(1));
+        assert_eq!(tasks.timed_task().unwrap().id, 2);
+        let report = tasks.find(1).unwrap();
+        assert!(!report.is_timed());
+        assert_eq!(
+            report.time_spent(start() + Duration::hours(5)),
+            TimeDelta::hours(1)
+        );
+
+        let stopped = tasks.stop_timer(start() + Duration::minutes(90)).unwrap();
+        assert_eq!(stopped.id, 2);
+        assert_eq!(
+            stopped.time_spent(start() + Duration::hours(5)),
+            TimeDelta::minutes(30)
+        );
+        assert!(tasks.timed_task().is_none());
+    }
+
+    #[test]
+    fn timers_only_run_once_and_on_active_tasks() {
+        let mut tasks = Tasks::new();
+        tasks.add("write report".to_string());
+        tasks.add("call bob".to_string());
+        tasks.complete(2).unwrap();
+
+        assert!(matches!(
+            tasks.stop_timer(start()),
+            Err(TodoError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            tasks.start_timer(2, start()),
+            Err(TodoError::NotFound(2))
+        ));
+        tasks.start_timer(1, start()).unwrap();
+        assert!(matches!(
+            tasks.start_timer(1, start() + Duration::hours(1)),
+            Err(TodoError::InvalidInput(_))
+        ));
+        assert_eq!(tasks.find(1).unwrap().work.len(), 1);
+    }
+}
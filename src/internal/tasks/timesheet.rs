@@ -0,0 +1,385 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::inputs::prelude::Console;
+use crate::internal::tasks::dates::{from_wall_clock, parse_due};
+use crate::internal::tasks::structs::{show_duration, TaskId, Tasks};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeDelta};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// the group of the time spent on tasks without a project.
+const NO_PROJECT: &str = "(no project)";
+
+/// the group of the time spent on tasks without tags.
+const NO_TAG: &str = "(no tag)";
+
+/// the header of the CSV export of a timesheet (see `to_csv`).
+const CSV_HEADER: &str = "group,task_id,task,minutes,hours";
+
+/// enum `Grouping`
+/// what the time spent on tasks is grouped by in a timesheet.
+///
+/// # Variants
+/// - **Day**: the local date the time was spent on e.g., '2024-07-01'
+/// - **Week**: the ISO week the time was spent in e.g., '2024-W27'
+/// - **Project**: the project of the task
+/// - **Tag**: each tag of the task, so the time of a task with two tags is
+///   counted in both groups
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Grouping {
+    Day,
+    Week,
+    Project,
+    Tag,
+}
+
+impl Grouping {
+    /// # parse
+    /// creates a new instance of `Grouping` from a string input such as
+    /// 'day' or 'project', ignoring case.
+    /// # Returns
+    /// `Some(Grouping)` if `grouping` names a grouping else `None`.
+    pub fn parse(grouping: &str) -> Option<Self> {
+        match grouping.trim().to_lowercase().as_str() {
+            "day" => Some(Grouping::Day),
+            "week" => Some(Grouping::Week),
+            "project" => Some(Grouping::Project),
+            "tag" => Some(Grouping::Tag),
+            _ => None,
+        }
+    }
+}
+
+/// # TimesheetEntry
+/// the time spent on one task within one group of a timesheet.
+///
+/// # Members
+/// - **group**: the day, week, project or tag the time was spent in
+/// - **task_id**: the id of the task
+/// - **name**: the name of the task
+/// - **duration**: the time spent on the task within the group
+#[derive(Clone)]
+pub struct TimesheetEntry {
+    pub group: String,
+    pub task_id: TaskId,
+    pub name: String,
+    pub duration: TimeDelta,
+}
+
+/// # parse_since
+/// parses the start of a timesheet e.g., '2024/07/01', 'monday' or 'today'
+/// (see `parse_due`). The timesheet starts at the beginning of that day.
+/// # Returns
+/// `Ok(since)` else `TodoError::InvalidInput` if `when` is not a date.
+pub fn parse_since(when: &str, now: DateTime<Local>) -> TodoResult<DateTime<Local>> {
+    let day = parse_due(when, now)?.date_naive();
+    from_wall_clock(day.and_time(NaiveTime::MIN), &Local)
+        .ok_or_else(|| TodoError::invalid(format!("'{when}' is out of range")))
+}
+
+/// # split_by_day
+/// splits the time from `start` to `end` at each local midnight.
+/// # Returns
+/// the local date and the time spent on it for each day in the period.
+fn split_by_day(start: DateTime<Local>, end: DateTime<Local>) -> Vec<(NaiveDate, TimeDelta)> {
+    let mut days = Vec::<(NaiveDate, TimeDelta)>::new();
+    let mut day_start = start;
+    while day_start < end {
+        let next_midnight = day_start
+            .date_naive()
+            .succ_opt()
+            .and_then(|next_day| from_wall_clock(next_day.and_time(NaiveTime::MIN), &Local));
+        let day_end = next_midnight.map_or(end, |midnight| midnight.min(end));
+        days.push((day_start.date_naive(), day_end - day_start));
+        day_start = day_end;
+    }
+    days
+}
+
+/// The reports on the time spent on tasks, recorded by their timers (see
+/// `Tasks::start_timer`).
+impl Tasks {
+    /// # timesheet
+    /// adds up the time spent on each task of every status per group. Time
+    /// before `since` is left out and a running timer counts up to `now`.
+    /// # Arguments
+    /// - **grouping**: what the time is grouped by
+    /// - **since**: when the timesheet starts, or `None` for all recorded time
+    /// - **now**: the current local datetime
+    /// # Returns
+    /// one `TimesheetEntry` per group and task that time was spent on, ordered
+    /// by group and then by Task ID.
+    pub fn timesheet(
+        &self,
+        grouping: Grouping,
+        since: Option<DateTime<Local>>,
+        now: DateTime<Local>,
+    ) -> Vec<TimesheetEntry> {
+        let mut totals = BTreeMap::<(String, TaskId), (String, TimeDelta)>::new();
+        for task in self.iter() {
+            for interval in &task.work {
+                let start = since.map_or(interval.start, |since| interval.start.max(since));
+                let end = interval.end.get().unwrap_or(now);
+                if end <= start {
+                    continue;
+                }
+                let groups = match grouping {
+                    Grouping::Day => split_by_day(start, end)
+                        .into_iter()
+                        .map(|(day, duration)| (day.format("%Y-%m-%d").to_string(), duration))
+                        .collect::<Vec<(String, TimeDelta)>>(),
+                    Grouping::Week => split_by_day(start, end)
+                        .into_iter()
+                        .map(|(day, duration)| (day.format("%G-W%V").to_string(), duration))
+                        .collect(),
+                    Grouping::Project => {
+                        let project = task.project.as_deref().unwrap_or(NO_PROJECT);
+                        vec![(project.to_string(), end - start)]
+                    }
+                    Grouping::Tag if task.tags.is_empty() => {
+                        vec![(NO_TAG.to_string(), end - start)]
+                    }
+                    Grouping::Tag => task
+                        .tags
+                        .iter()
+                        .map(|tag| (format!("+{tag}"), end - start))
+                        .collect(),
+                };
+                for (group, duration) in groups {
+                    let total = totals
+                        .entry((group, task.id))
+                        .or_insert_with(|| (task.name.clone(), TimeDelta::zero()));
+                    total.1 += duration;
+                }
+            }
+        }
+        totals
+            .into_iter()
+            .map(|((group, task_id), (name, duration))| TimesheetEntry {
+                group,
+                task_id,
+                name,
+                duration,
+            })
+            .collect()
+    }
+}
+
+/// # show_timesheet
+/// converts the entries of a timesheet to lines for display, with the total
+/// of each group followed by the time spent on each of its tasks and the
+/// total of the whole timesheet at the end.
+pub fn show_timesheet(entries: &[TimesheetEntry]) -> Vec<String> {
+    let mut lines = Vec::<String>::new();
+    let mut total = TimeDelta::zero();
+    let mut entry_idx = 0;
+    while entry_idx < entries.len() {
+        let group = &entries[entry_idx].group;
+        let group_entries = entries[entry_idx..]
+            .iter()
+            .take_while(|entry| &entry.group == group)
+            .collect::<Vec<&TimesheetEntry>>();
+        let group_total = group_entries
+            .iter()
+            .fold(TimeDelta::zero(), |group_total, entry| {
+                group_total + entry.duration
+            });
+        lines.push(format!("{group}: {}", show_duration(group_total)));
+        for entry in &group_entries {
+            lines.push(format!(
+                "    Task {} {}: {}",
+                entry.task_id,
+                entry.name,
+                show_duration(entry.duration)
+            ));
+        }
+        total += group_total;
+        entry_idx += group_entries.len();
+    }
+    lines.push(format!("Total: {}", show_duration(total)));
+    lines
+}
+
+/// # csv_field
+/// quotes `field` for a CSV file if it contains a comma, a quote or a
+/// line break, doubling the quotes inside it.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// # to_csv
+/// converts the entries of a timesheet to CSV with the columns group,
+/// task_id, task, minutes and hours, where hours are decimal e.g., '1.25'
+/// for an hour and a quarter.
+/// # Returns
+/// the CSV text with a header line and one line per entry, each terminated
+/// by a newline.
+pub fn to_csv(entries: &[TimesheetEntry]) -> String {
+    let mut csv = format!("{CSV_HEADER}\n");
+    for entry in entries {
+        let minutes = entry.duration.num_minutes();
+        csv.push_str(&format!(
+            "{},{},{},{minutes},{:.2}\n",
+            csv_field(&entry.group),
+            entry.task_id,
+            csv_field(&entry.name),
+            minutes as f64 / 60.0
+        ));
+    }
+    csv
+}
+
+/// # view_timesheet
+/// prompts the user to select what to group the time spent on tasks by and
+/// from which day, prints the timesheet (see `Tasks::timesheet`) and offers
+/// to export it to a CSV file (see `to_csv`).
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user and print the timesheet
+/// - **name_in**: The name of the user
+/// - **tasks**: A ref to Tasks which stores the tasks
+/// # Returns
+/// `Ok(())` if the function completes successfully else a `TodoError`.
+pub fn view_timesheet(console: &mut dyn Console, name_in: &str, tasks: &Tasks) -> TodoResult<()> {
+    let options = ["Day", "Week", "Project", "Tag"];
+    let selected =
+        console.input_option("Select what to group the time by: ", &options, "Timesheet")?;
+    let grouping = Grouping::parse(selected)
+        .ok_or_else(|| TodoError::invalid(format!("Unknown grouping '{selected}'")))?;
+    let now = Local::now();
+    let since_input =
+        console.input_str("Enter the first day of the timesheet (leave empty for all time): ")?;
+    let since = match since_input.as_str() {
+        "" => None,
+        when => Some(parse_since(when, now)?),
+    };
+
+    let entries = tasks.timesheet(grouping, since, now);
+    console.println("\nTimesheet");
+    if entries.is_empty() {
+        console.println(&format!("{name_in}, no time was recorded"));
+        return Ok(());
+    }
+    console.println(&format!(
+        "{name_in}, the time spent on your tasks is printed below"
+    ));
+    for line in show_timesheet(&entries) {
+        console.println(&line);
+    }
+    let export_input = console
+        .input_str("Enter a file to export the timesheet to as CSV (leave empty to skip): ")?;
+    if !export_input.is_empty() {
+        let export_path = Path::new(&export_input);
+        fs::write(export_path, to_csv(&entries)).map_err(|e| TodoError::io(export_path, e))?;
+        console.println(&format!(
+            "\nThe timesheet has been exported to {}",
+            export_path.display()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::tasks::structs::{DateTimeOption, WorkInterval};
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 1, day, hour, 0, 0).unwrap()
+    }
+
+    /// 'write report' in the project 'work' with the tags 'urgent' and
+    /// 'writing', worked on from 23:00 on Jan 30 until 01:00 on Jan 31, and
+    /// 'call bob' without a project or tags, whose timer is running since
+    /// 09:00 on Jan 31.
+    fn worked_tasks() -> Tasks {
+        let mut tasks = Tasks::new();
+        tasks.add("write report".to_string());
+        tasks.add("call, bob".to_string());
+        let report = tasks.find_mut(1).unwrap();
+        report.project = Some("work".to_string());
+        report.tags = ["urgent".to_string(), "writing".to_string()].into();
+        report.work.push(WorkInterval {
+            start: at(30, 23),
+            end: DateTimeOption::DateTime(at(31, 1)),
+        });
+        tasks.start_timer(2, at(31, 9)).unwrap();
+        tasks
+    }
+
+    /// the group, Task ID and minutes of each entry.
+    fn minutes(entries: &[TimesheetEntry]) -> Vec<(&str, TaskId, i64)> {
+        entries
+            .iter()
+            .map(|entry| {
+                let minutes = entry.duration.num_minutes();
+                (entry.group.as_str(), entry.task_id, minutes)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn time_is_split_at_midnight_and_running_timers_count_up_to_now() {
+        let tasks = worked_tasks();
+        let entries = tasks.timesheet(Grouping::Day, None, at(31, 10));
+        assert_eq!(
+            minutes(&entries),
+            [
+                ("2024-01-30", 1, 60),
+                ("2024-01-31", 1, 60),
+                ("2024-01-31", 2, 60)
+            ]
+        );
+
+        // time before the start of the timesheet is left out
+        let entries = tasks.timesheet(Grouping::Week, Some(at(31, 0)), at(31, 10));
+        assert_eq!(
+            minutes(&entries),
+            [("2024-W05", 1, 60), ("2024-W05", 2, 60)]
+        );
+    }
+
+    #[test]
+    fn time_is_counted_in_every_project_and_tag_of_the_task() {
+        let tasks = worked_tasks();
+        let entries = tasks.timesheet(Grouping::Project, None, at(31, 10));
+        assert_eq!(
+            minutes(&entries),
+            [("(no project)", 2, 60), ("work", 1, 120)]
+        );
+        let entries = tasks.timesheet(Grouping::Tag, None, at(31, 10));
+        assert_eq!(
+            minutes(&entries),
+            [
+                ("(no tag)", 2, 60),
+                ("+urgent", 1, 120),
+                ("+writing", 1, 120)
+            ]
+        );
+    }
+
+    #[test]
+    fn timesheets_are_shown_with_totals_and_exported_to_csv() {
+        let entries = worked_tasks().timesheet(Grouping::Project, None, at(31, 10));
+        assert_eq!(
+            show_timesheet(&entries),
+            [
+                "(no project): 1h 00m",
+                "    Task 2 call, bob: 1h 00m",
+                "work: 2h 00m",
+                "    Task 1 write report: 2h 00m",
+                "Total: 3h 00m",
+            ]
+        );
+        assert_eq!(
+            to_csv(&entries),
+            "group,task_id,task,minutes,hours\n\
+             (no project),2,\"call, bob\",60,1.00\n\
+             work,1,write report,120,2.00\n"
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use crate::internal::error::TodoResult;
+use crate::internal::inputs::prelude::Console;
+use crate::internal::tasks::structs::{TaskId, Tasks};
+use crate::internal::tasks::undo_stack::UndoStack;
+
+/// # undo
+/// undoes the most recent change to the tasks, whether it was made in the
+/// interactive menu or on the command line (see `UndoStack::undo`), and prints
+/// what was undone.
+/// # Arguments
+/// - **console**: The `Console` used to print the result
+/// - **name_in**: The name of the user
+/// - **tasks**: A mutable ref to Tasks which stores the active, completed and deleted tasks.
+/// # Returns
+/// `Ok(task_ids)` where task_ids are the ids of the restored tasks if the
+/// function completes successfully else a `TodoError`.
+pub fn undo(
+    console: &mut dyn Console,
+    name_in: &str,
+    tasks: &mut Tasks,
+) -> TodoResult<Vec<TaskId>> {
+    let mut undo_stack = UndoStack::load()?;
+    let action = undo_stack.undo(tasks)?;
+    undo_stack.save()?;
+    console.println(&format!("\n{name_in}, {} has been undone", action.show()));
+    Ok(action.changes.iter().map(|change| change.task_id).collect())
+}
+
+/// # redo
+/// applies the most recently undone change to the tasks again (see
+/// `UndoStack::redo`) and prints what was redone.
+/// # Arguments
+/// - **console**: The `Console` used to print the result
+/// - **name_in**: The name of the user
+/// - **tasks**: A mutable ref to Tasks which stores the active, completed and deleted tasks.
+/// # Returns
+/// `Ok(task_ids)` where task_ids are the ids of the restored tasks if the
+/// function completes successfully else a `TodoError`.
+pub fn redo(
+    console: &mut dyn Console,
+    name_in: &str,
+    tasks: &mut Tasks,
+) -> TodoResult<Vec<TaskId>> {
+    let mut undo_stack = UndoStack::load()?;
+    let action = undo_stack.redo(tasks)?;
+    undo_stack.save()?;
+    console.println(&format!("\n{name_in}, {} has been redone", action.show()));
+    Ok(action.changes.iter().map(|change| change.task_id).collect())
+}
@@ -0,0 +1,348 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::paths::get_file_path;
+use crate::internal::storage::atomic::write_atomic;
+use crate::internal::tasks::dates::utc_timestamp;
+use crate::internal::tasks::format::to_line;
+use crate::internal::tasks::structs::{Task, TaskId, Tasks};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// the file holding the changes that can be undone and redone.
+pub const UNDO_FILE: &str = "undo.json";
+
+/// the most changes kept for undo. The oldest change is dropped once a
+/// new change would exceed it.
+pub const MAX_UNDO: usize = 100;
+
+/// # Change
+/// the state of a single task before and after an operation.
+///
+/// # Members
+/// - **task_id**: the id of the task
+/// - **before**: the task before the operation, or `None` if the operation added it
+/// - **after**: the task after the operation, or `None` if the operation removed it
+/// - **before_idx**: the index of the task in the list of its status before the operation
+/// - **after_idx**: the index of the task in the list of its status after the operation
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Change {
+    pub task_id: TaskId,
+    pub before: Option<Task>,
+    pub after: Option<Task>,
+    #[serde(default)]
+    pub before_idx: Option<usize>,
+    #[serde(default)]
+    pub after_idx: Option<usize>,
+}
+
+/// # Action
+/// an operation that changed tasks, kept so that it can be undone and redone.
+///
+/// # Members
+/// - **description**: what the operation was e.g., 'Mark task as complete' or 'done 12'
+/// - **time**: when the operation was performed
+/// - **changes**: the tasks the operation changed
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Action {
+    pub description: String,
+    #[serde(with = "utc_timestamp")]
+    pub time: DateTime<Local>,
+    pub changes: Vec<Change>,
+}
+
+impl Action {
+    /// # show
+    /// converts an `Action` to a String for display e.g.,
+    /// "'done 12' (tasks 12, 13)".
+    pub fn show(&self) -> String {
+        let task_ids = self
+            .changes
+            .iter()
+            .map(|change| change.task_id.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+        let noun = if self.changes.len() == 1 {
+            "task"
+        } else {
+            "tasks"
+        };
+        format!("'{}' ({noun} {task_ids})", self.description)
+    }
+}
+
+/// # UndoStack
+/// the undo and redo stacks of the program, stored in 'undo.json' in the
+/// data dir so that changes can be undone after the program restarted. Both
+/// the interactive menu and the command line record every operation that
+/// changes tasks, and undoing or redoing one restores the recorded copies of
+/// the tasks it changed.
+///
+/// # Members
+/// - **undo**: the actions that can be undone, most recent last
+/// - **redo**: the undone actions that can be redone, most recently undone last
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UndoStack {
+    pub undo: Vec<Action>,
+    pub redo: Vec<Action>,
+}
+
+/// # list_idx
+/// returns the index of the task with id `task_id` in the list of the tasks
+/// with its status, or `None` if there is no such task.
+fn list_idx(tasks: &Tasks, task_id: TaskId) -> Option<usize> {
+    let task = tasks.find(task_id)?;
+    tasks
+        .list(task.status)
+        .iter()
+        .position(|each| each.id == task_id)
+}
+
+/// # same_task
+/// checks if two stored states of a task are the same by comparing their
+/// stored records (see `format::to_line`).
+fn same_task(first: Option<&Task>, second: Option<&Task>) -> TodoResult<bool> {
+    Ok(match (first, second) {
+        (Some(first), Some(second)) => to_line(first)? == to_line(second)?,
+        (first, second) => first.is_none() && second.is_none(),
+    })
+}
+
+impl UndoStack {
+    /// # load
+    /// reads the undo and redo stacks from 'undo.json'. If the file has not
+    /// been created yet, empty stacks are returned.
+    /// # Returns
+    /// `Ok(UndoStack)` if the function completes successfully else
+    /// `TodoError::Io` if the file cannot be read or `TodoError::Corrupt`
+    /// if it cannot be parsed.
+    pub fn load() -> TodoResult<UndoStack> {
+        let undo_path = get_file_path(UNDO_FILE);
+        if !undo_path.exists() {
+            return Ok(UndoStack::default());
+        }
+        let undo_str = fs::read_to_string(&undo_path).map_err(|e| TodoError::io(&undo_path, e))?;
+        serde_json::from_str::<UndoStack>(&undo_str).map_err(|e| {
+            TodoError::Corrupt(format!("Could not parse {}: {e}", undo_path.display()))
+        })
+    }
+
+    /// # save
+    /// writes the undo and redo stacks to 'undo.json', replacing the file
+    /// atomically.
+    /// # Returns
+    /// `Ok(())` if the function completes successfully else a `TodoError`.
+    pub fn save(&self) -> TodoResult<()> {
+        let undo_str = serde_json::to_string(self)
+            .map_err(|e| TodoError::Storage(format!("Could not serialize {UNDO_FILE}: {e}")))?;
+        write_atomic(&get_file_path(UNDO_FILE), undo_str.as_bytes())
+    }
+
+    /// # record
+    /// records an operation as the most recent action to undo by comparing
+    /// the tasks with ids `task_ids` before and after it. Nothing is recorded
+    /// if none of them changed, else the redo stack is cleared as the undone
+    /// actions no longer apply.
+    /// # Arguments
+    /// - **description**: what the operation was
+    /// - **before**: a copy of the tasks taken before the operation
+    /// - **after**: the tasks after the operation
+    /// - **task_ids**: the ids of the tasks the operation changed
+    /// # Returns
+    /// `Ok(recorded)` where recorded is `true` if an action was recorded
+    /// else a `TodoError` if a task could not be compared.
+    pub fn record(
+        &mut self,
+        description: &str,
+        before: &Tasks,
+        after: &Tasks,
+        task_ids: &[TaskId],
+    ) -> TodoResult<bool> {
+        let mut changes = Vec::<Change>::new();
+        for &task_id in task_ids {
+            if changes.iter().any(|change| change.task_id == task_id) {
+                continue;
+            }
+            let (task_before, task_after) = (before.find(task_id), after.find(task_id));
+            if !same_task(task_before, task_after)? {
+                changes.push(Change {
+                    task_id,
+                    before: task_before.cloned(),
+                    after: task_after.cloned(),
+                    before_idx: list_idx(before, task_id),
+                    after_idx: list_idx(after, task_id),
+                });
+            }
+        }
+        if changes.is_empty() {
+            return Ok(false);
+        }
+        self.undo.push(Action {
+            description: description.to_string(),
+            time: Local::now(),
+            changes,
+        });
+        if self.undo.len() > MAX_UNDO {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+        Ok(true)
+    }
+
+    /// # undo
+    /// restores the tasks changed by the most recent action to their state
+    /// and place in their list before it and moves the action to the redo
+    /// stack.
+    /// # Arguments
+    /// - **tasks**: the tasks the action is undone on
+    /// # Returns
+    /// `Ok(action)` where action is the undone `Action` else
+    /// `TodoError::InvalidInput` if there is nothing to undo.
+    pub fn undo(&mut self, tasks: &mut Tasks) -> TodoResult<Action> {
+        let action = self
+            .undo
+            .pop()
+            .ok_or_else(|| TodoError::invalid("There is nothing to undo"))?;
+        tasks.restore_states(
+            action
+                .changes
+                .iter()
+                .map(|change| (change.task_id, change.before.clone(), change.before_idx)),
+        );
+        self.redo.push(action.clone());
+        Ok(action)
+    }
+
+    /// # redo
+    /// applies the most recently undone action again i.e., restores the tasks
+    /// it changed to their state after it, and moves it back to the undo stack.
+    /// # Arguments
+    /// - **tasks**: the tasks the action is redone on
+    /// # Returns
+    /// `Ok(action)` where action is the redone `Action` else
+    /// `TodoError::InvalidInput` if there is nothing to redo.
+    pub fn redo(&mut self, tasks: &mut Tasks) -> TodoResult<Action> {
+        let action = self
+            .redo
+            .pop()
+            .ok_or_else(|| TodoError::invalid("There is nothing to redo"))?;
+        tasks.restore_states(
+            action
+                .changes
+                .iter()
+                .map(|change| (change.task_id, change.after.clone(), change.after_idx)),
+        );
+        self.undo.push(action.clone());
+        Ok(action)
+    }
+}
+
+impl Tasks {
+    /// # restore_state
+    /// puts back a recorded state of the task with id `task_id`, replacing
+    /// the task if it exists, or removes the task if `state` is `None`.
+    pub fn restore_state(&mut self, task_id: TaskId, state: Option<Task>) {
+        match state {
+            Some(task) => {
+                if let Err(TodoError::NotFound(_)) = self.replace(task.clone()) {
+                    self.push(task);
+                }
+            }
+            None => {
+                self.take(task_id);
+            }
+        }
+    }
+
+    /// # restore_states
+    /// puts back recorded states of tasks as (id, state, index) triples. Every
+    /// task with one of the ids is removed first, then each state that is not
+    /// `None` is inserted at its index in the list of its status, or appended
+    /// if no index was recorded. Inserting the states in the order of their
+    /// indexes puts every task back at the place it was recorded at.
+    pub fn restore_states(
+        &mut self,
+        states: impl IntoIterator<Item = (TaskId, Option<Task>, Option<usize>)>,
+    ) {
+        let mut restored = Vec::<(Task, Option<usize>)>::new();
+        for (task_id, state, task_idx) in states {
+            self.take(task_id);
+            if let Some(task) = state {
+                restored.push((task, task_idx));
+            }
+        }
+        restored.sort_by_key(|(_, task_idx)| task_idx.unwrap_or(usize::MAX));
+        for (task, task_idx) in restored {
+            let tasks_list = self.list_mut(task.status);
+            let task_idx = task_idx.unwrap_or(tasks_list.len()).min(tasks_list.len());
+            tasks_list.insert(task_idx, task);
+        }
+    }
+}
+
+/// # record_undo
+/// records an operation in 'undo.json' so that it can be undone (see
+/// `UndoStack::record`).
+/// # Arguments
+/// - **description**: what the operation was
+/// - **before**: a copy of the tasks taken before the operation
+/// - **after**: the tasks after the operation
+/// - **task_ids**: the ids of the tasks the operation changed
+/// # Returns
+/// `Ok(())` if the function completes successfully else a `TodoError`.
+pub fn record_undo(
+    description: &str,
+    before: &Tasks,
+    after: &Tasks,
+    task_ids: &[TaskId],
+) -> TodoResult<()> {
+    let mut undo_stack = UndoStack::load()?;
+    if undo_stack.record(description, before, after, task_ids)? {
+        undo_stack.save()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::tasks::structs::TaskStatus;
+
+    fn active_ids(tasks: &Tasks) -> Vec<TaskId> {
+        tasks.active.iter().map(|task| task.id).collect()
+    }
+
+    #[test]
+    fn undo_puts_tasks_back_at_their_place() {
+        let mut tasks = Tasks::new();
+        for name in ["first", "second", "third", "fourth"] {
+            tasks.add(name.to_string());
+        }
+        let mut undo_stack = UndoStack::default();
+        let before = tasks.clone();
+        tasks.complete(2).unwrap();
+        tasks.delete(3).unwrap();
+        undo_stack
+            .record("complete and delete", &before, &tasks, &[2, 3])
+            .unwrap();
+        assert_eq!(active_ids(&tasks), [1, 4]);
+
+        undo_stack.undo(&mut tasks).unwrap();
+        assert_eq!(active_ids(&tasks), [1, 2, 3, 4]);
+        assert!(tasks.completed.is_empty() && tasks.deleted.is_empty());
+        undo_stack.redo(&mut tasks).unwrap();
+        assert_eq!(active_ids(&tasks), [1, 4]);
+        assert_eq!(tasks.find(2).unwrap().status, TaskStatus::Completed);
+        assert_eq!(tasks.find(3).unwrap().status, TaskStatus::Deleted);
+    }
+
+    #[test]
+    fn nothing_is_recorded_for_unchanged_tasks() {
+        let mut tasks = Tasks::new();
+        tasks.add(String::from("first"));
+        let mut undo_stack = UndoStack::default();
+        let before = tasks.clone();
+        assert!(!undo_stack.record("view", &before, &tasks, &[1]).unwrap());
+        assert!(undo_stack.undo(&mut tasks).is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use crate::internal::config::Urgency;
+use crate::internal::tasks::structs::{Priority, Task};
+use chrono::{DateTime, Local};
+
+/// the number of seconds in a day.
+const DAY_SECS: f64 = 86_400.0;
+
+/// # due_proximity
+/// scores how close `due` is to `now` from 0.2 (due in two weeks or more)
+/// to 1.0 (a week or more overdue), rising linearly in between.
+fn due_proximity(due: DateTime<Local>, now: DateTime<Local>) -> f64 {
+    let days_left = (due - now).num_seconds() as f64 / DAY_SECS;
+    if days_left <= -7.0 {
+        1.0
+    } else if days_left >= 14.0 {
+        0.2
+    } else {
+        (14.0 - days_left) / 21.0 * 0.8 + 0.2
+    }
+}
+
+/// # urgency
+/// computes the urgency score of `task`, which is the sum of
+///
+/// - the coefficient of its priority
+/// - its age from `time_created` as a fraction of `age_max_days`, times
+///   the `age` coefficient
+/// - how close its due date is (see `due_proximity`), times the `due`
+///   coefficient
+/// - 0.8, 0.9 or 1.0 for one, two or more tags, times the `tags` coefficient
+///
+/// A higher score means the task is more urgent.
+/// # Arguments
+/// - **task**: the task to be scored
+/// - **coefficients**: the coefficients of each term, usually read from
+///   'config.json'
+/// - **now**: the current local datetime
+/// # Returns
+/// the urgency score of `task`.
+pub fn urgency(task: &Task, coefficients: &Urgency, now: DateTime<Local>) -> f64 {
+    let priority_score = match task.priority {
+        Priority::High => coefficients.high,
+        Priority::Medium => coefficients.medium,
+        Priority::Low => coefficients.low,
+        Priority::None => 0.0,
+    };
+
+    let age_days = (now - task.time_created).num_seconds().max(0) as f64 / DAY_SECS;
+    let age_score = if coefficients.age_max_days > 0.0 {
+        (age_days / coefficients.age_max_days).min(1.0) * coefficients.age
+    } else {
+        coefficients.age
+    };
+
+    let due_score = match task.due.get() {
+        Some(due) => due_proximity(due, now) * coefficients.due,
+        None => 0.0,
+    };
+
+    let tags_score = match task.tags.len() {
+        0 => 0.0,
+        1 => 0.8 * coefficients.tags,
+        2 => 0.9 * coefficients.tags,
+        _ => coefficients.tags,
+    };
+
+    priority_score + age_score + due_score + tags_score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::tasks::structs::{DateTimeOption, TaskId, TaskStatus, Tasks};
+    use chrono::{Duration, TimeZone};
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 1, 31, 10, 0, 0).unwrap()
+    }
+
+    /// a new task created at `now`.
+    fn new_task(tasks: &mut Tasks, name: &str) -> TaskId {
+        let task_id = tasks.add(name.to_string());
+        tasks.find_mut(task_id).unwrap().time_created = now();
+        task_id
+    }
+
+    fn assert_close(score: f64, expected: f64) {
+        assert!((score - expected).abs() < 1e-9, "{score} != {expected}");
+    }
+
+    #[test]
+    fn every_term_adds_its_coefficient() {
+        let coefficients = Urgency::default();
+        let mut tasks = Tasks::new();
+        let task_id = new_task(&mut tasks, "write report");
+        assert_close(
+            urgency(tasks.find(task_id).unwrap(), &coefficients, now()),
+            0.0,
+        );
+
+        let task = tasks.find_mut(task_id).unwrap();
+        task.priority = Priority::High;
+        task.time_created = now() - Duration::days(400);
+        task.tags = ["work".to_string(), "urgent".to_string()].into();
+        // 6.0 for the priority, 2.0 for over a year old and 0.9 * 1.0 for two tags
+        assert_close(urgency(task, &coefficients, now()), 8.9);
+
+        // due now is two thirds of the way from two weeks ahead to a week overdue
+        task.due = DateTimeOption::DateTime(now());
+        let due_score = (14.0 / 21.0 * 0.8 + 0.2) * coefficients.due;
+        assert_close(urgency(task, &coefficients, now()), 8.9 + due_score);
+    }
+
+    #[test]
+    fn due_dates_score_between_a_fifth_and_the_whole_coefficient() {
+        let in_days = |days: i64| due_proximity(now() + Duration::days(days), now());
+        assert_close(in_days(-30), 1.0);
+        assert_close(in_days(-7), 1.0);
+        assert_close(in_days(0), 14.0 / 21.0 * 0.8 + 0.2);
+        assert_close(in_days(14), 0.2);
+        assert_close(in_days(60), 0.2);
+    }
+
+    #[test]
+    fn the_most_urgent_tasks_are_listed_first() {
+        let mut tasks = Tasks::new();
+        let low = new_task(&mut tasks, "water plants");
+        let overdue = new_task(&mut tasks, "pay rent");
+        let high = new_task(&mut tasks, "call bob");
+        tasks.find_mut(low).unwrap().priority = Priority::Low;
+        tasks.find_mut(overdue).unwrap().due = DateTimeOption::DateTime(now() - Duration::days(7));
+        tasks.find_mut(high).unwrap().priority = Priority::High;
+
+        let ranked = tasks.by_urgency(TaskStatus::Active, &Urgency::default(), now());
+        let ranked_ids = ranked.iter().map(|task| task.id).collect::<Vec<TaskId>>();
+        assert_eq!(ranked_ids, [overdue, high, low]);
+    }
+}
@@ -1,22 +1,123 @@
-use crate::internal::tasks::structs::Task;
+use crate::internal::config::Urgency;
+use crate::internal::inputs::prelude::Console;
+use crate::internal::tasks::structs::{Task, TaskStatus, Tasks};
+use crate::internal::tasks::table::{print_table, TableRow};
+use crate::internal::tasks::urgency::urgency;
+use chrono::{DateTime, Local};
 
 /// # view
 /// prints out all the tasks passed into the function as
-/// the argument `tasks_list`
+/// the argument `tasks_list` as a table (see `print_table`) with the table
+/// settings of the console
 /// # Arguments
+/// - **console**: The `Console` the tasks are printed to
 /// - **name_in**: The name of the user
 /// - **tasks_list**: a list of tasks that should be printed
 /// # Returns
 /// none
-pub fn view(name_in: &str, tasks_list: &Vec<Task>) {
-    println!("\nTasks View");
-    if tasks_list.len() > 0 {
-        println!("{name_in}, your tasks are printed below");
+pub fn view(console: &mut dyn Console, name_in: &str, tasks_list: &[Task]) {
+    console.println("\nTasks View");
+    if !tasks_list.is_empty() {
+        console.println(&format!("{name_in}, your tasks are printed below"));
     } else {
-        println!("{name_in}, you have no tasks to view");
+        console.println(&format!("{name_in}, you have no tasks to view"));
     }
 
-    for (task_idx, task) in tasks_list.iter().enumerate() {
-        println!("{}. {}", task_idx + 1, task.show());
+    let rows = tasks_list
+        .iter()
+        .map(TableRow::from_task)
+        .collect::<Vec<TableRow>>();
+    if !rows.is_empty() {
+        let table = console.table();
+        print_table(console, &rows, &table);
+    }
+}
+
+/// # view_by_urgency
+/// prints out the tasks passed into the function as the argument
+/// `tasks_list` as a table with the urgency score of each task in its first
+/// column (see `print_table`). The tasks
+/// are expected to be ordered already e.g., by `Tasks::by_urgency`.
+/// # Arguments
+/// - **console**: The `Console` the tasks are printed to
+/// - **name_in**: The name of the user
+/// - **tasks_list**: a list of tasks that should be printed
+/// - **coefficients**: the coefficients of the urgency score
+/// - **now**: the current local datetime
+/// # Returns
+/// none
+pub fn view_by_urgency(
+    console: &mut dyn Console,
+    name_in: &str,
+    tasks_list: &[Task],
+    coefficients: &Urgency,
+    now: DateTime<Local>,
+) {
+    console.println("\nTasks View (most urgent first)");
+    if !tasks_list.is_empty() {
+        console.println(&format!("{name_in}, your tasks are printed below"));
+    } else {
+        console.println(&format!("{name_in}, you have no tasks to view"));
+    }
+
+    let rows = tasks_list
+        .iter()
+        .map(|task| TableRow {
+            urgency: Some(urgency(task, coefficients, now)),
+            ..TableRow::from_task(task)
+        })
+        .collect::<Vec<TableRow>>();
+    if !rows.is_empty() {
+        let table = console.table();
+        print_table(console, &rows, &table);
+    }
+}
+
+/// # view_tree
+/// prints out the tasks with status `status` as a table of a tree (see
+/// `Tasks::tree` and `print_table`) where the name of each subtask is
+/// indented below its parent, and each task with
+/// subtasks shows how many of them are done e.g., '[3/5 subtasks done]'. Active
+/// tasks waiting on prerequisites are marked e.g., '[blocked by 7, 9]'.
+/// # Arguments
+/// - **console**: The `Console` the tasks are printed to
+/// - **name_in**: The name of the user
+/// - **tasks**: A ref to Tasks which stores the tasks and their subtasks
+/// - **status**: the status of the tasks that should be printed
+/// # Returns
+/// none
+pub fn view_tree(console: &mut dyn Console, name_in: &str, tasks: &Tasks, status: TaskStatus) {
+    console.println("\nTasks View");
+    let tree = tasks.tree(status);
+    if !tree.is_empty() {
+        console.println(&format!("{name_in}, your tasks are printed below"));
+    } else {
+        console.println(&format!("{name_in}, you have no tasks to view"));
+    }
+
+    let mut rows = Vec::<TableRow>::new();
+    for (depth, task) in tree {
+        let mut progress = match tasks.subtask_progress(task.id) {
+            Some((done, total)) => format!(" [{done}/{total} subtasks done]"),
+            None => String::new(),
+        };
+        let blockers = tasks.blockers(task);
+        if task.status == TaskStatus::Active && !blockers.is_empty() {
+            let blocker_ids = blockers
+                .iter()
+                .map(|blocker_id| blocker_id.to_string())
+                .collect::<Vec<String>>();
+            progress.push_str(&format!(" [blocked by {}]", blocker_ids.join(", ")));
+        }
+        rows.push(TableRow {
+            task,
+            depth,
+            marks: progress.trim_start().to_string(),
+            urgency: None,
+        });
+    }
+    if !rows.is_empty() {
+        let table = console.table();
+        print_table(console, &rows, &table);
     }
 }
@@ -0,0 +1,4 @@
+pub mod app;
+pub mod draw;
+pub mod prelude;
+pub mod run;
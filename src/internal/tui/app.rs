@@ -0,0 +1,652 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::storage::backend::Storage;
+use crate::internal::tasks::events::log_events;
+use crate::internal::tasks::fuzzy::{fuzzy_match, match_notes};
+use crate::internal::tasks::labels::Labels;
+use crate::internal::tasks::save::save_task;
+use crate::internal::tasks::structs::{Task, TaskId, TaskStatus, Tasks};
+use crate::internal::tasks::subtasks::SubtaskPolicy;
+use crate::internal::tasks::undo_stack::{record_undo, UndoStack};
+use chrono::Local;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// the keys of the full-screen interface, shown in the status bar by '?'.
+pub const KEY_HELP: &str = "j/k move, tab pane, enter details, space complete/reopen/restore, \
+                            d delete, a add, e edit, / search, t timer, u undo, U redo, q quit";
+
+/// enum `Pane`
+/// the lists of tasks the full-screen interface switches between.
+///
+/// # Variants
+/// - **Active**: the active tasks
+/// - **Completed**: the completed tasks
+/// - **Deleted**: the deleted tasks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pane {
+    Active,
+    Completed,
+    Deleted,
+}
+
+impl Pane {
+    /// every pane in the order they are shown.
+    pub const ALL: [Pane; 3] = [Pane::Active, Pane::Completed, Pane::Deleted];
+
+    /// # status
+    /// returns the status of the tasks listed in the pane.
+    pub fn status(&self) -> TaskStatus {
+        match self {
+            Pane::Active => TaskStatus::Active,
+            Pane::Completed => TaskStatus::Completed,
+            Pane::Deleted => TaskStatus::Deleted,
+        }
+    }
+
+    /// # index
+    /// returns the position of the pane in `Pane::ALL`.
+    pub fn index(&self) -> usize {
+        match self {
+            Pane::Active => 0,
+            Pane::Completed => 1,
+            Pane::Deleted => 2,
+        }
+    }
+}
+
+/// enum `Prompt`
+/// what the text typed on the input line of the interface is for.
+///
+/// # Variants
+/// - **Add**: the name and labels of a new task
+/// - **Edit**: the new name and label changes of the task with the held id
+/// - **Search**: the text the listed tasks are filtered by
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Prompt {
+    Add,
+    Edit(TaskId),
+    Search,
+}
+
+impl Prompt {
+    /// # show
+    /// converts a `Prompt` to the label shown before the input line.
+    pub fn show(&self) -> String {
+        match self {
+            Prompt::Add => String::from("Add"),
+            Prompt::Edit(task_id) => format!("Edit task {task_id}"),
+            Prompt::Search => String::from("Search"),
+        }
+    }
+}
+
+/// enum `Mode`
+/// whether keys move around the lists or edit text.
+///
+/// # Variants
+/// - **Normal**: keys select and change tasks
+/// - **Input**: keys edit the text of `prompt` held in `buffer`, with the
+///   cursor before the character at `cursor`
+pub enum Mode {
+    Normal,
+    Input {
+        prompt: Prompt,
+        buffer: String,
+        cursor: usize,
+    },
+}
+
+/// # App
+/// the state of the full-screen interface. Every change goes through the
+/// same `Tasks` operations as the interactive menu and is saved, logged and
+/// recorded for undo straight away, just as the menu does.
+///
+/// # Members
+/// - **tasks**: the tasks of the program
+/// - **storage**: the storage backend every change is saved to
+/// - **name**: the name of the user, recorded as the actor of each change
+/// - **pane**: the pane that is shown
+/// - **selected**: the index of the selected row in each pane
+/// - **search**: the text the listed tasks are filtered by, if not empty
+/// - **mode**: whether keys select tasks or edit text
+/// - **details**: whether the details of the selected task are shown
+/// - **status**: the message shown in the status bar
+/// - **quit**: whether the user asked to leave the interface
+pub struct App<'a> {
+    pub tasks: &'a mut Tasks,
+    storage: &'a mut dyn Storage,
+    pub name: String,
+    pub pane: Pane,
+    pub selected: [usize; 3],
+    pub search: String,
+    pub mode: Mode,
+    pub details: bool,
+    pub status: String,
+    pub quit: bool,
+}
+
+impl<'a> App<'a> {
+    /// # new
+    /// creates a new `App` showing the active tasks.
+    pub fn new(tasks: &'a mut Tasks, storage: &'a mut dyn Storage, name_in: &str) -> App<'a> {
+        App {
+            tasks,
+            storage,
+            name: name_in.to_string(),
+            pane: Pane::Active,
+            selected: [0; 3],
+            search: String::new(),
+            mode: Mode::Normal,
+            details: false,
+            status: String::from("Press ? for the keys"),
+            quit: false,
+        }
+    }
+
+    /// # rows
+    /// returns the tasks listed in the current pane as a tree (see
+    /// `Tasks::tree`), each with its depth, keeping only the tasks whose
+    /// names or notes match the search text if there is one.
+    pub fn rows(&self) -> Vec<(usize, &Task)> {
+        self.tasks
+            .tree(self.pane.status())
+            .into_iter()
+            .filter(|(_, task)| {
+                self.search.is_empty()
+                    || fuzzy_match(&self.search, &task.name).is_some()
+                    || task
+                        .notes
+                        .as_deref()
+                        .is_some_and(|notes| match_notes(&self.search, notes).is_some())
+            })
+            .collect()
+    }
+
+    /// # selected_row
+    /// returns the index of the selected row of the current pane, kept
+    /// within its rows.
+    pub fn selected_row(&self) -> usize {
+        let row_count = self.rows().len();
+        self.selected[self.pane.index()].min(row_count.saturating_sub(1))
+    }
+
+    /// # selected_task
+    /// returns the selected task of the current pane, if the pane has any.
+    pub fn selected_task(&self) -> Option<&Task> {
+        self.rows().get(self.selected_row()).map(|(_, task)| *task)
+    }
+
+    /// # select
+    /// moves the selection of the current pane by `offset` rows, stopping at
+    /// the first and the last row.
+    fn select(&mut self, offset: isize) {
+        let row_count = self.rows().len();
+        let selected = self.selected_row().saturating_add_signed(offset);
+        self.selected[self.pane.index()] = selected.min(row_count.saturating_sub(1));
+    }
+
+    /// # select_task
+    /// selects the task with id `task_id` if it is shown in the current pane,
+    /// so a task that was completed, deleted or restored leaves the selection
+    /// on the row that took its place.
+    fn select_task(&mut self, task_id: TaskId) {
+        if let Some(row_idx) = self.rows().iter().position(|(_, task)| task.id == task_id) {
+            self.selected[self.pane.index()] = row_idx;
+        }
+    }
+
+    /// # switch_pane
+    /// shows the pane `offset` panes after the current one, wrapping around.
+    fn switch_pane(&mut self, offset: usize) {
+        let pane_idx = (self.pane.index() + offset) % Pane::ALL.len();
+        self.pane = Pane::ALL[pane_idx];
+    }
+
+    /// # persist
+    /// saves the tasks with ids `task_ids`, logs the change in the event log
+    /// and records it so that it can be undone, or saves every task and
+    /// `undo_stack` after an undo or redo so that the restored tasks keep
+    /// their place, as the interactive menu does after each operation.
+    fn persist(
+        &mut self,
+        description: &str,
+        before: &Tasks,
+        task_ids: &[TaskId],
+        undo_stack: Option<UndoStack>,
+    ) -> TodoResult<()> {
+        if undo_stack.is_some() {
+            self.storage.save_all(self.tasks)?;
+        } else {
+            for task_id in task_ids {
+                save_task(self.storage, self.tasks, *task_id)?;
+            }
+        }
+        log_events(&self.name, description, before, self.tasks, task_ids)?;
+        match undo_stack {
+            Some(undo_stack) => undo_stack.save(),
+            None => record_undo(description, before, self.tasks, task_ids),
+        }
+    }
+
+    /// # change
+    /// runs `operation` on the tasks and persists the tasks it changed (see
+    /// `App::persist`). If it fails, the tasks are put back as they were and
+    /// the error is shown in the status bar.
+    /// # Arguments
+    /// - **description**: what the operation is, recorded in the event log and for undo
+    /// - **operation**: changes the tasks and returns the ids of the changed tasks and
+    ///   the message for the status bar
+    fn change(
+        &mut self,
+        description: &str,
+        operation: impl FnOnce(&mut Tasks) -> TodoResult<(Vec<TaskId>, String)>,
+    ) {
+        let before = self.tasks.clone();
+        match operation(self.tasks) {
+            Ok((task_ids, message)) => {
+                self.status = match self.persist(description, &before, &task_ids, None) {
+                    Ok(()) => message,
+                    Err(e) => format!("WARNING: {e}"),
+                };
+                if let Some(task_id) = task_ids.first() {
+                    self.select_task(*task_id);
+                }
+            }
+            Err(e) => {
+                *self.tasks = before;
+                self.status = e.to_string();
+            }
+        }
+    }
+
+    /// # undo
+    /// undoes the most recent change, or redoes the most recently undone
+    /// change if `redo` is `true` (see `UndoStack::undo`).
+    fn undo(&mut self, redo: bool) {
+        let before = self.tasks.clone();
+        let undone = UndoStack::load().and_then(|mut undo_stack| {
+            let action = if redo {
+                undo_stack.redo(self.tasks)?
+            } else {
+                undo_stack.undo(self.tasks)?
+            };
+            Ok((undo_stack, action))
+        });
+        match undone {
+            Ok((undo_stack, action)) => {
+                let task_ids = action
+                    .changes
+                    .iter()
+                    .map(|change| change.task_id)
+                    .collect::<Vec<TaskId>>();
+                let (description, past_tense) = if redo {
+                    ("Redo last undone change", "redone")
+                } else {
+                    ("Undo last change", "undone")
+                };
+                self.status = match self.persist(description, &before, &task_ids, Some(undo_stack))
+                {
+                    Ok(()) => format!("{} has been {past_tense}", action.show()),
+                    Err(e) => format!("WARNING: {e}"),
+                };
+            }
+            Err(e) => self.status = e.to_string(),
+        }
+    }
+
+    /// # toggle_done
+    /// completes the selected active task, reopens the selected completed
+    /// task or restores the selected deleted task. Completing a task adds
+    /// the next instance of a recurring task (see `Tasks::recur`).
+    fn toggle_done(&mut self) {
+        let Some(task_id) = self.selected_task().map(|task| task.id) else {
+            return;
+        };
+        match self.pane {
+            Pane::Active => self.change("Mark task as complete", |tasks| {
+                tasks.resolve_subtasks(task_id, TaskStatus::Completed, SubtaskPolicy::Block)?;
+                tasks.complete(task_id)?;
+                let mut changed_ids = vec![task_id];
+                let mut message = format!("Task {task_id} has been completed");
+                let unblocked = tasks.unblocked_by(task_id);
+                if !unblocked.is_empty() {
+                    let unblocked_ids = unblocked
+                        .iter()
+                        .map(|id| id.to_string())
+                        .collect::<Vec<String>>();
+                    message.push_str(&format!(". Tasks {} are ready", unblocked_ids.join(", ")));
+                }
+                if let Some(next_task_id) = tasks.recur(task_id)? {
+                    changed_ids.push(next_task_id);
+                    message.push_str(&format!(". Added the next instance as task {next_task_id}"));
+                }
+                Ok((changed_ids, message))
+            }),
+            Pane::Completed => self.change("Reopen completed task", |tasks| {
+                tasks.reopen(task_id)?;
+                Ok((vec![task_id], format!("Task {task_id} has been reopened")))
+            }),
+            Pane::Deleted => self.change("Restore deleted task", |tasks| {
+                tasks.restore(task_id)?;
+                Ok((vec![task_id], format!("Task {task_id} has been restored")))
+            }),
+        }
+    }
+
+    /// # delete
+    /// deletes the selected active task. A task with open subtasks or with
+    /// tasks depending on it is kept; the menu or the command line can
+    /// delete it together with them.
+    fn delete(&mut self) {
+        let Some(task_id) = self.selected_task().map(|task| task.id) else {
+            return;
+        };
+        if self.pane != Pane::Active {
+            self.status = String::from("Only active tasks can be deleted");
+            return;
+        }
+        self.change("Delete task", |tasks| {
+            let dependents = tasks.dependents(task_id);
+            if !dependents.is_empty() {
+                let dependent_ids = dependents
+                    .iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<String>>();
+                return Err(TodoError::invalid(format!(
+                    "Tasks {} depend on task {task_id}. Use 'todolist delete {task_id} --unlink'",
+                    dependent_ids.join(", ")
+                )));
+            }
+            tasks.resolve_subtasks(task_id, TaskStatus::Deleted, SubtaskPolicy::Block)?;
+            tasks.delete(task_id)?;
+            Ok((vec![task_id], format!("Task {task_id} has been deleted")))
+        });
+    }
+
+    /// # toggle_timer
+    /// stops the timer of the selected task if it is running, else starts it
+    /// (see `Tasks::start_timer`).
+    fn toggle_timer(&mut self) {
+        let Some((task_id, timed)) = self.selected_task().map(|task| (task.id, task.is_timed()))
+        else {
+            return;
+        };
+        if timed {
+            self.change("Stop timer", |tasks| {
+                tasks.stop_timer(Local::now())?;
+                Ok((
+                    vec![task_id],
+                    format!("Stopped the timer of task {task_id}"),
+                ))
+            });
+        } else {
+            self.change("Start timer", |tasks| {
+                let mut changed_ids = vec![task_id];
+                changed_ids.extend(tasks.start_timer(task_id, Local::now())?);
+                Ok((changed_ids, format!("Started the timer of task {task_id}")))
+            });
+        }
+    }
+
+    /// # open_prompt
+    /// switches to editing the text of `prompt`, starting with `text`.
+    fn open_prompt(&mut self, prompt: Prompt, text: String) {
+        self.mode = Mode::Input {
+            prompt,
+            cursor: text.chars().count(),
+            buffer: text,
+        };
+    }
+
+    /// # submit
+    /// applies the text entered for `prompt` and switches back to selecting
+    /// tasks.
+    fn submit(&mut self, prompt: Prompt, text: String) {
+        self.mode = Mode::Normal;
+        match prompt {
+            Prompt::Search => {
+                self.search = text.trim().to_string();
+                self.selected[self.pane.index()] = 0;
+            }
+            Prompt::Add => self.change("Add task", |tasks| {
+                let labels = Labels::parse(&text)?;
+                let name = labels
+                    .name
+                    .clone()
+                    .ok_or_else(|| TodoError::invalid("A task needs a name"))?;
+                let new_task_id = tasks.add(name);
+                tasks.relabel(new_task_id, &labels)?;
+                Ok((vec![new_task_id], format!("Added task {new_task_id}")))
+            }),
+            Prompt::Edit(task_id) => self.change("Edit task name", |tasks| {
+                tasks.relabel(task_id, &Labels::parse_changes(&text)?)?;
+                Ok((vec![task_id], format!("Task {task_id} has been updated")))
+            }),
+        }
+    }
+
+    /// # handle_key
+    /// applies a key pressed by the user.
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if let Mode::Input { .. } = self.mode {
+            self.handle_input_key(key);
+            return;
+        }
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c') if ctrl => self.quit = true,
+            KeyCode::Char('r') if ctrl => self.undo(true),
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Esc if !self.search.is_empty() => {
+                self.search.clear();
+                self.status = String::from("Search cleared");
+            }
+            KeyCode::Esc => self.quit = true,
+            KeyCode::Char('j') | KeyCode::Down => self.select(1),
+            KeyCode::Char('k') | KeyCode::Up => self.select(-1),
+            KeyCode::PageDown => self.select(10),
+            KeyCode::PageUp => self.select(-10),
+            KeyCode::Char('g') | KeyCode::Home => self.selected[self.pane.index()] = 0,
+            KeyCode::Char('G') | KeyCode::End => self.select(isize::MAX),
+            KeyCode::Tab | KeyCode::Char('l') | KeyCode::Right => self.switch_pane(1),
+            KeyCode::BackTab | KeyCode::Char('h') | KeyCode::Left => {
+                self.switch_pane(Pane::ALL.len() - 1)
+            }
+            KeyCode::Char(pane_key @ '1'..='3') => {
+                self.pane = Pane::ALL[pane_key as usize - '1' as usize];
+            }
+            KeyCode::Enter => self.details = !self.details,
+            KeyCode::Char(' ') => self.toggle_done(),
+            KeyCode::Char('d') => self.delete(),
+            KeyCode::Char('t') => self.toggle_timer(),
+            KeyCode::Char('u') => self.undo(false),
+            KeyCode::Char('U') => self.undo(true),
+            KeyCode::Char('a') => self.open_prompt(Prompt::Add, String::new()),
+            KeyCode::Char('/') => self.open_prompt(Prompt::Search, self.search.clone()),
+            KeyCode::Char('e') => match self.selected_task() {
+                Some(task) if task.status == TaskStatus::Active => {
+                    let (task_id, name) = (task.id, task.name.clone());
+                    self.open_prompt(Prompt::Edit(task_id), name);
+                }
+                Some(_) => self.status = String::from("Only active tasks can be edited"),
+                None => (),
+            },
+            KeyCode::Char('?') => self.status = KEY_HELP.to_string(),
+            _ => (),
+        }
+    }
+
+    /// # handle_input_key
+    /// applies a key pressed while text is being entered. The search text
+    /// filters the listed tasks while it is typed.
+    fn handle_input_key(&mut self, key: KeyEvent) {
+        let Mode::Input {
+            prompt,
+            buffer,
+            cursor,
+        } = &mut self.mode
+        else {
+            return;
+        };
+        let prompt = *prompt;
+        let byte_idx = |text: &str, char_idx: usize| {
+            text.char_indices()
+                .nth(char_idx)
+                .map_or(text.len(), |(byte_idx, _)| byte_idx)
+        };
+        match key.code {
+            KeyCode::Enter => {
+                let text = std::mem::take(buffer);
+                self.submit(prompt, text);
+                return;
+            }
+            KeyCode::Esc => {
+                self.mode = Mode::Normal;
+                if prompt == Prompt::Search {
+                    self.search.clear();
+                }
+                return;
+            }
+            KeyCode::Char(typed) => {
+                buffer.insert(byte_idx(buffer, *cursor), typed);
+                *cursor += 1;
+            }
+            KeyCode::Backspace if *cursor > 0 => {
+                *cursor -= 1;
+                buffer.remove(byte_idx(buffer, *cursor));
+            }
+            KeyCode::Delete if *cursor < buffer.chars().count() => {
+                buffer.remove(byte_idx(buffer, *cursor));
+            }
+            KeyCode::Left => *cursor = cursor.saturating_sub(1),
+            KeyCode::Right => *cursor = (*cursor + 1).min(buffer.chars().count()),
+            KeyCode::Home => *cursor = 0,
+            KeyCode::End => *cursor = buffer.chars().count(),
+            _ => (),
+        }
+        if prompt == Prompt::Search {
+            self.search = buffer.trim().to_string();
+            self.selected[self.pane.index()] = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::paths::with_test_dir;
+    use crate::internal::storage::memory::MemoryStorage;
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for typed in text.chars() {
+            press(app, KeyCode::Char(typed));
+        }
+    }
+
+    fn names(app: &App) -> Vec<String> {
+        app.rows()
+            .iter()
+            .map(|(_, task)| task.name.clone())
+            .collect()
+    }
+
+    fn with_tasks(names: &[&str]) -> Tasks {
+        let mut tasks = Tasks::new();
+        for name in names {
+            tasks.add(name.to_string());
+        }
+        tasks
+    }
+
+    #[test]
+    fn keys_move_the_selection_within_the_pane() {
+        let mut tasks = with_tasks(&["first", "second", "third"]);
+        let mut storage = MemoryStorage::new();
+        let mut app = App::new(&mut tasks, &mut storage, "bob");
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.selected_task().unwrap().name, "second");
+        press(&mut app, KeyCode::Char('G'));
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.selected_task().unwrap().name, "third");
+        press(&mut app, KeyCode::Char('k'));
+        press(&mut app, KeyCode::Char('k'));
+        press(&mut app, KeyCode::Char('k'));
+        assert_eq!(app.selected_row(), 0);
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(app.pane, Pane::Completed);
+        assert!(app.selected_task().is_none());
+        press(&mut app, KeyCode::BackTab);
+        press(&mut app, KeyCode::BackTab);
+        assert_eq!(app.pane, Pane::Deleted);
+
+        press(&mut app, KeyCode::Char('1'));
+        press(&mut app, KeyCode::Char('/'));
+        type_text(&mut app, "thd");
+        assert_eq!(names(&app), ["third"]);
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(names(&app).len(), 3);
+    }
+
+    #[test]
+    fn adding_completing_and_undoing_are_saved() {
+        with_test_dir(|_| {
+            let mut tasks = Tasks::new();
+            let mut storage = MemoryStorage::new();
+            let mut app = App::new(&mut tasks, &mut storage, "bob");
+            for name in ["first", "second +home"] {
+                press(&mut app, KeyCode::Char('a'));
+                type_text(&mut app, name);
+                press(&mut app, KeyCode::Enter);
+            }
+            assert_eq!(app.status, "Added task 2");
+            assert_eq!(names(&app), ["first", "second"]);
+            assert!(app.tasks.find(2).unwrap().tags.contains("home"));
+
+            // the added task is selected
+            assert_eq!(app.selected_task().unwrap().name, "second");
+            // completing leaves the selection on the row that took its place
+            press(&mut app, KeyCode::Char('g'));
+            press(&mut app, KeyCode::Char(' '));
+            assert_eq!(app.tasks.find(1).unwrap().status, TaskStatus::Completed);
+            assert_eq!(app.selected_task().unwrap().name, "second");
+
+            press(&mut app, KeyCode::Char('u'));
+            assert_eq!(names(&app), ["first", "second"]);
+            press(&mut app, KeyCode::Char('U'));
+            assert_eq!(names(&app), ["second"]);
+            drop(app);
+
+            let stored = storage.load_all().unwrap();
+            assert_eq!(stored.find(1).unwrap().status, TaskStatus::Completed);
+            assert_eq!(stored.find(2).unwrap().status, TaskStatus::Active);
+        });
+    }
+
+    #[test]
+    fn a_failed_change_leaves_the_tasks_as_they_were() {
+        with_test_dir(|_| {
+            let mut tasks = with_tasks(&["first"]);
+            let mut storage = MemoryStorage::new();
+            let mut app = App::new(&mut tasks, &mut storage, "bob");
+            app.change("Add task", |tasks| {
+                tasks.add(String::from("second"));
+                Err(TodoError::invalid("The change failed"))
+            });
+            assert!(app.status.ends_with("The change failed"));
+            assert_eq!(names(&app), ["first"]);
+
+            // a task others depend on is kept
+            app.tasks.add(String::from("second"));
+            app.tasks.change_dependencies(2, &[1], &[]).unwrap();
+            press(&mut app, KeyCode::Char('d'));
+            assert!(app.status.contains("Tasks 2 depend on task 1"));
+            assert_eq!(app.tasks.find(1).unwrap().status, TaskStatus::Active);
+            drop(app);
+
+            assert_eq!(storage.load_all().unwrap().iter().count(), 0);
+        });
+    }
+}
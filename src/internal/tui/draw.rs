@@ -0,0 +1,180 @@
+use crate::internal::tasks::structs::{Priority, Task, DATE_FMT};
+use crate::internal::tasks::table::fit;
+use crate::internal::tui::app::{App, Mode, Pane};
+use chrono::Local;
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::queue;
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{Clear, ClearType};
+use std::io::{self, Write};
+
+/// # show_row
+/// converts a task to one row of a pane, indented by its `depth` in the
+/// tree of tasks, with its id, name and the members useful at a glance.
+fn show_row(task: &Task, depth: usize) -> String {
+    let mut row = format!("{}{:>4}  {}", "  ".repeat(depth), task.id, task.name);
+    if let Some(due) = task.due.get() {
+        let overdue = if due < Local::now() { " (overdue)" } else { "" };
+        row.push_str(&format!("  due {}{overdue}", due.format(DATE_FMT)));
+    }
+    if task.priority != Priority::None {
+        row.push_str(&format!("  {}", task.priority.show()));
+    }
+    if !task.tags.is_empty() {
+        row.push_str(&format!("  {}", task.show_tags()));
+    }
+    if let Some(project) = &task.project {
+        row.push_str(&format!("  project:{project}"));
+    }
+    if !task.work.is_empty() {
+        row.push_str(&format!("  {}", task.show_time_spent(Local::now())));
+    }
+    row
+}
+
+/// # draw_line
+/// writes `text` fitted to `width` on line `y` of the terminal, in reverse
+/// video if `reverse` is `true`.
+fn draw_line(
+    out: &mut impl Write,
+    y: u16,
+    text: &str,
+    width: usize,
+    reverse: bool,
+) -> io::Result<()> {
+    queue!(out, MoveTo(0, y))?;
+    if reverse {
+        queue!(out, SetAttribute(Attribute::Reverse))?;
+    }
+    queue!(out, Print(fit(text, width)), SetAttribute(Attribute::Reset))
+}
+
+/// # draw
+/// draws the whole interface on a terminal of `width` by `height`
+/// characters: the pane tabs on the first line, the rows of the current
+/// pane, the details of the selected task if they are shown, the input or
+/// key line and the status bar on the last line.
+/// # Arguments
+/// - **out**: the terminal the interface is drawn to
+/// - **app**: the state of the interface
+/// - **width**: the number of columns of the terminal
+/// - **height**: the number of lines of the terminal
+/// # Returns
+/// `Ok(())` else the error writing to the terminal failed with.
+pub fn draw(out: &mut impl Write, app: &App, width: u16, height: u16) -> io::Result<()> {
+    let line_width = usize::from(width);
+    queue!(out, Hide, Clear(ClearType::All))?;
+    if height < 4 {
+        return out.flush();
+    }
+
+    // the pane tabs, with the shown pane in reverse video
+    queue!(out, MoveTo(0, 0))?;
+    let mut tab_x = 0;
+    for pane in Pane::ALL {
+        let tab = format!(
+            " {} {:?} ({}) ",
+            pane.index() + 1,
+            pane,
+            app.tasks.list(pane.status()).len()
+        );
+        if pane == app.pane {
+            queue!(out, SetAttribute(Attribute::Reverse))?;
+        }
+        queue!(out, Print(&tab), SetAttribute(Attribute::Reset))?;
+        tab_x += tab.chars().count();
+    }
+    if !app.search.is_empty() && tab_x < line_width {
+        queue!(
+            out,
+            Print(fit(&format!("  /{}", app.search), line_width - tab_x))
+        )?;
+    }
+
+    // the details take up to half of the lines below the tabs
+    let body_height = usize::from(height) - 3;
+    let detail_lines = match (app.details, app.selected_task()) {
+        (true, Some(task)) => task
+            .show_details()
+            .lines()
+            .map(String::from)
+            .collect::<Vec<String>>(),
+        _ => Vec::new(),
+    };
+    let detail_height = if detail_lines.is_empty() {
+        0
+    } else {
+        (detail_lines.len() + 1).min(body_height / 2)
+    };
+    let list_height = body_height - detail_height;
+
+    // the rows of the pane, scrolled so that the selected row is shown
+    let rows = app.rows();
+    let selected = app.selected_row();
+    let first_row = (selected + 1).saturating_sub(list_height);
+    if rows.is_empty() {
+        let empty = if app.search.is_empty() {
+            "No tasks"
+        } else {
+            "No tasks match the search"
+        };
+        draw_line(out, 1, &format!("  {empty}"), line_width, false)?;
+    }
+    for (line_idx, (depth, task)) in rows.iter().skip(first_row).take(list_height).enumerate() {
+        let row_idx = first_row + line_idx;
+        let y = 1 + line_idx as u16;
+        draw_line(
+            out,
+            y,
+            &show_row(task, *depth),
+            line_width,
+            row_idx == selected,
+        )?;
+    }
+
+    // the details of the selected task below a separator
+    if detail_height > 0 {
+        let detail_y = 1 + list_height as u16;
+        draw_line(out, detail_y, &"─".repeat(line_width), line_width, false)?;
+        for (line_idx, line) in detail_lines.iter().take(detail_height - 1).enumerate() {
+            draw_line(out, detail_y + 1 + line_idx as u16, line, line_width, false)?;
+        }
+    }
+
+    // the input line while text is entered, else the main keys
+    let input_y = height - 2;
+    let mut cursor_x = None;
+    match &app.mode {
+        Mode::Input {
+            prompt,
+            buffer,
+            cursor,
+        } => {
+            let label = format!("{}: ", prompt.show());
+            draw_line(out, input_y, &format!("{label}{buffer}"), line_width, false)?;
+            cursor_x = Some((label.chars().count() + cursor).min(line_width.saturating_sub(1)));
+        }
+        Mode::Normal => {
+            let keys =
+                "j/k move  enter details  space done  d delete  a add  e edit  / search  ? keys";
+            draw_line(out, input_y, keys, line_width, false)?;
+        }
+    }
+
+    // the status bar with the user, the running timer and the last message
+    let timer = match app.tasks.timed_task() {
+        Some(task) => format!(
+            " | timing task {} {}",
+            task.id,
+            task.show_time_spent(Local::now())
+        ),
+        None => String::new(),
+    };
+    let status_bar = format!(" {}{timer} | {}", app.name, app.status);
+    draw_line(out, height - 1, &status_bar, line_width, true)?;
+
+    if let Some(cursor_x) = cursor_x {
+        queue!(out, MoveTo(cursor_x as u16, input_y), Show)?;
+    }
+    out.flush()
+}
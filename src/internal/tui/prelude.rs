@@ -0,0 +1,2 @@
+pub use super::app::{App, Mode, Pane, Prompt};
+pub use super::run::run_tui;
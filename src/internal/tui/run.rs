@@ -0,0 +1,66 @@
+use crate::internal::error::{TodoError, TodoResult};
+use crate::internal::storage::backend::Storage;
+use crate::internal::tasks::structs::Tasks;
+use crate::internal::tui::app::App;
+use crate::internal::tui::draw::draw;
+use crossterm::cursor::Show;
+use crossterm::event::{self, Event, KeyEventKind};
+use crossterm::execute;
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use std::io::{self, stdout};
+use std::path::Path;
+
+/// # terminal_error
+/// creates the `TodoError::Io` returned when the terminal cannot be used.
+fn terminal_error(e: io::Error) -> TodoError {
+    TodoError::io(Path::new("terminal"), e)
+}
+
+/// # RawTerminal
+/// puts the terminal in raw mode on the alternate screen while it exists and
+/// puts it back when it is dropped, also if the interface fails or panics.
+struct RawTerminal;
+
+impl RawTerminal {
+    /// # enter
+    /// switches the terminal to raw mode on the alternate screen.
+    fn enter() -> TodoResult<RawTerminal> {
+        terminal::enable_raw_mode().map_err(terminal_error)?;
+        let raw_terminal = RawTerminal;
+        execute!(stdout(), EnterAlternateScreen).map_err(terminal_error)?;
+        Ok(raw_terminal)
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        execute!(stdout(), LeaveAlternateScreen, Show).unwrap_or_default();
+        terminal::disable_raw_mode().unwrap_or_default();
+    }
+}
+
+/// # run_tui
+/// runs the full-screen interface until the user leaves it. Each key press
+/// is applied (see `App::handle_key`) and the interface is drawn again.
+/// # Arguments
+/// - **storage**: the storage backend every change is saved to
+/// - **tasks**: the tasks of the program
+/// - **name_in**: The name of the user
+/// # Returns
+/// `Ok(())` once the user leaves the interface else `TodoError::Io` if the
+/// terminal cannot be used.
+pub fn run_tui(storage: &mut dyn Storage, tasks: &mut Tasks, name_in: &str) -> TodoResult<()> {
+    let _raw_terminal = RawTerminal::enter()?;
+    let mut app = App::new(tasks, storage, name_in);
+    let mut out = stdout();
+    while !app.quit {
+        let (width, height) = terminal::size().map_err(terminal_error)?;
+        draw(&mut out, &app, width, height).map_err(terminal_error)?;
+        match event::read().map_err(terminal_error)? {
+            // only presses count as some terminals also report releases
+            Event::Key(key) if key.kind == KeyEventKind::Press => app.handle_key(key),
+            _ => (),
+        }
+    }
+    Ok(())
+}
@@ -1,8 +1,27 @@
 pub mod internal;
 
-pub use internal::inputs::prelude::{input_num, input_option, input_str};
-pub use internal::name::ret_name_loop;
-pub use internal::paths::{get_file_path, get_name_path, get_path};
+pub use internal::config::{load_config, ColorMode, Column, Config, StorageKind, Table, Urgency};
+pub use internal::error::{TodoError, TodoResult};
+pub use internal::inputs::prelude::{
+    input_num, input_option, input_str, Console, ScriptedConsole, StdConsole,
+};
+pub use internal::name::{ret_name_loop, ret_stored_name};
+pub use internal::paths::{
+    adopt_legacy_path, get_file_path, get_legacy_path, get_name_path, get_path, set_data_dir,
+};
+#[cfg(feature = "sqlite")]
+pub use internal::storage::prelude::SqliteStorage;
+pub use internal::storage::prelude::{open_storage, FileStorage, MemoryStorage, Storage};
 pub use internal::tasks::prelude::{
-    add, check, delete, edit, load, ret_last_task_id, save, view, Tasks,
+    add, check, check_task, delete, delete_task, edit, edit_in_editor, edit_notes, edit_task,
+    from_line, fuzzy_match, highlight, input_notes, input_subtask_policy, input_unlink, load,
+    load_events, load_tasks, log_events, migrate, parse_dependencies, parse_due, parse_since,
+    purge, record_undo, redo, reopen, replay, restore, ret_editor, ret_last_task_id, save,
+    save_task, search, set_dependencies, set_due, set_parent, set_priority, set_recurrence,
+    show_timesheet, start_timer, stop_timer, task_events, to_csv, to_line, to_tsv, undo, urgency,
+    view, view_by_urgency, view_filtered, view_history, view_query, view_task, view_timesheet,
+    view_tree, Event, EventKind, Filter, Frequency, Grouping, Labels, Query, Recurrence, SearchHit,
+    SubtaskPolicy, TaskId, Tasks, TimesheetEntry, UndoStack,
 };
+#[cfg(feature = "tui")]
+pub use internal::tui::prelude::run_tui;
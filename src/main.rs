@@ -1,59 +1,129 @@
+mod cli;
 mod mainloop;
 
-use todolist::get_name_path;
-use todolist::{load, Tasks};
 use mainloop::mainloop;
-use todolist::ret_name_loop;
 use std::io::{stdout, Write};
+use std::path::PathBuf;
+use todolist::ret_name_loop;
+use todolist::{adopt_legacy_path, set_data_dir};
+use todolist::{load, load_config, open_storage, Console, StdConsole, Tasks, TodoError};
+
+/// # take_data_dir_flag
+/// removes the `--data-dir` flag from `args` and returns its value if it
+/// was passed to the program either as `--data-dir <path>` or
+/// `--data-dir=<path>`.
+/// # Arguments
+/// - **args**: the arguments passed to the program without the program name
+/// # Returns
+/// `Some(path)` if the flag was passed else `None`.
+fn take_data_dir_flag(args: &mut Vec<String>) -> Option<PathBuf> {
+    let flag_idx = args
+        .iter()
+        .position(|arg| arg == "--data-dir" || arg.starts_with("--data-dir="))?;
+    let flag = args.remove(flag_idx);
+    if let Some(path) = flag.strip_prefix("--data-dir=") {
+        return Some(PathBuf::from(path));
+    }
+    if flag_idx < args.len() {
+        return Some(PathBuf::from(args.remove(flag_idx)));
+    }
+    None
+}
 
 /// # main
 /// The main entry point to the program. This function
-/// tries to read in the user's name if it exists or
-/// prompt the user to enter a new name before rerunning
-/// the `mainloop` function until the user explicitly
-/// asks the program to stop.
+/// runs a single command (see `cli::run`) and exits with
+/// its exit code if one was given on the command line,
+/// or runs the full-screen interface (see `run_tui`) for
+/// the `tui` command. Otherwise, it tries to read in the user's name if it
+/// exists or prompt the user to enter a new name before
+/// rerunning the `mainloop` function until the user
+/// explicitly asks the program to stop.
 /// # Arguments
 /// none
 /// # Returns
 /// none
 fn main() {
+    // resolve the data dir before anything is read from it
+    let mut args = std::env::args().skip(1).collect::<Vec<String>>();
+    if let Some(data_dir) = take_data_dir_flag(&mut args) {
+        set_data_dir(&data_dir);
+    }
+    adopt_legacy_path();
+
+    // run a non-interactive command if one was given
+    let tui_mode = cfg!(feature = "tui") && args == ["tui"];
+    if !args.is_empty() && !tui_mode {
+        std::process::exit(cli::run(&args));
+    }
+
+    // open the storage backend selected in the configuration
+    let config = load_config();
+    let mut storage = match open_storage(config.storage) {
+        Ok(storage) => storage,
+        Err(e) => {
+            println!(
+                "\nCould not open the {:?} storage backend. {e}",
+                config.storage
+            );
+            println!("Exiting..");
+            std::process::exit(e.exit_code());
+        }
+    };
     // instantiate `Tasks` instance
     let mut tasks = Tasks::new();
     // loads previous tasks if there are any stored by previous runs.
-    if get_name_path().exists() {
-        load(&mut tasks);
+    // Stop if they cannot be loaded so that they are not overwritten.
+    if let Err(e) = load(storage.as_mut(), &mut tasks) {
+        println!("\nCould not load your tasks. {e}");
+        println!("Exiting..");
+        std::process::exit(e.exit_code());
     }
     // attempt to retrieve the user's name
+    let mut console = StdConsole::with_table(config.table);
     let trials = 5;
-    let name_value = ret_name_loop(trials);
+    let name_value = ret_name_loop(&mut console, trials);
     let name = match name_value {
-        Some(text) => text,
-        None => {
+        Ok(text) => text,
+        Err(e) => {
             println!(
                 "\nCould not retrieve user's name successfully after {} trials. Exiting..",
                 trials
             );
-            return ();
+            std::process::exit(e.exit_code());
         }
     };
     let name = name.as_str();
+
+    // run the full-screen interface instead of the menu if it was asked for
+    #[cfg(feature = "tui")]
+    if tui_mode {
+        if let Err(e) = todolist::run_tui(storage.as_mut(), &mut tasks, name) {
+            println!("\nCould not run the full-screen interface. {e}");
+            std::process::exit(e.exit_code());
+        }
+        return;
+    }
     stdout().flush().unwrap_or_default();
-    println!("\nHello {name}. Your Todo List Manager here 👋");
-    println!("How may I help you today?");
+    console.println(&format!("\nHello {name}. Your Todo List Manager here 👋"));
+    console.println("How may I help you today?");
 
     // rerun the `mainloop` function through this loop
     loop {
         // get the result of the mainloop function
-        let rerun_option = mainloop(name, &mut tasks);
-        match rerun_option {
-            // handle boolean cases by breaking the loop if the
-            // returned boolean (understood as `rerun`) is false
-            Some(rerun) => {
-                if !rerun {
+        match mainloop(&mut console, name, &mut tasks, storage.as_mut()) {
+            // break the loop if the returned boolean (understood
+            // as `rerun`) is false
+            Ok(false) => break,
+            Ok(true) => (),
+            // the next menu selection exits once there is no more input
+            Err(TodoError::InputClosed) => (),
+            Err(e) => {
+                console.println(&format!("\n{e}"));
+                if console.is_closed() {
                     break;
                 }
             }
-            None => (),
         }
     }
 }
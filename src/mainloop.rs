@@ -1,74 +1,242 @@
-use todolist::{add, check, delete, edit, input_option, ret_last_task_id, save, view, Tasks};
+use chrono::Local;
+use todolist::internal::tasks::structs::TaskStatus;
+use todolist::{
+    add, check, delete, edit, edit_notes, save, save_task, search, set_dependencies, set_due,
+};
+use todolist::{
+    load_config, view, view_by_urgency, view_filtered, view_history, view_query, view_task,
+    view_timesheet, view_tree,
+};
+use todolist::{log_events, purge, record_undo, redo, reopen, restore};
+use todolist::{set_parent, set_priority, set_recurrence, start_timer, stop_timer, undo};
+use todolist::{Console, Storage, TaskId, Tasks};
+use todolist::{TodoError, TodoResult};
 
 /// # mainloop
 /// runs the main block of all the program's operations once.
 /// # Arguments
+/// - **console**: The `Console` used to prompt the user and print results
 /// - **name_in**: The name of the user
 /// - **tasks**: A mutable struct that models all the
-/// different tasks in the program stored in its members
-/// as vectors of tasks
+///   different tasks in the program stored in its members
+///   as vectors of tasks
+/// - **storage**: the storage backend every change is saved to
 /// # Returns
-/// `Some(bool)` where bool is a `bool` value that indicates if
-/// the `mainloop` function should be rerun. May return `None`
-/// if any part of the function fails. In the case `None` is
-/// returned, the `mainloop` function is automatically called
-/// again.
-pub fn mainloop(name_in: &str, tasks: &mut Tasks) -> Option<bool> {
+/// `Ok(bool)` where bool is a `bool` value that indicates if
+/// the `mainloop` function should be rerun. May return a
+/// `TodoError` if any part of the function fails. In that case
+/// the tasks are left as they were before the operation, the
+/// error is printed and the `mainloop` function is called
+/// again unless there is no more input.
+pub fn mainloop(
+    console: &mut dyn Console,
+    name_in: &str,
+    tasks: &mut Tasks,
+    storage: &mut dyn Storage,
+) -> TodoResult<bool> {
     // initialize main menu
-    let options = vec![
+    let options = [
         "Add task",
         "View active tasks",
+        "View active tasks by urgency",
+        "View tasks ready to work on",
         "View completed tasks",
         "View deleted tasks",
+        "View tasks by tag or project",
+        "Query tasks",
+        "Search tasks",
+        "View tasks due today",
+        "View overdue tasks",
+        "View tasks due this week",
+        "View task history",
+        "View task details",
+        "View timesheet",
         "Edit task name",
+        "Edit task notes",
+        "Set task due date",
+        "Set task priority",
+        "Set task parent",
+        "Set task dependencies",
+        "Set task repetition",
+        "Start timer",
+        "Stop timer",
         "Mark task as complete",
         "Delete task",
+        "Restore deleted task",
+        "Reopen completed task",
+        "Purge deleted tasks",
+        "Undo last change",
+        "Redo last undone change",
         "Exit program",
     ];
 
     // get user's menu selection
-    let choice = match input_option("Select an option: ", &options, "Menu") {
-        Some(choice) => choice,
-        None => return Some(true),
+    let choice = match console.input_option("Select an option: ", &options, "Menu") {
+        Ok(choice) => choice,
+        // stop once there is no more input e.g., stdin was closed
+        Err(TodoError::InputClosed) => "Exit program",
+        Err(e) => return Err(e),
     };
 
-    // get the task id of the most recent task in the program
-    let last_task_id = ret_last_task_id(tasks);
-
-    // create new task id
-    let new_task_id = last_task_id + 1;
-
-    // extract all types of tasks in the program
-    let active_tasks = &mut tasks.active;
-    let completed_tasks = &mut tasks.completed;
-    let deleted_tasks = &mut tasks.deleted;
-
     // get the index of the user's selected menu operation
     let choice_idx = options
         .iter()
-        .position(|&each_option| each_option == choice)?;
+        .position(|&each_option| each_option == choice)
+        .ok_or_else(|| TodoError::invalid(format!("Unknown option '{choice}'")))?;
 
     // convert the previous index into a count from 1
     let choice_num = choice_idx + 1;
 
-    // match the user's selected index and perform the valid
-    // operation for each index else return None for invalid
-    // values.
-    match choice_num as i32 {
-        1 => add(name_in, active_tasks, new_task_id)?,
-        2 => view(name_in, active_tasks),
-        3 => view(name_in, completed_tasks),
-        4 => view(name_in, deleted_tasks),
-        5 => edit(name_in, active_tasks)?,
-        6 => check(name_in, tasks)?,
-        7 => delete(name_in, tasks)?,
-        8 => {
-            save(name_in, tasks)?;
-            return Some(false);
+    // save the tasks and stop the program when asked to exit
+    if choice == "Exit program" {
+        save(storage, tasks)?;
+        console.println(&format!(
+            "So sad 😔 to see you go {name_in}. Visit again soon."
+        ));
+        return Ok(false);
+    }
+
+    // keep a copy of the tasks so that the operation can be undone, and so
+    // that a failed operation leaves the tasks as they were
+    let before = tasks.clone();
+    let changed_task_ids = match run_option(console, name_in, tasks, choice_num) {
+        Ok(task_ids) => task_ids,
+        Err(e) => {
+            *tasks = before;
+            return Err(e);
         }
-        _ => return None,
     };
-    println!();
+
+    // save the changed tasks straight away so that the changes
+    // survive the program being stopped before it exits. Undo and redo
+    // save every task so that the restored tasks keep their place.
+    let is_undo = matches!(choice, "Undo last change" | "Redo last undone change");
+    let saved = if is_undo {
+        save(storage, tasks)
+    } else {
+        changed_task_ids
+            .iter()
+            .try_for_each(|task_id| save_task(storage, tasks, *task_id))
+    };
+    if let Err(e) = saved {
+        console.println(&format!("WARNING: {e}"));
+        console.println("Your changes could not be saved. They will be saved again on exit");
+    }
+    // log the change in the audit trail, including undos and redos
+    if let Err(e) = log_events(name_in, choice, &before, tasks, &changed_task_ids) {
+        console.println(&format!("WARNING: {e}"));
+        console.println("This change could not be added to the history of its tasks");
+    }
+    // record the change so that it can be undone. Undo and redo record themselves.
+    if !is_undo {
+        if let Err(e) = record_undo(choice, &before, tasks, &changed_task_ids) {
+            console.println(&format!("WARNING: {e}"));
+            console.println("This change cannot be undone");
+        }
+    }
+    console.println("");
     // return rerun as true so that this function is called again.
-    Some(true)
+    Ok(true)
+}
+
+/// # run_option
+/// performs the menu operation numbered `choice_num`, counting from 1, on
+/// `tasks`. The tasks may be left partly changed if the operation fails.
+/// # Arguments
+/// - **console**: The `Console` used to prompt the user and print results
+/// - **name_in**: The name of the user
+/// - **tasks**: A mutable ref to Tasks the operation is performed on
+/// - **choice_num**: the number of the selected menu option
+/// # Returns
+/// `Ok(task_ids)` where task_ids are the ids of the tasks the operation
+/// changed if the function completes successfully else a `TodoError`.
+fn run_option(
+    console: &mut dyn Console,
+    name_in: &str,
+    tasks: &mut Tasks,
+    choice_num: usize,
+) -> TodoResult<Vec<TaskId>> {
+    // match the user's selected index and perform the valid
+    // operation for each index else return an error for
+    // invalid values. Operations that change tasks return their ids.
+    Ok(match choice_num as i32 {
+        1 => vec![add(console, name_in, tasks)?],
+        2 => {
+            view_tree(console, name_in, tasks, TaskStatus::Active);
+            Vec::new()
+        }
+        3 => {
+            let coefficients = load_config().urgency;
+            let now = Local::now();
+            let ranked = tasks.by_urgency(TaskStatus::Active, &coefficients, now);
+            view_by_urgency(console, name_in, &ranked, &coefficients, now);
+            Vec::new()
+        }
+        4 => {
+            view(console, name_in, &tasks.ready());
+            Vec::new()
+        }
+        5 => {
+            view_tree(console, name_in, tasks, TaskStatus::Completed);
+            Vec::new()
+        }
+        6 => {
+            view_tree(console, name_in, tasks, TaskStatus::Deleted);
+            Vec::new()
+        }
+        7 => {
+            view_filtered(console, name_in, tasks)?;
+            Vec::new()
+        }
+        8 => {
+            view_query(console, name_in, tasks)?;
+            Vec::new()
+        }
+        9 => search(console, name_in, tasks)?,
+        10 => {
+            view(console, name_in, &tasks.due_today(Local::now()));
+            Vec::new()
+        }
+        11 => {
+            view(console, name_in, &tasks.overdue(Local::now()));
+            Vec::new()
+        }
+        12 => {
+            view(console, name_in, &tasks.upcoming(Local::now()));
+            Vec::new()
+        }
+        13 => {
+            view_history(console, name_in)?;
+            Vec::new()
+        }
+        14 => {
+            view_task(console, name_in, tasks)?;
+            Vec::new()
+        }
+        15 => {
+            view_timesheet(console, name_in, tasks)?;
+            Vec::new()
+        }
+        16 => vec![edit(console, name_in, tasks)?],
+        17 => vec![edit_notes(console, name_in, tasks)?],
+        18 => vec![set_due(console, name_in, tasks)?],
+        19 => vec![set_priority(console, name_in, tasks)?],
+        20 => vec![set_parent(console, name_in, tasks)?],
+        21 => vec![set_dependencies(console, name_in, tasks)?],
+        22 => vec![set_recurrence(console, name_in, tasks)?],
+        23 => start_timer(console, name_in, tasks)?,
+        24 => vec![stop_timer(console, tasks)?],
+        25 => check(console, name_in, tasks)?,
+        26 => delete(console, name_in, tasks)?,
+        27 => vec![restore(console, name_in, tasks)?],
+        28 => vec![reopen(console, name_in, tasks)?],
+        29 => purge(console, name_in, tasks)?,
+        30 => undo(console, name_in, tasks)?,
+        31 => redo(console, name_in, tasks)?,
+        _ => {
+            return Err(TodoError::invalid(format!(
+                "Unknown option number {choice_num}"
+            )))
+        }
+    })
 }
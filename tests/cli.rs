@@ -0,0 +1,72 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// a new empty data dir for the test named `test_name`.
+fn data_dir(test_name: &str) -> PathBuf {
+    let data_dir =
+        std::env::temp_dir().join(format!("todolist-cli-{test_name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&data_dir);
+    fs::create_dir_all(&data_dir).unwrap();
+    data_dir
+}
+
+/// runs the program with `args` on the data in `data_dir`.
+fn todolist(data_dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_todolist"))
+        .args(args)
+        .env("TODOLIST_HOME", data_dir)
+        .output()
+        .unwrap()
+}
+
+/// the id and status of every task, as listed by `list --status all`.
+fn listed(data_dir: &Path) -> Vec<(String, String)> {
+    let output = todolist(data_dir, &["list", "--status", "all"]);
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| {
+            let fields = line.split('\t').collect::<Vec<&str>>();
+            (fields[0].to_string(), fields[1].to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn done_with_detach_keeps_the_subtasks_open() {
+    let data_dir = data_dir("detach");
+    todolist(&data_dir, &["add", "parent"]);
+    todolist(
+        &data_dir,
+        &["add", "child", "--parent", "1", "--repeat", "daily"],
+    );
+    let output = todolist(&data_dir, &["done", "1", "--detach"]);
+
+    assert!(output.status.success(), "{output:?}");
+    // the detached subtask is not repeated as it was not completed
+    let mut tasks = listed(&data_dir);
+    tasks.sort();
+    assert_eq!(
+        tasks,
+        [
+            (String::from("1"), String::from("Completed")),
+            (String::from("2"), String::from("Active"))
+        ]
+    );
+    fs::remove_dir_all(&data_dir).unwrap();
+}
+
+#[test]
+fn unknown_commands_print_the_usage_and_change_nothing() {
+    let data_dir = data_dir("unknown");
+    todolist(&data_dir, &["add", "water plants"]);
+    let output = todolist(&data_dir, &["water", "1"]);
+
+    assert_eq!(output.status.code(), Some(2), "{output:?}");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("unknown command 'water'"), "{stderr}");
+    assert!(stderr.contains("Usage"), "{stderr}");
+    assert_eq!(listed(&data_dir), [(String::from("1"), String::from("Active"))]);
+    fs::remove_dir_all(&data_dir).unwrap();
+}
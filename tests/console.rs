@@ -0,0 +1,137 @@
+use todolist::internal::tasks::structs::{Priority, TaskStatus};
+use todolist::{add, check, delete, Console, ScriptedConsole, Tasks, TodoError};
+
+/// adds a task named `name` through the interactive `add` with no due date
+/// and no priority.
+fn add_task(tasks: &mut Tasks, name: &str) -> i32 {
+    let mut console = ScriptedConsole::new(&[name, "", ""]);
+    add(&mut console, "bob", tasks).unwrap()
+}
+
+#[test]
+fn add_reads_the_name_due_date_and_priority() {
+    let mut tasks = Tasks::new();
+    let mut console = ScriptedConsole::new(&["water plants +home", "2030-01-01", "high"]);
+    let task_id = add(&mut console, "bob", &mut tasks).unwrap();
+
+    let task = tasks.find(task_id).unwrap();
+    assert_eq!(task.name, "water plants");
+    assert!(task.tags.contains("home"));
+    assert!(task.due.get().is_some());
+    assert_eq!(task.priority, Priority::High);
+    assert!(console.inputs.is_empty());
+    assert!(console
+        .output
+        .iter()
+        .any(|line| line.contains("Added Task with details")));
+}
+
+#[test]
+fn add_rejects_bad_input_without_adding_a_task() {
+    let mut tasks = Tasks::new();
+    let mut console = ScriptedConsole::new(&["water plants", "not a date"]);
+    assert!(matches!(
+        add(&mut console, "bob", &mut tasks),
+        Err(TodoError::InvalidInput(_))
+    ));
+    assert_eq!(tasks.iter().count(), 0);
+}
+
+#[test]
+fn check_completes_the_entered_task() {
+    let mut tasks = Tasks::new();
+    add_task(&mut tasks, "first");
+    let second_id = add_task(&mut tasks, "second");
+    let mut console = ScriptedConsole::new(&[&second_id.to_string()]);
+    let changed = check(&mut console, "bob", &mut tasks).unwrap();
+
+    assert_eq!(changed, vec![second_id]);
+    let task = tasks.find(second_id).unwrap();
+    assert_eq!(task.status, TaskStatus::Completed);
+    assert!(task.time_finished.get().is_some());
+    assert_eq!(tasks.active.len(), 1);
+}
+
+#[test]
+fn check_asks_about_open_subtasks() {
+    let mut tasks = Tasks::new();
+    let parent_id = add_task(&mut tasks, "parent");
+    let child_id = add_task(&mut tasks, "child");
+    tasks.set_parent(child_id, Some(parent_id)).unwrap();
+    // option 1 completes the subtasks too
+    let mut console = ScriptedConsole::new(&[&parent_id.to_string(), "1"]);
+    check(&mut console, "bob", &mut tasks).unwrap();
+
+    assert_eq!(tasks.find(child_id).unwrap().status, TaskStatus::Completed);
+    assert_eq!(tasks.find(parent_id).unwrap().status, TaskStatus::Completed);
+}
+
+#[test]
+fn check_of_an_unknown_id_changes_nothing() {
+    let mut tasks = Tasks::new();
+    add_task(&mut tasks, "first");
+    let mut console = ScriptedConsole::new(&["42"]);
+    assert!(matches!(
+        check(&mut console, "bob", &mut tasks),
+        Err(TodoError::NotFound(42))
+    ));
+    assert_eq!(tasks.active.len(), 1);
+}
+
+#[test]
+fn delete_unlinks_the_dependents_when_asked() {
+    let mut tasks = Tasks::new();
+    let first_id = add_task(&mut tasks, "first");
+    let second_id = add_task(&mut tasks, "second");
+    tasks
+        .change_dependencies(second_id, &[first_id], &[])
+        .unwrap();
+    // option 1 removes the task from the dependencies of its dependents
+    let mut console = ScriptedConsole::new(&[&first_id.to_string(), "1"]);
+    let changed = delete(&mut console, "bob", &mut tasks).unwrap();
+
+    assert_eq!(changed, vec![first_id, second_id]);
+    assert_eq!(tasks.find(first_id).unwrap().status, TaskStatus::Deleted);
+    assert!(tasks.find(second_id).unwrap().depends_on.is_empty());
+}
+
+#[test]
+fn delete_keeps_a_task_others_depend_on() {
+    let mut tasks = Tasks::new();
+    let first_id = add_task(&mut tasks, "first");
+    let second_id = add_task(&mut tasks, "second");
+    tasks
+        .change_dependencies(second_id, &[first_id], &[])
+        .unwrap();
+    // option 2 keeps the task
+    let mut console = ScriptedConsole::new(&[&first_id.to_string(), "2"]);
+    assert!(delete(&mut console, "bob", &mut tasks).is_err());
+    assert_eq!(tasks.find(first_id).unwrap().status, TaskStatus::Active);
+}
+
+#[test]
+fn running_out_of_input_closes_the_console() {
+    let mut tasks = Tasks::new();
+    let mut console = ScriptedConsole::new(&["water plants"]);
+    assert!(matches!(
+        add(&mut console, "bob", &mut tasks),
+        Err(TodoError::InputClosed)
+    ));
+    assert!(console.is_closed());
+}
+
+#[test]
+fn check_detaches_the_open_subtasks_when_asked() {
+    let mut tasks = Tasks::new();
+    let parent_id = add_task(&mut tasks, "parent");
+    let child_id = add_task(&mut tasks, "child");
+    tasks.set_parent(child_id, Some(parent_id)).unwrap();
+    // option 3 detaches the subtasks
+    let mut console = ScriptedConsole::new(&[&parent_id.to_string(), "3"]);
+    check(&mut console, "bob", &mut tasks).unwrap();
+
+    let child = tasks.find(child_id).unwrap();
+    assert_eq!(child.status, TaskStatus::Active);
+    assert_eq!(child.parent, None);
+    assert_eq!(tasks.find(parent_id).unwrap().status, TaskStatus::Completed);
+}
@@ -0,0 +1,45 @@
+use std::env;
+use todolist::{get_file_path, get_path, set_data_dir};
+
+/// The environment and the `--data-dir` override are shared by the whole
+/// process and the override cannot be unset, so the order in which the data
+/// dir is resolved is checked in a single test, from the last option to the
+/// first.
+#[test]
+fn the_data_dir_flag_beats_the_environment_which_beats_the_platform_dir() {
+    let test_dir = env::temp_dir().join(format!("todolist-paths-{}", std::process::id()));
+    env::remove_var("TODOLIST_HOME");
+
+    // the per-user data dir of the platform
+    #[cfg(target_os = "linux")]
+    {
+        env::set_var("XDG_DATA_HOME", test_dir.join("xdg"));
+        assert_eq!(get_path(), test_dir.join("xdg").join("todolist"));
+    }
+    let platform_dir = dirs::data_dir().map(|data_dir| data_dir.join("todolist"));
+    if let Some(platform_dir) = &platform_dir {
+        assert_eq!(&get_path(), platform_dir);
+    }
+
+    // the `TODOLIST_HOME` environment variable, relative to the work dir,
+    // unless it is empty
+    env::set_var("TODOLIST_HOME", test_dir.join("home"));
+    assert_eq!(get_path(), test_dir.join("home"));
+    assert_eq!(
+        get_file_path("tasks.jsonl"),
+        test_dir.join("home").join("tasks.jsonl")
+    );
+    env::set_var("TODOLIST_HOME", "home");
+    assert_eq!(get_path(), env::current_dir().unwrap().join("home"));
+    env::set_var("TODOLIST_HOME", "");
+    if let Some(platform_dir) = &platform_dir {
+        assert_eq!(&get_path(), platform_dir);
+    }
+
+    // the `--data-dir` flag, which only the first call sets
+    env::set_var("TODOLIST_HOME", test_dir.join("home"));
+    assert!(set_data_dir(&test_dir.join("flag")));
+    assert_eq!(get_path(), test_dir.join("flag"));
+    assert!(!set_data_dir(&test_dir.join("other")));
+    assert_eq!(get_path(), test_dir.join("flag"));
+}
@@ -0,0 +1,111 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::{America::New_York, Asia::Tokyo, Australia::Sydney, Europe::London};
+use todolist::internal::tasks::dates::from_wall_clock;
+use todolist::internal::tasks::structs::{DateTimeOption, Task};
+use todolist::{from_line, to_line};
+
+/// a line in the legacy text format with the given created, modified and
+/// finished dates.
+fn legacy_line(created: &str, modified: &str, finished: &str) -> String {
+    format!(
+        "{{ Task ID: 1, Name: water plants, Status: Completed, Created: {created}, \
+         Last Modified: {modified}, Finished: {finished} }}"
+    )
+}
+
+fn wall_clock(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(year, month, day)
+        .and_then(|date| date.and_hms_opt(hour, minute, 0))
+        .unwrap()
+}
+
+fn utc(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&wall_clock(year, month, day, hour, minute))
+}
+
+#[test]
+fn legacy_dates_use_the_offset_of_their_own_date() {
+    // written in summer (BST, +01:00) and in winter (GMT, +00:00)
+    let line = legacy_line(
+        "2024/07/01 10:00:00",
+        "2024/01/15 10:00:00",
+        "2024/07/02 18:30:00",
+    );
+    let task = Task::from_legacy_in(&line, &London).unwrap();
+    assert_eq!(task.time_created, utc(2024, 7, 1, 9, 0));
+    assert_eq!(task.last_time_modified, utc(2024, 1, 15, 10, 0));
+    assert_eq!(task.time_finished.get().unwrap(), utc(2024, 7, 2, 17, 30));
+}
+
+#[test]
+fn legacy_dates_are_read_in_the_zone_they_were_written_in() {
+    let line = legacy_line(
+        "2024/07/01 10:00:00",
+        "2024/07/01 10:00:00",
+        "Not completed",
+    );
+    let new_york = Task::from_legacy_in(&line, &New_York).unwrap();
+    let tokyo = Task::from_legacy_in(&line, &Tokyo).unwrap();
+    assert_eq!(new_york.time_created, utc(2024, 7, 1, 14, 0));
+    assert_eq!(tokyo.time_created, utc(2024, 7, 1, 1, 0));
+    assert!(matches!(tokyo.time_finished, DateTimeOption::None));
+}
+
+#[test]
+fn times_skipped_by_a_dst_change_move_forward_an_hour() {
+    // London clocks jump from 01:00 to 02:00 on 2024-03-31
+    let skipped = from_wall_clock(wall_clock(2024, 3, 31, 1, 30), &London).unwrap();
+    assert_eq!(skipped, utc(2024, 3, 31, 1, 30));
+    // Sydney clocks jump from 02:00 to 03:00 on 2024-10-06
+    let skipped = from_wall_clock(wall_clock(2024, 10, 6, 2, 30), &Sydney).unwrap();
+    assert_eq!(skipped, utc(2024, 10, 5, 16, 30));
+}
+
+#[test]
+fn times_repeated_by_a_dst_change_take_the_first_occurrence() {
+    // London clocks go back from 02:00 BST to 01:00 GMT on 2024-10-27
+    let repeated = from_wall_clock(wall_clock(2024, 10, 27, 1, 30), &London).unwrap();
+    assert_eq!(repeated, utc(2024, 10, 27, 0, 30));
+    let line = legacy_line(
+        "2024/10/27 01:30:00",
+        "2024/10/27 01:30:00",
+        "Not completed",
+    );
+    let task = Task::from_legacy_in(&line, &London).unwrap();
+    assert_eq!(task.time_created, utc(2024, 10, 27, 0, 30));
+}
+
+#[test]
+fn records_are_written_in_utc() {
+    let line = legacy_line(
+        "2024/07/01 10:00:00",
+        "2024/07/01 10:00:00",
+        "2024/07/01 12:00:00",
+    );
+    let task = Task::from_legacy_in(&line, &London).unwrap();
+    let record = to_line(&task).unwrap();
+    assert!(
+        record.contains("\"time_created\":\"2024-07-01T09:00:00Z\""),
+        "{record}"
+    );
+    assert!(
+        record.contains("\"time_finished\":\"2024-07-01T11:00:00Z\""),
+        "{record}"
+    );
+}
+
+#[test]
+fn records_written_in_another_zone_keep_their_instant() {
+    // written by a machine in Tokyo (+09:00) with a due date in New York time
+    let record = r#"{"time_created":"2024-07-01T18:00:00+09:00","last_time_modified":"2024-12-01T09:00:00+09:00","time_finished":null,"due":"2024-11-03T01:30:00-04:00","name":"ship it","id":7,"status":"Active"}"#;
+    let task = from_line(record).unwrap();
+    assert_eq!(task.time_created, utc(2024, 7, 1, 9, 0));
+    assert_eq!(task.last_time_modified, utc(2024, 12, 1, 0, 0));
+    assert_eq!(task.due.get().unwrap(), utc(2024, 11, 3, 5, 30));
+
+    // reading back what was written gives the same instants
+    let reloaded = from_line(&to_line(&task).unwrap()).unwrap();
+    assert_eq!(reloaded.time_created, task.time_created);
+    assert_eq!(reloaded.last_time_modified, task.last_time_modified);
+    assert_eq!(reloaded.due.get(), task.due.get());
+}